@@ -0,0 +1,151 @@
+//! Local Analytics
+//! Opt-in (`general.local_analytics`) per-day usage counters. Nothing is sent anywhere;
+//! the counts live in data/analytics.db (a JSON document) and back the `--stats` report.
+
+use crate::config::get_exe_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Days shown by `--stats`
+const REPORT_DAYS: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    OcrCapture,
+    Query,
+    /// A provider attempt failed inside the auto fallback chain
+    ProviderFallback,
+    /// Error category, e.g. "ocr", "llm", "browser"
+    Error(&'static str),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct DayCounts {
+    #[serde(default)]
+    ocr_captures: u64,
+    #[serde(default)]
+    queries: u64,
+    #[serde(default)]
+    provider_fallbacks: u64,
+    #[serde(default)]
+    errors: BTreeMap<String, u64>,
+}
+
+impl DayCounts {
+    fn add(&mut self, event: Event) {
+        match event {
+            Event::OcrCapture => self.ocr_captures += 1,
+            Event::Query => self.queries += 1,
+            Event::ProviderFallback => self.provider_fallbacks += 1,
+            Event::Error(kind) => *self.errors.entry(kind.to_string()).or_insert(0) += 1,
+        }
+    }
+
+    fn error_total(&self) -> u64 {
+        self.errors.values().sum()
+    }
+}
+
+/// Date (YYYY-MM-DD) -> counters
+type Store = BTreeMap<String, DayCounts>;
+
+/// None until `init` enables analytics
+static STORE: Mutex<Option<Store>> = Mutex::new(None);
+
+fn db_path() -> PathBuf {
+    get_exe_dir().join("data").join("analytics.db")
+}
+
+fn load() -> Store {
+    std::fs::read_to_string(db_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Enable recording for this session; a no-op when the user has not opted in
+pub fn init(enabled: bool) {
+    if enabled {
+        *STORE.lock().unwrap() = Some(load());
+    }
+}
+
+pub fn record(event: Event) {
+    let mut guard = STORE.lock().unwrap();
+    let Some(store) = guard.as_mut() else { return };
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    store.entry(today).or_default().add(event);
+
+    // Events are rare (one per hotkey press), so writing through is cheap and survives a panic-exit
+    let result = serde_json::to_string(store)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| std::fs::write(db_path(), json).map_err(anyhow::Error::from));
+    if let Err(e) = result {
+        error!("Failed to write analytics: {}", e);
+    }
+}
+
+/// Print the last `REPORT_DAYS` days of counters (`--stats`)
+pub fn print_report() {
+    let store = load();
+    if store.is_empty() {
+        println!("[*] No analytics recorded yet. Set local_analytics = true under [general] to start.");
+        return;
+    }
+
+    println!("{:<12} {:>6} {:>8} {:>10} {:>7}", "Date", "OCR", "Queries", "Fallbacks", "Errors");
+    let mut totals = DayCounts::default();
+    for (date, day) in store.iter().rev().take(REPORT_DAYS).collect::<Vec<_>>().into_iter().rev() {
+        println!(
+            "{:<12} {:>6} {:>8} {:>10} {:>7}",
+            date, day.ocr_captures, day.queries, day.provider_fallbacks, day.error_total()
+        );
+        totals.ocr_captures += day.ocr_captures;
+        totals.queries += day.queries;
+        totals.provider_fallbacks += day.provider_fallbacks;
+        for (kind, count) in &day.errors {
+            *totals.errors.entry(kind.clone()).or_insert(0) += count;
+        }
+    }
+    println!(
+        "{:<12} {:>6} {:>8} {:>10} {:>7}",
+        "Total", totals.ocr_captures, totals.queries, totals.provider_fallbacks, totals.error_total()
+    );
+
+    if !totals.errors.is_empty() {
+        println!("\nErrors by type:");
+        for (kind, count) in &totals.errors {
+            println!("  {:<10} {}", kind, count);
+        }
+    }
+    if totals.queries > 0 && totals.provider_fallbacks * 4 > totals.queries {
+        println!("\n[!] Over 25% of queries needed a fallback; consider changing [models] provider.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_counts_aggregate_and_round_trip() {
+        let mut day = DayCounts::default();
+        for event in [Event::Query, Event::Query, Event::OcrCapture, Event::ProviderFallback, Event::Error("llm"), Event::Error("llm"), Event::Error("ocr")] {
+            day.add(event);
+        }
+        assert_eq!(day.queries, 2);
+        assert_eq!(day.ocr_captures, 1);
+        assert_eq!(day.provider_fallbacks, 1);
+        assert_eq!(day.errors["llm"], 2);
+        assert_eq!(day.error_total(), 3);
+
+        let mut store = Store::new();
+        store.insert("2026-01-01".to_string(), day);
+        let json = serde_json::to_string(&store).unwrap();
+        let parsed: Store = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, store);
+    }
+}
@@ -0,0 +1,115 @@
+//! Answer Cache
+//! Question text -> answer pairs for the running session (`general.answer_cache`): a question
+//! seen on an earlier form page, or copied to the clipboard again, is answered without another
+//! LLM call. An answer is only reused under the same `Scope` (caller, provider, model and RAG
+//! filters), since form answers are option labels and a switched model should answer afresh.
+//! Memory only.
+
+use crate::capabilities::ModelCapabilities;
+use crate::config::Config;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Oldest entries are dropped past this many questions
+const MAX_ENTRIES: usize = 500;
+
+/// Questions shorter than this ("Name", "Email") are too generic to reuse an answer for
+const MIN_QUESTION_CHARS: usize = 12;
+
+#[derive(Default)]
+struct Cache {
+    answers: HashMap<String, String>,
+    order: Vec<String>,
+}
+
+fn cache() -> &'static Mutex<Cache> {
+    static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(Cache::default()))
+}
+
+/// Who asked, which decides the shape of the answer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Caller {
+    /// Wake/Model keys: a free-text answer
+    Hotkey,
+    /// The form flow: the label of the option to pick
+    Form,
+}
+
+/// Everything besides the question an answer depends on
+pub struct Scope(String);
+
+impl Scope {
+    /// `filters` is whatever was parsed off the question (`@collection`, `#tag`, `!papers`)
+    pub fn new(caller: Caller, config: &Config, filters: &str) -> Self {
+        let model = ModelCapabilities::get_current_model_id(config).unwrap_or_default();
+        Scope(format!("{:?}\n{}\n{}\n{}", caller, config.models.provider, model, filters))
+    }
+}
+
+pub fn get(scope: &Scope, question: &str) -> Option<String> {
+    let key = key(scope, question)?;
+    cache().lock().unwrap().answers.get(&key).cloned()
+}
+
+pub fn insert(scope: &Scope, question: &str, answer: &str) {
+    let Some(key) = key(scope, question) else { return };
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return;
+    }
+    let mut cache = cache().lock().unwrap();
+    if cache.answers.insert(key.clone(), answer.to_string()).is_none() {
+        cache.order.push(key);
+        if cache.order.len() > MAX_ENTRIES {
+            let oldest = cache.order.remove(0);
+            cache.answers.remove(&oldest);
+        }
+    }
+}
+
+fn key(scope: &Scope, question: &str) -> Option<String> {
+    normalize(question).map(|question| format!("{}\n{}", scope.0, question))
+}
+
+/// Lowercase words without punctuation, so "Capital of France?" and "capital of  france"
+/// are the same question
+fn normalize(question: &str) -> Option<String> {
+    let key = question
+        .split_whitespace()
+        .map(|w| w.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (key.chars().count() >= MIN_QUESTION_CHARS).then_some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_ignores_case_and_punctuation() {
+        let scope = Scope::new(Caller::Hotkey, &Config::default(), "");
+        insert(&scope, "What is the capital of France?", "Paris");
+        assert_eq!(get(&scope, "what is the capital of   FRANCE").as_deref(), Some("Paris"));
+        assert_eq!(get(&scope, "What is the capital of Spain?"), None);
+
+        insert(&scope, "Name", "Ada");
+        assert_eq!(get(&scope, "Name"), None);
+    }
+
+    #[test]
+    fn test_answers_stay_in_their_scope() {
+        let config = Config::default();
+        let hotkey = Scope::new(Caller::Hotkey, &config, "");
+        insert(&hotkey, "Which organelle makes ATP?", "Mitochondria");
+
+        assert_eq!(get(&Scope::new(Caller::Form, &config, ""), "Which organelle makes ATP?"), None);
+        assert_eq!(get(&Scope::new(Caller::Hotkey, &config, "#biology"), "Which organelle makes ATP?"), None);
+        let mut other_model = config.clone();
+        other_model.models.provider = "ollama".to_string();
+        assert_eq!(get(&Scope::new(Caller::Hotkey, &other_model, ""), "Which organelle makes ATP?"), None);
+        assert_eq!(get(&hotkey, "which organelle makes atp").as_deref(), Some("Mitochondria"));
+    }
+}
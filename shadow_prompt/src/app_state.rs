@@ -0,0 +1,301 @@
+//! App State
+//! The main loop's record of the query or form flow in flight; every indicator change goes
+//! through it. A Wake/Model press while a query is running cancels it: its token fires, the
+//! task stops at its next await, and anything it still sends is dropped, so overlapping
+//! presses can't race on the clipboard or indicator. The newest question wins, and a query
+//! and a form flow supersede each other the same way.
+
+use crate::browser::FlowControl;
+use crate::ui::{IndicatorState, UICommand};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Orange while the first OCR corner is set (BGR)
+const COLOR_POINT_CAPTURED: u32 = 0x0000A5FF;
+/// Yellow while the captured region is read (BGR)
+const COLOR_REGION_CAPTURED: u32 = 0x0000FFFF;
+
+/// What started a query job, which decides the indicator while it runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobKind {
+    /// OCR region from the Wake selection
+    Ocr,
+    /// Model key (clipboard)
+    Clipboard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Ready,
+    /// Wake was pressed; waiting for the OCR region
+    Selecting,
+    /// A query job is running
+    Answering,
+}
+
+/// A job's handle on the indicator and overlay; stale once a newer job has started
+#[derive(Clone)]
+pub struct JobUi {
+    id: u64,
+    current: Arc<AtomicU64>,
+    cancel: CancellationToken,
+    tx: Sender<UICommand>,
+}
+
+impl JobUi {
+    /// Forward `command` unless this job has been replaced or cancelled
+    pub fn send(&self, command: UICommand) {
+        if self.is_current() {
+            let _ = self.tx.send(command);
+        }
+    }
+
+    /// False once a newer job started or this one was cancelled; checked before the clipboard is written
+    pub fn is_current(&self) -> bool {
+        !self.cancel.is_cancelled() && self.current.load(Ordering::SeqCst) == self.id
+    }
+
+    /// End the job: show `answer` (if any), then the ready indicator
+    pub fn finish(&self, answer: Option<IndicatorState>) {
+        if let Some(answer) = answer {
+            self.send(UICommand::SetState(answer));
+        }
+        self.send(UICommand::SetState(IndicatorState::Ready));
+    }
+}
+
+struct Job {
+    handle: JoinHandle<()>,
+    cancel: CancellationToken,
+}
+
+pub struct AppState {
+    ui_tx: Sender<UICommand>,
+    phase: Phase,
+    job: Option<Job>,
+    /// ID of the newest job; shared with every `JobUi`
+    current: Arc<AtomicU64>,
+    /// The headless browser form flow, if one was started
+    browser: Option<JoinHandle<()>>,
+    /// Pause and skips of that flow; cleared whenever it ends
+    flow_control: Arc<FlowControl>,
+}
+
+impl AppState {
+    /// Starts out ready, and shows it
+    pub fn new(ui_tx: Sender<UICommand>, flow_control: Arc<FlowControl>) -> Self {
+        let state = Self {
+            ui_tx,
+            phase: Phase::Ready,
+            job: None,
+            current: Arc::new(AtomicU64::new(0)),
+            browser: None,
+            flow_control,
+        };
+        state.show(UICommand::SetState(IndicatorState::Ready));
+        state
+    }
+
+    pub fn phase(&self) -> Phase {
+        let running = matches!(&self.job, Some(job) if !job.handle.is_finished());
+        if self.phase == Phase::Answering && !running {
+            return Phase::Ready;
+        }
+        self.phase
+    }
+
+    /// A query or form flow is still running (idle exit waits for it)
+    pub fn is_busy(&self) -> bool {
+        self.phase() != Phase::Ready || self.browser_running()
+    }
+
+    pub fn browser_running(&self) -> bool {
+        self.browser.as_ref().is_some_and(|handle| !handle.is_finished())
+    }
+
+    /// Wake: a running query or form flow is dropped in favour of the coming selection
+    pub fn begin_selection(&mut self) {
+        self.cancel_job();
+        self.abort_browser();
+        self.phase = Phase::Selecting;
+        self.show(UICommand::SetState(IndicatorState::Processing));
+        self.show(UICommand::ResetSecondary);
+    }
+
+    /// First OCR corner; ignored unless a selection is in progress
+    pub fn point_captured(&mut self) {
+        if self.phase() == Phase::Selecting {
+            self.show(UICommand::SetColor(COLOR_POINT_CAPTURED));
+        }
+    }
+
+    /// Cancel whatever is running and run `job` as the current query. The job reports
+    /// through the `JobUi` it is given and ends with `JobUi::finish`.
+    pub fn start_job<F, Fut>(&mut self, kind: JobKind, job: F)
+    where
+        F: FnOnce(JobUi) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.cancel_job();
+        self.abort_browser();
+        let id = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+        let cancel = CancellationToken::new();
+        let ui = JobUi { id, current: self.current.clone(), cancel: cancel.clone(), tx: self.ui_tx.clone() };
+        self.phase = Phase::Answering;
+        match kind {
+            JobKind::Ocr => self.show(UICommand::SetColor(COLOR_REGION_CAPTURED)),
+            JobKind::Clipboard => {
+                self.show(UICommand::SetState(IndicatorState::Processing));
+                self.show(UICommand::ClearOverlayText);
+            }
+        }
+
+        let future = job(ui);
+        let token = cancel.clone();
+        let handle = tokio::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => info!("Query cancelled by a newer one"),
+                _ = future => {}
+            }
+        });
+        self.job = Some(Job { handle, cancel });
+    }
+
+    /// Run `flow` as the form flow, cancelling a running query. The caller checks
+    /// `browser_running` first: two flows would drive the same tab.
+    pub fn start_browser<Fut>(&mut self, flow: Fut)
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        if self.phase() == Phase::Answering {
+            self.cancel_job();
+            self.show(UICommand::SetState(IndicatorState::Ready));
+        }
+        self.abort_browser();
+        let control = self.flow_control.clone();
+        let ui_tx = self.ui_tx.clone();
+        self.browser = Some(tokio::spawn(async move {
+            flow.await;
+            // A pause pressed after the last page was filled would otherwise outlive the flow
+            if control.is_paused() {
+                let _ = ui_tx.send(UICommand::ClearOverlayText);
+            }
+            control.reset();
+        }));
+    }
+
+    /// Stop the form flow, unpausing it and clearing its plan overlay; false if none was running
+    pub fn abort_browser(&mut self) -> bool {
+        match self.browser.take() {
+            Some(handle) if !handle.is_finished() => {
+                handle.abort();
+                self.flow_control.reset();
+                self.show(UICommand::ClearOverlayText);
+                println!("[*] Form flow aborted.");
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Stop the running query, if any; the indicator is left to the caller's next state
+    fn cancel_job(&mut self) {
+        if let Some(job) = self.job.take() {
+            if !job.handle.is_finished() {
+                job.cancel.cancel();
+                println!("[*] Previous query cancelled.");
+            }
+        }
+        // Also retires the finished job's JobUi, in case a clone outlived it
+        self.current.fetch_add(1, Ordering::SeqCst);
+        self.phase = Phase::Ready;
+    }
+
+    fn show(&self, command: UICommand) {
+        let _ = self.ui_tx.send(command);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_newer_job_cancels_and_silences_older() {
+        let (tx, rx) = mpsc::channel();
+        let mut state = AppState::new(tx, Arc::default());
+
+        let (slow_tx, slow_rx) = tokio::sync::oneshot::channel::<JobUi>();
+        state.start_job(JobKind::Clipboard, |ui| async move {
+            let _ = slow_tx.send(ui.clone());
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            ui.send(UICommand::SetState(IndicatorState::McqA));
+        });
+        let stale = slow_rx.await.unwrap();
+        assert!(stale.is_current());
+        assert_eq!(state.phase(), Phase::Answering);
+
+        state.start_job(JobKind::Clipboard, |ui| async move { ui.finish(None) });
+        assert!(!stale.is_current());
+        stale.send(UICommand::SetState(IndicatorState::McqB));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(state.phase(), Phase::Ready);
+        let states: Vec<IndicatorState> = rx
+            .try_iter()
+            .filter_map(|command| match command {
+                UICommand::SetState(state) => Some(state),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(states, [IndicatorState::Ready, IndicatorState::Processing, IndicatorState::Processing, IndicatorState::Ready]);
+    }
+
+    #[tokio::test]
+    async fn test_query_and_form_flow_supersede_each_other() {
+        let (tx, rx) = mpsc::channel();
+        let mut state = AppState::new(tx, Arc::default());
+
+        state.start_browser(tokio::time::sleep(Duration::from_secs(30)));
+        assert!(state.browser_running());
+        state.start_job(JobKind::Clipboard, |_| tokio::time::sleep(Duration::from_secs(30)));
+        assert!(!state.browser_running());
+        assert!(state.is_busy());
+
+        state.start_browser(tokio::time::sleep(Duration::from_secs(30)));
+        assert_eq!(state.phase(), Phase::Ready);
+        assert!(state.browser_running());
+        // The cancelled query's indicator goes back to ready
+        assert!(matches!(rx.try_iter().last(), Some(UICommand::SetState(IndicatorState::Ready))));
+
+        assert!(state.abort_browser());
+        assert!(!state.abort_browser());
+        assert!(!state.is_busy());
+    }
+
+    #[tokio::test]
+    async fn test_ending_the_flow_unpauses_it() {
+        let (tx, _rx) = mpsc::channel();
+        let control = Arc::new(FlowControl::default());
+        let mut state = AppState::new(tx, control.clone());
+
+        state.start_browser(tokio::time::sleep(Duration::from_secs(30)));
+        assert!(control.toggle_pause());
+        control.toggle_skip(2);
+        assert!(state.abort_browser());
+        assert!(!control.is_paused() && !control.paused_flag().load(Ordering::SeqCst));
+        assert!(control.toggle_skip(2), "skips are cleared too");
+
+        state.start_browser(async {});
+        control.toggle_pause();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!state.browser_running());
+        assert!(!control.is_paused());
+    }
+}
@@ -0,0 +1,158 @@
+//! Access Walls
+//! Sign-in pages, CAPTCHAs and expired sessions can appear at any point of a form flow
+//! (a redirect after Next, a session timing out mid-form). The flow checks the tab before
+//! every extraction and aborts instead of sending such a page to the LLM.
+
+use crate::i18n::{t, tf};
+use headless_chrome::Tab;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// What the page looks like, read in one evaluation
+const PAGE_SIGNALS_JS: &str = r#"
+(function() {
+    let text = document.body ? document.body.innerText.slice(0, 3000) : "";
+    return JSON.stringify({
+        url: location.href,
+        title: document.title,
+        captcha: !!document.querySelector('iframe[src*="recaptcha"], iframe[src*="hcaptcha"], iframe[src*="challenges.cloudflare.com"], .g-recaptcha, #captcha-form'),
+        password: !!document.querySelector('input[type="password"]'),
+        questions: document.querySelectorAll('[role="listitem"], textarea, input:not([type="hidden"])').length,
+        text: text
+    });
+})();
+"#;
+
+/// Hosts that only ever serve sign-in or SSO pages; a trailing dot matches a host prefix
+const SIGN_IN_HOSTS: &[&str] = &[
+    "accounts.google.com",
+    "login.microsoftonline.com",
+    "login.live.com",
+    "sso.",
+    "login.",
+    "idp.",
+    "shibboleth.",
+    "okta.com",
+    "auth0.com",
+    "onelogin.com",
+];
+
+const EXPIRED_PHRASES: &[&str] = &[
+    "session has expired",
+    "session expired",
+    "you've been signed out",
+    "you have been signed out",
+    "sign in again",
+];
+
+#[derive(Debug, PartialEq)]
+pub enum Wall {
+    Captcha,
+    SignIn(String),
+    SessionExpired,
+    NoPermission,
+}
+
+impl Wall {
+    /// Overlay text explaining why the flow stopped
+    pub fn message(&self) -> String {
+        match self {
+            Wall::Captcha => t("browser.wall_captcha"),
+            Wall::SignIn(host) => tf("browser.wall_sign_in", &[("host", host)]),
+            Wall::SessionExpired => t("browser.wall_session_expired"),
+            Wall::NoPermission => t("browser.wall_no_permission"),
+        }
+    }
+}
+
+/// The wall the tab is showing, if any. An unreadable page (still loading) counts as none;
+/// the extractor fails on it anyway.
+pub fn detect(tab: &Arc<Tab>) -> Option<Wall> {
+    let result = tab.evaluate(PAGE_SIGNALS_JS, false).ok()?;
+    let signals: Value = serde_json::from_str(result.value?.as_str()?).ok()?;
+    classify(&signals)
+}
+
+fn classify(signals: &Value) -> Option<Wall> {
+    let url = signals["url"].as_str().unwrap_or("");
+    let title = signals["title"].as_str().unwrap_or("").to_lowercase();
+    let text = signals["text"].as_str().unwrap_or("").to_lowercase();
+
+    if signals["captcha"] == true || url.contains("/sorry/") {
+        return Some(Wall::Captcha);
+    }
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let is_form = host == "docs.google.com" || host == "forms.gle";
+    if !host.is_empty() && !is_form && SIGN_IN_HOSTS.iter().any(|h| host_matches(&host, h)) {
+        return Some(Wall::SignIn(host));
+    }
+    // Body text only counts on a page without questions; a quiz may well ask about expired sessions
+    let has_questions = signals["questions"].as_u64().unwrap_or(0) > 0;
+    if EXPIRED_PHRASES.iter().any(|p| title.contains(p) || (!has_questions && text.contains(p))) {
+        return Some(Wall::SessionExpired);
+    }
+    // Login markers before permission ones: "Sign in - Google Accounts" needs a sign-in, not another account
+    if title.contains("sign in") || url.contains("/ServiceLogin") {
+        return Some(Wall::SignIn(host));
+    }
+    if title.contains("you need permission") {
+        return Some(Wall::NoPermission);
+    }
+    // A password field has no business on a form page; some SSO portals keep a neutral host
+    if signals["password"] == true {
+        return Some(Wall::SignIn(host));
+    }
+    None
+}
+
+fn host_matches(host: &str, pattern: &str) -> bool {
+    if pattern.ends_with('.') {
+        host.starts_with(pattern)
+    } else {
+        host == pattern || host.ends_with(&format!(".{}", pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn page(url: &str, title: &str, text: &str) -> Value {
+        json!({ "url": url, "title": title, "text": text, "captcha": false, "password": false, "questions": 0 })
+    }
+
+    #[test]
+    fn test_classify_walls() {
+        let form = "https://docs.google.com/forms/d/e/abc/viewform";
+        assert_eq!(classify(&page(form, "Quiz 3", "Name *")), None);
+
+        let mut captcha = page(form, "Quiz 3", "");
+        captcha["captcha"] = json!(true);
+        assert_eq!(classify(&captcha), Some(Wall::Captcha));
+
+        assert_eq!(
+            classify(&page("https://login.microsoftonline.com/common/oauth2", "Sign in to your account", "")),
+            Some(Wall::SignIn("login.microsoftonline.com".to_string()))
+        );
+        assert_eq!(classify(&page(form, "Google Forms - You need permission", "")), Some(Wall::NoPermission));
+        assert_eq!(
+            classify(&page(form, "Sign in - Google Accounts", "Sign in to continue to Forms")),
+            Some(Wall::SignIn("docs.google.com".to_string()))
+        );
+        assert_eq!(classify(&page(form, "Sign in again", "")), Some(Wall::SessionExpired));
+        assert_eq!(classify(&page(form, "Quiz 3", "Your session has expired.")), Some(Wall::SessionExpired));
+        let mut quiz = page(form, "Quiz 3", "What happens when a session has expired? *");
+        quiz["questions"] = json!(4);
+        assert_eq!(classify(&quiz), None);
+
+        let mut portal = page("https://portal.example.edu/cas", "Portal", "");
+        portal["password"] = json!(true);
+        assert!(matches!(classify(&portal), Some(Wall::SignIn(_))));
+        assert!(!host_matches("lasso.com", "sso."));
+        assert!(host_matches("acme.okta.com", "okta.com"));
+    }
+}
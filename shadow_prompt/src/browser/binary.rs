@@ -0,0 +1,122 @@
+//! Browser Executable
+//! Finds a Chromium-based browser for the incognito debugger and the headless launch:
+//! `browser.binary_path` when set, else the App Paths registry entries (HKCU, then HKLM),
+//! else the usual machine-wide and per-user (LOCALAPPDATA) install folders.
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Executables registered under App Paths, in order of preference
+const APP_PATHS_EXES: &[&str] = &["chrome.exe", "msedge.exe", "brave.exe", "chromium.exe"];
+
+/// Install folders relative to Program Files, Program Files (x86) or LOCALAPPDATA
+const INSTALL_DIRS: &[&str] = &[
+    r"Google\Chrome\Application\chrome.exe",
+    r"Microsoft\Edge\Application\msedge.exe",
+    r"BraveSoftware\Brave-Browser\Application\brave.exe",
+    r"Chromium\Application\chrome.exe",
+];
+
+pub fn find_browser(configured: Option<&str>) -> Result<PathBuf> {
+    if let Some(path) = configured.map(str::trim).filter(|p| !p.is_empty()) {
+        let path = PathBuf::from(path);
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(anyhow!("browser.binary_path does not exist: {}", path.display()))
+        };
+    }
+
+    for exe in APP_PATHS_EXES {
+        for hive in ["HKCU", "HKLM"] {
+            if let Some(path) = app_path(hive, exe).filter(|p| p.is_file()) {
+                log::info!("[Browser] Found {} via App Paths: {}", exe, path.display());
+                return Ok(path);
+            }
+        }
+    }
+
+    let roots: Vec<PathBuf> = ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var).map(PathBuf::from))
+        .collect();
+    for dir in INSTALL_DIRS {
+        if let Some(path) = roots.iter().map(|root| root.join(dir)).find(|p| p.is_file()) {
+            return Ok(path);
+        }
+    }
+
+    Err(anyhow!("Could not find Chrome, Edge, Brave or Chromium. Set browser.binary_path in config.toml."))
+}
+
+fn app_path(hive: &str, exe: &str) -> Option<PathBuf> {
+    let key = format!(r"{}\SOFTWARE\Microsoft\Windows\CurrentVersion\App Paths\{}", hive, exe);
+    let mut command = Command::new("reg");
+    command.args(["query", &key, "/ve"]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_reg_default(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The (Default) value from `reg query <key> /ve` output
+fn parse_reg_default(output: &str) -> Option<PathBuf> {
+    output.lines().find_map(|line| {
+        let (_, value) = line.split_once("REG_SZ").or_else(|| line.split_once("REG_EXPAND_SZ"))?;
+        let value = value.trim().trim_matches('"');
+        let value = expand_env(value);
+        (!value.is_empty()).then(|| Path::new(&value).to_path_buf())
+    })
+}
+
+/// %VAR% expansion for REG_EXPAND_SZ values
+fn expand_env(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else { break };
+        let name = &rest[start + 1..start + 1 + len];
+        out.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(var) => out.push_str(&var),
+            Err(_) => out.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reg_default() {
+        let output = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\App Paths\\chrome.exe\r\n    (Default)    REG_SZ    C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe\r\n\r\n";
+        assert_eq!(
+            parse_reg_default(output),
+            Some(PathBuf::from(r"C:\Program Files\Google\Chrome\Application\chrome.exe"))
+        );
+        assert_eq!(parse_reg_default("ERROR: The system was unable to find the specified registry key or value."), None);
+    }
+
+    #[test]
+    fn test_expand_env_keeps_unknown_vars() {
+        assert_eq!(expand_env(r"%SHADOWPROMPT_NO_SUCH_VAR%\brave.exe"), r"%SHADOWPROMPT_NO_SUCH_VAR%\brave.exe");
+        assert_eq!(expand_env("plain"), "plain");
+    }
+
+    #[test]
+    fn test_missing_configured_path_is_an_error() {
+        assert!(find_browser(Some(r"C:\nowhere\chrome.exe")).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Question Images
+//! The extractor reports an `image_url` for questions with a diagram or graph. For
+//! vision-capable models those images are downloaded with the browser's cookies (form
+//! images are often only served to the signed-in account) and sent with the page.
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use headless_chrome::protocol::cdp::Network;
+use headless_chrome::Tab;
+use reqwest::header;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Images sent per page; more than a few mostly adds latency and cost
+pub const MAX_IMAGES: usize = 4;
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// A downloaded question image as a data URL for `LlmClient::query_with_images`
+pub struct QuestionImage {
+    /// The question's `index` in the Form JSON, so the prompt can say which image is whose
+    pub question: u64,
+    pub data_url: String,
+}
+
+/// (question index, image URL) for every question that has an image
+pub fn image_urls(form_json: &str) -> Vec<(u64, String)> {
+    let Ok(form) = serde_json::from_str::<Value>(form_json) else {
+        return Vec::new();
+    };
+    form["questions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|q| {
+            let url = q["image_url"].as_str()?;
+            url.starts_with("http").then(|| (q["index"].as_u64().unwrap_or(0), url.to_string()))
+        })
+        .take(MAX_IMAGES)
+        .collect()
+}
+
+/// Download the images with the tab's cookies; failures are logged and skipped
+pub async fn download(tab: &Arc<Tab>, urls: &[(u64, String)]) -> Vec<QuestionImage> {
+    if urls.is_empty() {
+        return Vec::new();
+    }
+    let cookies = match tab.call_method(Network::GetAllCookies(None)) {
+        Ok(all) => all.cookies,
+        Err(e) => {
+            eprintln!("[-] Could not read browser cookies for images: {}", e);
+            Vec::new()
+        }
+    };
+    let client = match reqwest::Client::builder().timeout(Duration::from_secs(15)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("[-] Image download unavailable: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut images = Vec::new();
+    for (question, url) in urls {
+        match fetch(&client, url, &cookies).await {
+            Ok(data_url) => images.push(QuestionImage { question: *question, data_url }),
+            Err(e) => eprintln!("[-] Skipping image for question {}: {}", question, e),
+        }
+    }
+    images
+}
+
+async fn fetch(client: &reqwest::Client, url: &str, cookies: &[Network::Cookie]) -> Result<String> {
+    let url = reqwest::Url::parse(url)?;
+    let cookie_header = cookie_header(&url, cookies);
+    let mut request = client.get(url);
+    if !cookie_header.is_empty() {
+        request = request.header(header::COOKIE, cookie_header);
+    }
+
+    let res = request.send().await?.error_for_status()?;
+    let mime = res.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    if !mime.starts_with("image/") {
+        return Err(anyhow!("not an image ({})", mime));
+    }
+    let bytes = res.bytes().await?;
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(anyhow!("image is too large ({} KB)", bytes.len() / 1024));
+    }
+    Ok(format!("data:{};base64,{}", mime, base64::engine::general_purpose::STANDARD.encode(&bytes)))
+}
+
+/// "name=value; ..." for the cookies a browser would send to `url`. Nothing goes over plain
+/// http (which also keeps `secure` cookies on https), so the session can't leak to an `http://` image
+fn cookie_header(url: &reqwest::Url, cookies: &[Network::Cookie]) -> String {
+    if url.scheme() != "https" {
+        return String::new();
+    }
+    let host = url.host_str().unwrap_or("");
+    cookies
+        .iter()
+        .filter(|c| domain_matches(host, &c.domain) && path_matches(url.path(), &c.path))
+        .map(|c| format!("{}={}", c.name, c.value))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// RFC 6265 path-match: the cookie path is the request path or a `/`-bounded prefix of it
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    if cookie_path.is_empty() || cookie_path == "/" {
+        return true;
+    }
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+fn domain_matches(host: &str, cookie_domain: &str) -> bool {
+    let domain = cookie_domain.trim_start_matches('.');
+    !domain.is_empty() && (host == domain || host.ends_with(&format!(".{}", domain)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_urls_keep_question_index() {
+        let form = r#"{"questions":[
+            {"index":0,"text":"No image"},
+            {"index":3,"text":"Graph","image_url":"https://lh7-rt.googleusercontent.com/formsz/abc"},
+            {"index":4,"text":"Inline","image_url":"data:image/gif;base64,R0lG"}]}"#;
+        assert_eq!(image_urls(form), vec![(3, "https://lh7-rt.googleusercontent.com/formsz/abc".to_string())]);
+        assert!(image_urls("not json").is_empty());
+    }
+
+    #[test]
+    fn test_domain_matches() {
+        assert!(domain_matches("lh7-rt.googleusercontent.com", ".googleusercontent.com"));
+        assert!(domain_matches("docs.google.com", "docs.google.com"));
+        assert!(!domain_matches("evilgoogle.com", ".google.com"));
+        assert!(!domain_matches("docs.google.com", ""));
+    }
+
+    #[test]
+    fn test_path_matches() {
+        assert!(path_matches("/formsz/abc", "/"));
+        assert!(path_matches("/formsz/abc", "/formsz"));
+        assert!(path_matches("/formsz/abc", "/formsz/"));
+        assert!(path_matches("/formsz", "/formsz"));
+        assert!(!path_matches("/formszz/abc", "/formsz"));
+        assert!(!path_matches("/other", "/formsz"));
+    }
+}
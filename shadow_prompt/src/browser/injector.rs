@@ -1,3 +1,8 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
 pub const EXTRACTOR_JS: &str = r#"
 (function() {
     try {
@@ -26,8 +31,56 @@ pub const EXTRACTOR_JS: &str = r#"
 
             let isAnswered = false;
 
+            let listbox = item.querySelector('[role="listbox"]');
             let options = item.querySelectorAll('[role="radio"], [role="checkbox"]');
-            if (options.length > 0) {
+            let rowGroups = Array.from(item.querySelectorAll('[role="radiogroup"], [role="group"]'))
+                .filter(group => group.querySelector('[role="radio"], [role="checkbox"]'));
+            if (rowGroups.length > 1) {
+                // Grid: one radiogroup (multiple choice grid) or group (checkbox grid) per row,
+                // labelled with the row; each cell's data-value is its column
+                questionData.type = rowGroups[0].querySelector('[role="checkbox"]') ? "checkbox_grid" : "grid";
+                questionData.rows = [];
+                let answeredRows = 0;
+                rowGroups.forEach((group, rowIdx) => {
+                    let row = { text: (group.getAttribute('aria-label') || ("Row " + (rowIdx + 1))).trim(), options: [] };
+                    let rowAnswered = false;
+                    group.querySelectorAll('[role="radio"], [role="checkbox"]').forEach((cell, colIdx) => {
+                        if (cell.getAttribute('aria-checked') === 'true') {
+                            rowAnswered = true;
+                        }
+                        let cellId = cell.id;
+                        if (!cellId) {
+                            cellId = questionData.container_id + "_r" + rowIdx + "_c" + colIdx;
+                            cell.id = cellId;
+                        }
+                        let label = cell.getAttribute('data-value') || cell.getAttribute('aria-label') || cell.innerText;
+                        row.options.push({ text: label.trim(), id: cellId });
+                    });
+                    if (rowAnswered) {
+                        answeredRows++;
+                    } else {
+                        questionData.rows.push(row);
+                    }
+                });
+                isAnswered = answeredRows === rowGroups.length;
+            } else if (listbox) {
+                // Dropdown: the options sit inside the (closed) listbox; "Choose" has no value
+                questionData.type = "dropdown";
+                questionData.options = [];
+                listbox.querySelectorAll('[role="option"]').forEach((opt, optIdx) => {
+                    let value = opt.getAttribute('data-value');
+                    if (!value) return;
+                    if (opt.getAttribute('aria-selected') === 'true') {
+                        isAnswered = true;
+                    }
+                    let optId = opt.id;
+                    if (!optId) {
+                        optId = questionData.container_id + "_opt_" + optIdx;
+                        opt.id = optId;
+                    }
+                    questionData.options.push({ text: opt.innerText.trim() || value, id: optId });
+                });
+            } else if (options.length > 0) {
                 questionData.type = options[0].getAttribute('role'); // "radio" or "checkbox"
                 questionData.options = [];
                 options.forEach((opt, optIdx) => {
@@ -42,6 +95,15 @@ pub const EXTRACTOR_JS: &str = r#"
                     }
                     questionData.options.push({ text: label, id: optId });
                 });
+
+                // Linear scale: numbered radios with the end labels as loose text in the group
+                let numeric = Array.from(options).every(opt => /^\d+$/.test(opt.getAttribute('data-value') || ""));
+                let group = item.querySelector('[role="radiogroup"]');
+                if (questionData.type === "radio" && numeric && group) {
+                    questionData.type = "scale";
+                    let labels = group.innerText.split('\n').map(s => s.trim()).filter(s => s && !/^\d+$/.test(s));
+                    if (labels.length > 0) { questionData.scale_labels = labels; }
+                }
             } else {
                 let textInput = item.querySelector('input[type="text"], input[type="url"], input[type="email"], input[type="number"], textarea');
                 if (textInput) {
@@ -85,26 +147,303 @@ pub const EXTRACTOR_JS: &str = r#"
 })();
 "#;
 
-pub fn build_injector_call(raw_actions_json: &str) -> String {
+/// Action the injector knows how to perform
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionKind {
+    Click,
+    Check,
+    Type,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormAction {
+    pub id: String,
+    pub action: ActionKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// Element IDs the extractor reported for the current page, grouped by what may be done to them
+#[derive(Debug, Default)]
+pub struct FormSchema {
+    choice_ids: HashSet<String>,
+    text_ids: HashSet<String>,
+    next_ids: HashSet<String>,
+    submit_ids: HashSet<String>,
+    /// Question text in page order; question N (1-based) is `questions[N - 1]`
+    questions: Vec<String>,
+    /// Extractor container ID per question, in the same order
+    containers: Vec<String>,
+    /// Element ID -> (question number, option label)
+    labels: HashMap<String, (usize, String)>,
+    /// Cell ID -> (question number, row) for multiple choice grids, which take one cell per row
+    grid_rows: HashMap<String, (usize, usize)>,
+}
+
+impl FormSchema {
+    pub fn from_form_json(form_json: &str) -> Result<Self> {
+        let form: Value = serde_json::from_str(form_json)
+            .map_err(|e| anyhow!("Extractor returned invalid JSON: {}", e))?;
+        let mut schema = FormSchema::default();
+
+        for question in form["questions"].as_array().into_iter().flatten() {
+            schema.questions.push(question["text"].as_str().unwrap_or("").trim().to_string());
+            schema.containers.push(question["container_id"].as_str().unwrap_or("").to_string());
+            let number = schema.questions.len();
+
+            if let Some(id) = question["id"].as_str() {
+                schema.text_ids.insert(id.to_string());
+                schema.labels.insert(id.to_string(), (number, String::new()));
+            }
+            for option in question["options"].as_array().into_iter().flatten() {
+                if let Some(id) = option["id"].as_str() {
+                    schema.choice_ids.insert(id.to_string());
+                    let label = option["text"].as_str().unwrap_or(id).trim().to_string();
+                    schema.labels.insert(id.to_string(), (number, label));
+                }
+            }
+
+            let single_choice = question["type"].as_str() == Some("grid");
+            for (row_index, row) in question["rows"].as_array().into_iter().flatten().enumerate() {
+                let row_text = row["text"].as_str().unwrap_or("").trim();
+                for cell in row["options"].as_array().into_iter().flatten() {
+                    let Some(id) = cell["id"].as_str() else { continue };
+                    schema.choice_ids.insert(id.to_string());
+                    let column = cell["text"].as_str().unwrap_or(id).trim();
+                    schema.labels.insert(id.to_string(), (number, format!("{}: {}", row_text, column)));
+                    if single_choice {
+                        schema.grid_rows.insert(id.to_string(), (number, row_index));
+                    }
+                }
+            }
+        }
+
+        for button in form["navigation"].as_array().into_iter().flatten() {
+            let Some(id) = button["id"].as_str() else { continue };
+            match button["type"].as_str() {
+                Some("next") => { schema.next_ids.insert(id.to_string()); }
+                Some("submit") => { schema.submit_ids.insert(id.to_string()); }
+                _ => {}
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Parse the LLM's action array and check every entry against this page.
+    /// `allow_next` permits clicking the page's Next button (auto mode only); Submit is never allowed.
+    pub fn validate(&self, raw_actions: &str, allow_next: bool) -> Result<Vec<FormAction>> {
+        let actions: Vec<FormAction> = serde_json::from_str(raw_actions)
+            .map_err(|e| anyhow!("Output is not a valid action array: {}", e))?;
+
+        let mut answered_rows = HashSet::new();
+        for (i, action) in actions.iter().enumerate() {
+            let id = action.id.as_str();
+            match action.action {
+                ActionKind::Type => {
+                    if !self.text_ids.contains(id) {
+                        return Err(anyhow!("Action {}: \"{}\" is not a text input on this page", i, id));
+                    }
+                    if action.value.is_none() {
+                        return Err(anyhow!("Action {}: \"type\" requires a \"value\"", i));
+                    }
+                }
+                ActionKind::Click | ActionKind::Check => {
+                    if self.submit_ids.contains(id) {
+                        return Err(anyhow!("Action {}: clicking Submit is not allowed", i));
+                    }
+                    if self.next_ids.contains(id) {
+                        if !allow_next {
+                            return Err(anyhow!("Action {}: navigation is not allowed in single-page mode", i));
+                        }
+                    } else if !self.choice_ids.contains(id) {
+                        return Err(anyhow!("Action {}: unknown option ID \"{}\"", i, id));
+                    }
+                    if let Some(row) = self.grid_rows.get(id) {
+                        if !answered_rows.insert(*row) {
+                            return Err(anyhow!("Action {}: grid row already answered; pick one cell per row", i));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(actions)
+    }
+
+    pub fn clicks_next(&self, actions: &[FormAction]) -> bool {
+        actions.iter().any(|a| a.action != ActionKind::Type && self.next_ids.contains(&a.id))
+    }
+
+    /// 1-based question number an action answers; None for navigation
+    pub fn question_number(&self, action: &FormAction) -> Option<usize> {
+        self.labels.get(&action.id).map(|(number, _)| *number)
+    }
+
+    /// Container ID of the question an action answers, which stays the same across reloads
+    pub fn container_of(&self, action: &FormAction) -> Option<&str> {
+        let number = self.question_number(action)?;
+        self.containers.get(number - 1).map(String::as_str).filter(|id| !id.is_empty())
+    }
+
+    /// A click on the page's Next button, if it has one
+    pub fn next_button(&self) -> Option<FormAction> {
+        self.next_ids.iter().next().map(|id| FormAction { id: id.clone(), action: ActionKind::Click, value: None })
+    }
+
+    /// Drop actions answering any of the `skipped` question numbers
+    pub fn without_skipped(&self, actions: Vec<FormAction>, skipped: &HashSet<usize>) -> Vec<FormAction> {
+        actions
+            .into_iter()
+            .filter(|a| self.question_number(a).map_or(true, |n| !skipped.contains(&n)))
+            .collect()
+    }
+
+    /// Full question text and planned answer per question, for dry runs
+    pub fn preview(&self, actions: &[FormAction]) -> String {
+        let mut lines = Vec::new();
+        for (i, text) in self.questions.iter().enumerate() {
+            lines.push(format!("{}. {}\n   -> {}", i + 1, text, self.answers_for(actions, i + 1)));
+        }
+        if self.clicks_next(actions) {
+            lines.push("(would continue to the next page)".to_string());
+        }
+        lines.join("\n")
+    }
+
+    fn answers_for(&self, actions: &[FormAction], number: usize) -> String {
+        let answers: Vec<String> = actions
+            .iter()
+            .filter(|a| self.question_number(a) == Some(number))
+            .map(|a| match a.action {
+                ActionKind::Type => format!("\"{}\"", a.value.as_deref().unwrap_or("")),
+                _ => self.labels[&a.id].1.clone(),
+            })
+            .collect();
+        if answers.is_empty() { "(no answer)".to_string() } else { answers.join(", ") }
+    }
+
+    /// (question text, answer) for every question the actions answer: the typed value, or
+    /// one option label per line. This is the form the answer cache stores.
+    pub fn answered_pairs(&self, actions: &[FormAction]) -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+        for (i, text) in self.questions.iter().enumerate() {
+            let answer: Vec<String> = actions
+                .iter()
+                .filter(|a| self.question_number(a) == Some(i + 1))
+                .map(|a| match a.action {
+                    ActionKind::Type => a.value.clone().unwrap_or_default(),
+                    _ => self.labels[&a.id].1.clone(),
+                })
+                .collect();
+            if !answer.is_empty() {
+                pairs.push((text.clone(), answer.join("\n")));
+            }
+        }
+        pairs
+    }
+
+    /// Actions for questions whose text `lookup` has an answer for, keyed by container ID.
+    /// A cached answer is used only when every line of it is an option label of the question
+    /// (or the question is a text input); anything else is left to the LLM.
+    pub fn cached_answers(&self, lookup: impl Fn(&str) -> Option<String>) -> Vec<(String, Vec<FormAction>)> {
+        let mut found = Vec::new();
+        for (i, text) in self.questions.iter().enumerate() {
+            let number = i + 1;
+            let container = &self.containers[i];
+            let Some(answer) = lookup(text).filter(|_| !container.is_empty()) else { continue };
+            let ids: Vec<&String> = self.labels.iter().filter(|(_, (n, _))| *n == number).map(|(id, _)| id).collect();
+
+            let actions = if let Some(input) = ids.iter().find(|id| self.text_ids.contains(id.as_str())) {
+                Some(vec![FormAction { id: input.to_string(), action: ActionKind::Type, value: Some(answer) }])
+            } else {
+                answer
+                    .lines()
+                    .map(|line| {
+                        let line = line.trim();
+                        ids.iter()
+                            .find(|id| self.labels[id.as_str()].1.eq_ignore_ascii_case(line))
+                            .map(|id| FormAction { id: id.to_string(), action: ActionKind::Click, value: None })
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|actions| !actions.is_empty())
+            };
+            if let Some(actions) = actions {
+                found.push((container.clone(), actions));
+            }
+        }
+        found
+    }
+
+    /// One line per question with its planned answer, for the paused overlay
+    pub fn describe_plan(&self, actions: &[FormAction], skipped: &HashSet<usize>) -> String {
+        let mut lines = Vec::new();
+        for (i, text) in self.questions.iter().enumerate() {
+            let number = i + 1;
+            let answer = self.answers_for(actions, number);
+            let mark = if skipped.contains(&number) { " [SKIP]" } else { "" };
+            lines.push(format!("{}. {:.40} -> {}{}", number, text, answer, mark));
+        }
+        lines.join("\n")
+    }
+}
+
+/// The Form JSON without the questions in `containers`
+pub fn drop_questions(form_json: &str, containers: &HashSet<&str>) -> String {
+    let Ok(mut form) = serde_json::from_str::<Value>(form_json) else {
+        return form_json.to_string();
+    };
+    if let Some(questions) = form["questions"].as_array_mut() {
+        questions.retain(|q| !q["container_id"].as_str().is_some_and(|id| containers.contains(id)));
+    }
+    form.to_string()
+}
+
+/// Element lookup shared by the injector and the verifier: by ID, else by option value/label
+const FIND_TARGET_JS: &str = r#"
+    function findTarget(id) {
+        let target = document.getElementById(id);
+        if (!target && id) {
+            let all = document.querySelectorAll('[role="radio"], [role="checkbox"], [role="option"]');
+            for (let i = 0; i < all.length; i++) {
+                if (all[i].getAttribute('data-value') == id || all[i].getAttribute('aria-label') == id) {
+                    target = all[i];
+                    break;
+                }
+            }
+        }
+        return target;
+    }
+"#;
+
+/// Script applying the actions; it returns a promise (dropdowns need time to open),
+/// so evaluate it with `await_promise`
+pub fn build_injector_call(actions: &[FormAction]) -> String {
+    // Re-serialized from validated structs, so only plain JSON data reaches the page
+    let actions_json = serde_json::to_string(actions).unwrap_or_else(|_| "[]".to_string());
     format!(
         r#"
-        (function() {{
+        (async function() {{
+            {find_target}
+            const pause = ms => new Promise(resolve => setTimeout(resolve, ms));
             try {{
                 let actions = {actions};
                 for (let action of actions) {{
-                    let target = document.getElementById(action.id);
+                    let target = findTarget(action.id);
                     
-                    if (!target && action.id) {{
-                        let all = document.querySelectorAll('[role="radio"], [role="checkbox"]');
-                        for(let i=0; i<all.length; i++) {{
-                            if (all[i].getAttribute('data-value') == action.id || all[i].getAttribute('aria-label') == action.id) {{
-                                target = all[i];
-                                break;
-                            }}
+                    if (target && target.getAttribute('role') === 'option') {{
+                        // Dropdown: open the listbox, then click the same value in the popup
+                        if (target.getAttribute('aria-selected') !== 'true') {{
+                            let listbox = target.closest('[role="listbox"]');
+                            if (listbox) {{ listbox.click(); await pause(300); }}
+                            let value = target.getAttribute('data-value');
+                            let popup = Array.from(document.querySelectorAll('[role="option"]'))
+                                .find(o => o !== target && o.getAttribute('data-value') === value && o.offsetParent !== null);
+                            (popup || target).click();
+                            await pause(300);
                         }}
-                    }}
-                    
-                    if (target) {{
+                    }} else if (target) {{
                         if (action.action === "click" || action.action === "check") {{
                             if (target.getAttribute('aria-checked') !== 'true') {{
                                 target.click();
@@ -122,6 +461,184 @@ pub fn build_injector_call(raw_actions_json: &str) -> String {
             }}
         }})();
         "#,
-        actions = raw_actions_json
+        find_target = FIND_TARGET_JS,
+        actions = actions_json
+    )
+}
+
+/// Script returning a JSON array of the action IDs whose effect is not on the page:
+/// options that are not aria-checked and inputs that don't hold the typed value.
+/// Only pass answer actions; navigation has no state to check.
+pub fn build_verify_call(actions: &[FormAction]) -> String {
+    let actions_json = serde_json::to_string(actions).unwrap_or_else(|_| "[]".to_string());
+    format!(
+        r#"
+        (function() {{
+            {find_target}
+            let failed = [];
+            for (let action of {actions}) {{
+                let target = findTarget(action.id);
+                if (!target) {{
+                    failed.push(action.id);
+                }} else if (target.getAttribute('role') === 'option') {{
+                    if (target.getAttribute('aria-selected') !== 'true') failed.push(action.id);
+                }} else if (action.action === "type") {{
+                    if ((target.value || "") !== (action.value || "")) failed.push(action.id);
+                }} else if (target.getAttribute('aria-checked') !== 'true') {{
+                    failed.push(action.id);
+                }}
+            }}
+            return JSON.stringify(failed);
+        }})();
+        "#,
+        find_target = FIND_TARGET_JS,
+        actions = actions_json
     )
 }
+
+/// The actions the verifier reported as not applied
+pub fn failed_actions(actions: &[FormAction], verify_result: &str) -> Result<Vec<FormAction>> {
+    let failed: HashSet<String> = serde_json::from_str(verify_result)
+        .map_err(|e| anyhow!("Verifier returned invalid JSON: {}", e))?;
+    Ok(actions.iter().filter(|a| failed.contains(&a.id)).cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORM: &str = r#"{"title":"Quiz","questions":[
+        {"index":0,"type":"radio","text":"Pick one","options":[{"text":"A","id":"q0_opt_0"},{"text":"B","id":"q0_opt_1"}]},
+        {"index":1,"type":"text","text":"Capital of France?","id":"q1_input"}],
+        "navigation":[{"type":"next","id":"nav_next_btn"},{"type":"submit","id":"nav_submit_btn"}]}"#;
+
+    #[test]
+    fn test_valid_actions_pass() {
+        let schema = FormSchema::from_form_json(FORM).unwrap();
+        let raw = r#"[{"id":"q0_opt_1","action":"click"},{"id":"q1_input","action":"type","value":"Paris"},{"id":"nav_next_btn","action":"click"}]"#;
+        let actions = schema.validate(raw, true).unwrap();
+        assert_eq!(actions.len(), 3);
+        assert!(schema.clicks_next(&actions));
+    }
+
+    #[test]
+    fn test_invalid_actions_rejected() {
+        let schema = FormSchema::from_form_json(FORM).unwrap();
+        let rejected = [
+            r#"[{"id":"nav_submit_btn","action":"click"}]"#,
+            r#"[{"id":"nav_next_btn","action":"click"}]"#,
+            r#"[{"id":"evil","action":"click"}]"#,
+            r#"[{"id":"q0_opt_0","action":"eval"}]"#,
+            r#"[{"id":"q0_opt_0","action":"type","value":"x"}]"#,
+            r#"[{"id":"q1_input","action":"type"}]"#,
+            "alert(1)",
+        ];
+        for raw in rejected {
+            assert!(schema.validate(raw, false).is_err(), "accepted: {}", raw);
+        }
+    }
+
+    #[test]
+    fn test_skipped_questions_are_dropped() {
+        let schema = FormSchema::from_form_json(FORM).unwrap();
+        let raw = r#"[{"id":"q0_opt_1","action":"click"},{"id":"q1_input","action":"type","value":"Paris"},{"id":"nav_next_btn","action":"click"}]"#;
+        let actions = schema.validate(raw, true).unwrap();
+
+        let skipped: HashSet<usize> = [2].into_iter().collect();
+        let kept = schema.without_skipped(actions.clone(), &skipped);
+        assert_eq!(kept.len(), 2);
+        assert!(kept.iter().all(|a| a.id != "q1_input"));
+        assert!(schema.clicks_next(&kept));
+
+        let plan = schema.describe_plan(&actions, &skipped);
+        assert!(plan.contains("-> B"));
+        assert!(plan.contains("\"Paris\" [SKIP]"));
+
+        assert_eq!(
+            schema.preview(&actions),
+            "1. Pick one\n   -> B\n2. Capital of France?\n   -> \"Paris\"\n(would continue to the next page)"
+        );
+    }
+
+    #[test]
+    fn test_dropdown_and_scale_options_are_choices() {
+        let form = r#"{"title":"Survey","questions":[
+            {"index":0,"type":"dropdown","text":"Country","options":[{"text":"France","id":"q0_opt_1"},{"text":"Spain","id":"q0_opt_2"}]},
+            {"index":1,"type":"scale","text":"Rate it","scale_labels":["Poor","Great"],"options":[{"text":"1","id":"q1_opt_0"},{"text":"5","id":"q1_opt_4"}]}],
+            "navigation":[]}"#;
+        let schema = FormSchema::from_form_json(form).unwrap();
+        let actions = schema.validate(r#"[{"id":"q0_opt_2","action":"click"},{"id":"q1_opt_4","action":"click"}]"#, false).unwrap();
+        assert_eq!(schema.preview(&actions), "1. Country\n   -> Spain\n2. Rate it\n   -> 5");
+        assert!(build_injector_call(&actions).contains("closest('[role=\"listbox\"]')"));
+    }
+
+    #[test]
+    fn test_grid_cells_one_per_row() {
+        let form = r#"{"title":"Survey","questions":[
+            {"index":0,"type":"grid","text":"Rate each","rows":[
+                {"text":"Speed","options":[{"text":"Bad","id":"q0_r0_c0"},{"text":"Good","id":"q0_r0_c1"}]},
+                {"text":"Price","options":[{"text":"Bad","id":"q0_r1_c0"},{"text":"Good","id":"q0_r1_c1"}]}]},
+            {"index":1,"type":"checkbox_grid","text":"Days","rows":[
+                {"text":"Week 1","options":[{"text":"Mon","id":"q1_r0_c0"},{"text":"Tue","id":"q1_r0_c1"}]}]}],
+            "navigation":[]}"#;
+        let schema = FormSchema::from_form_json(form).unwrap();
+        let raw = r#"[{"id":"q0_r0_c1","action":"click"},{"id":"q0_r1_c0","action":"click"},{"id":"q1_r0_c0","action":"check"},{"id":"q1_r0_c1","action":"check"}]"#;
+        let actions = schema.validate(raw, false).unwrap();
+        assert_eq!(
+            schema.preview(&actions),
+            "1. Rate each\n   -> Speed: Good, Price: Bad\n2. Days\n   -> Week 1: Mon, Week 1: Tue"
+        );
+
+        let same_row = r#"[{"id":"q0_r0_c0","action":"click"},{"id":"q0_r0_c1","action":"click"}]"#;
+        assert!(schema.validate(same_row, false).is_err());
+    }
+
+    #[test]
+    fn test_cached_answers_map_to_options() {
+        let form = r#"{"title":"Quiz","questions":[
+            {"index":0,"container_id":"111","type":"radio","text":"Pick one","options":[{"text":"Alpha","id":"q0_opt_0"},{"text":"Beta","id":"q0_opt_1"}]},
+            {"index":1,"container_id":"222","type":"text","text":"Capital of France?","id":"q1_input"},
+            {"index":2,"container_id":"333","type":"radio","text":"Free text answer","options":[{"text":"Yes","id":"q2_opt_0"}]}],
+            "navigation":[]}"#;
+        let schema = FormSchema::from_form_json(form).unwrap();
+        let cached = schema.cached_answers(|q| match q {
+            "Pick one" => Some("beta".to_string()),
+            "Capital of France?" => Some("Paris".to_string()),
+            "Free text answer" => Some("B. Maybe, because...".to_string()),
+            _ => None,
+        });
+        assert_eq!(cached.len(), 2);
+        assert_eq!(cached[0], ("111".to_string(), vec![FormAction { id: "q0_opt_1".to_string(), action: ActionKind::Click, value: None }]));
+        assert_eq!(cached[1].1[0].value.as_deref(), Some("Paris"));
+
+        let actions: Vec<FormAction> = cached.into_iter().flat_map(|(_, a)| a).collect();
+        assert_eq!(
+            schema.answered_pairs(&actions),
+            vec![("Pick one".to_string(), "Beta".to_string()), ("Capital of France?".to_string(), "Paris".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_failed_actions_from_verifier() {
+        let schema = FormSchema::from_form_json(FORM).unwrap();
+        let raw = r#"[{"id":"q0_opt_1","action":"click"},{"id":"q1_input","action":"type","value":"Paris"}]"#;
+        let actions = schema.validate(raw, false).unwrap();
+
+        let failed = failed_actions(&actions, r#"["q1_input"]"#).unwrap();
+        assert_eq!(failed, vec![actions[1].clone()]);
+        assert!(failed_actions(&actions, "[]").unwrap().is_empty());
+        assert!(failed_actions(&actions, "ERROR: x").is_err());
+        assert!(build_verify_call(&actions).contains("function findTarget"));
+    }
+
+    #[test]
+    fn test_injector_escapes_values() {
+        let actions = vec![FormAction {
+            id: "q1_input".to_string(),
+            action: ActionKind::Type,
+            value: Some("\"}]; alert(1); //".to_string()),
+        }];
+        let script = build_injector_call(&actions);
+        assert!(script.contains(r#"\"}]; alert(1); //"#));
+    }
+}
@@ -1,36 +1,100 @@
+pub mod access;
+pub mod binary;
 pub mod cookies;
+pub mod images;
 pub mod injector;
+pub mod progress;
+pub mod run_log;
+pub mod whole_form;
 
+use crate::capabilities::ModelCapabilities;
 use crate::config::Config;
+use crate::i18n::{t, tf};
 use crate::ui::UICommand;
 use anyhow::{anyhow, Result};
-use headless_chrome::{Browser, LaunchOptions};
+use headless_chrome::{Browser, LaunchOptions, Tab};
+use injector::{FormAction, FormSchema};
 use std::sync::mpsc::Sender;
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::process::Command;
 use tokio::time::{sleep, Duration};
-use serde_json::Value;
-
-pub fn launch_incognito_debugger() -> Result<()> {
-    // Attempt standard locations for chrome.exe or msedge.exe
-    let possible_paths = vec![
-        r#"C:\Program Files\Google\Chrome\Application\chrome.exe"#,
-        r#"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe"#,
-        r#"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe"#,
-    ];
-
-    let mut binary_path = String::new();
-    for path in possible_paths {
-        if std::path::Path::new(path).exists() {
-            binary_path = path.to_string();
-            break;
+use serde_json::{json, Value};
+use run_log::RunLog;
+
+/// LLM attempts per page before giving up on malformed or out-of-schema actions
+const MAX_ACTION_ATTEMPTS: usize = 3;
+
+/// Injection passes per page before unapplied answers are reported
+const MAX_INJECT_ATTEMPTS: usize = 3;
+
+/// Tags the current document so a newly loaded page can be told apart from it
+const MARK_PAGE_JS: &str = "window.__shadowPromptPage = true; 'ok'";
+
+/// What `wait_for_next_page` compares: the mark, the URL and the first question's text
+const PAGE_STATE_JS: &str = r#"
+(function() {
+    let first = document.querySelector('div[role="listitem"]');
+    return JSON.stringify({
+        marked: window.__shadowPromptPage === true,
+        url: location.href,
+        first: first ? first.innerText.slice(0, 200) : "",
+        ready: document.readyState === "complete"
+    });
+})();
+"#;
+
+/// Shared between the main loop and a running form flow so the user can pause
+/// before a page is filled and skip individual questions.
+#[derive(Default)]
+pub struct FlowControl {
+    /// Shared with the input hook, which only reports digit keys while it is set
+    paused: Arc<AtomicBool>,
+    /// 1-based question numbers on the current page
+    skipped: Mutex<HashSet<usize>>,
+}
+
+impl FlowControl {
+    /// Returns the new paused state
+    pub fn toggle_pause(&self) -> bool {
+        !self.paused.fetch_xor(true, Ordering::SeqCst)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// The paused flag itself, for `HotkeyBindings::skip_digits`
+    pub fn paused_flag(&self) -> Arc<AtomicBool> {
+        self.paused.clone()
+    }
+
+    /// Returns true when the question is now skipped
+    pub fn toggle_skip(&self, question: usize) -> bool {
+        let mut skipped = self.skipped.lock().unwrap();
+        if skipped.remove(&question) {
+            false
+        } else {
+            skipped.insert(question);
+            true
         }
     }
 
-    if binary_path.is_empty() {
-        return Err(anyhow!("Could not find Chrome/Edge executable in standard paths."));
+    fn skipped(&self) -> HashSet<usize> {
+        self.skipped.lock().unwrap().clone()
     }
 
+    /// Unpaused with nothing skipped, as at the start of a flow
+    pub fn reset(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        self.skipped.lock().unwrap().clear();
+    }
+}
+
+pub fn launch_incognito_debugger(configured_path: Option<&str>) -> Result<()> {
+    let binary_path = binary::find_browser(configured_path)?;
+
     // Create a temporary debug profile directory so Chrome doesn't fuse with the user's existing Chrome process
     let debug_profile_dir = std::env::temp_dir().join("shadow_chrome_debug");
     let _ = std::fs::create_dir_all(&debug_profile_dir);
@@ -52,16 +116,32 @@ pub async fn execute_form_flow(
     config: Arc<Config>,
     ui_tx: Sender<UICommand>,
     is_auto: bool,
+    control: Arc<FlowControl>,
 ) -> Result<()> {
-    let debug_mode = config.general.debug;
-    let ui_tx_clone = ui_tx.clone();
-    let send_ui = move |msg: String| {
-        if debug_mode {
-            let _ = ui_tx_clone.send(UICommand::SetOverlayText(msg));
-        }
-    };
+    let mode = if config.browser.dry_run { "dry_run" } else if is_auto { "auto" } else { "single_page" };
+    let mut log = RunLog::start(config.browser.run_log, mode, url);
+    let result = run_form_flow(url, config, ui_tx, is_auto, control, &mut log).await;
+    match &result {
+        Ok(()) => log.record("finished", 0, Value::Null),
+        Err(e) => log.record("error", 0, json!({ "error": e.to_string() })),
+    }
+    result
+}
 
-    send_ui("🧠 Checking for active sessions...".to_string());
+async fn run_form_flow(
+    url: Option<&str>,
+    config: Arc<Config>,
+    ui_tx: Sender<UICommand>,
+    is_auto: bool,
+    control: Arc<FlowControl>,
+    log: &mut RunLog,
+) -> Result<()> {
+    control.reset();
+    let llm_config = config.for_form_flow();
+    let cache_scope = crate::answer_cache::Scope::new(crate::answer_cache::Caller::Form, &llm_config, "");
+    let send_ui = debug_overlay(&ui_tx, config.general.debug);
+
+    send_ui(t("browser.checking_sessions"));
 
     // 1. Try to connect to an existing Remote Debugging Session (Port 9222)
     let debug_ws_url = match reqwest::get("http://127.0.0.1:9222/json/version").await {
@@ -83,13 +163,13 @@ pub async fn execute_form_flow(
 
     if let Some(ws_url) = debug_ws_url {
         // Connected to active debug session!
-        send_ui("🔌 Connecting to active Incognito tab...".to_string());
+        send_ui(t("browser.connecting_incognito"));
         
         browser = Browser::connect(ws_url)
             .map_err(|e| anyhow!("Failed to connect to Debugger WebSocket: {}", e))?;
             
         let mut found_tab = None;
-        send_ui("⏳ Waiting for tabs to sync...".to_string());
+        send_ui(t("browser.waiting_for_tabs"));
         
         // Headless Chrome needs a moment to receive Target.targetCreated events over the websocket
         for _ in 0..10 {
@@ -118,7 +198,7 @@ pub async fn execute_form_flow(
         let _ = url.ok_or_else(|| anyhow!("No valid Google Forms URL found on Clipboard (required for standard launch)."))?;
 
         // Fallback to Rookie Auth Extraction Strategy
-        send_ui("🍪 Extracting local session...".to_string());
+        send_ui(t("browser.extracting_session"));
 
         let cookies = cookies::get_google_cookies()
             .map_err(|e| anyhow!("Failed to extract Google session cookies: {}", e))?;
@@ -127,9 +207,14 @@ pub async fn execute_form_flow(
             return Err(anyhow!("No active Google account found. Please sign in or use Incognito approach."));
         }
 
-        send_ui("🌐 Launching headless browser...".to_string());
+        send_ui(t("browser.launching_headless"));
 
+        // Without a detected binary, headless_chrome falls back to its own Chrome lookup
+        let binary_path = binary::find_browser(config.browser.binary_path.as_deref())
+            .map_err(|e| eprintln!("[-] {}", e))
+            .ok();
         let options = LaunchOptions::default_builder()
+            .path(binary_path)
             .headless(true)
             .sandbox(false)
             .enable_gpu(false)
@@ -151,7 +236,7 @@ pub async fn execute_form_flow(
     if needs_navigation {
         // If we are navigating, we are in cold launch, so we know `url` is Some.
         let target_url = url.unwrap();
-        send_ui("🧭 Navigating to form...".to_string());
+        send_ui(t("browser.navigating"));
         tab.navigate_to(target_url)
             .map_err(|e| anyhow!("Failed to navigate: {}", e))?;
         tab.wait_until_navigated()
@@ -160,27 +245,47 @@ pub async fn execute_form_flow(
 
     sleep(Duration::from_secs(2)).await;
 
-    // Check for Permission Error
-    let title = tab.get_title().unwrap_or_default();
-    if title.to_lowercase().contains("you need permission") || title.to_lowercase().contains("sign in") {
-        return Err(anyhow!("Access Denied. Ensure your .edu active profile is logged in to Chrome."));
+    let mut progress = progress::FormProgress::load(config.browser.resume && !config.browser.dry_run, &tab.get_url());
+    if is_auto && config.browser.strategy == whole_form::STRATEGY {
+        return whole_form::run(&tab, &config, &llm_config, &ui_tx, log, &control, &mut progress).await;
     }
-
     let mut page_count = 1;
 
     loop {
-        send_ui(format!("🧠 Reading Page {}...", page_count));
+        // Sign-in, CAPTCHA and expired-session pages are never extracted or sent to the LLM
+        check_access(&tab, &ui_tx, log, page_count)?;
 
-        // 5. Extract JSON
-        let extraction_res = tab.evaluate(injector::EXTRACTOR_JS, false)
-            .map_err(|e| anyhow!("Extraction Script Error: {}", e))?;
+        send_ui(tf("browser.reading_page", &[("page", &page_count.to_string())]));
 
-        let json_val = extraction_res.value.ok_or(anyhow!("Extractor returned null"))?;
-        let form_json = json_val.as_str().unwrap_or("[]");
+        // 5. Extract JSON
+        let extracted = extract_page(&tab)?;
+        let form_json = extracted.as_str();
         
         println!("\n[DEBUG] EXTRACTED JSON:\n{}", form_json);
+        log.record("extracted", page_count, json!({
+            "url": tab.get_url(),
+            "form": serde_json::from_str::<Value>(form_json).unwrap_or_else(|_| Value::String(form_json.to_string())),
+        }));
+
+        // Questions answered in an earlier, interrupted run are replayed instead of asked again
+        let schema = injector::FormSchema::from_form_json(form_json)?;
+        let (mut replay, mut pending_json) = replay_saved(&progress, &schema, form_json, page_count)?;
+        // Questions already answered this session (an earlier page or form) come from the cache
+        if config.general.answer_cache {
+            let replayed: HashSet<&str> = replay.iter().filter_map(|a| schema.container_of(a)).collect();
+            let mut cached = schema.cached_answers(|question| crate::answer_cache::get(&cache_scope, question));
+            cached.retain(|(container, _)| !replayed.contains(container.as_str()));
+            if !cached.is_empty() {
+                println!("[*] {} question(s) on page {} answered from the cache", cached.len(), page_count);
+                let containers: HashSet<&str> = cached.iter().map(|(container, _)| container.as_str()).collect();
+                pending_json = injector::drop_questions(&pending_json, &containers);
+                replay.extend(cached.into_iter().flat_map(|(_, actions)| actions));
+            }
+        }
+        let has_questions = serde_json::from_str::<Value>(&pending_json)
+            .map_or(true, |form| form["questions"].as_array().map_or(false, |q| !q.is_empty()));
 
-        send_ui(format!("🤖 Calculating Page {}...", page_count));
+        send_ui(tf("browser.calculating_page", &[("page", &page_count.to_string())]));
 
         // 6. Query LLM
         let prompt = if is_auto {
@@ -189,58 +294,279 @@ pub async fn execute_form_flow(
 Read the following JSON. It contains `questions` and `navigation` buttons. 
 CRITICAL RULE 1: If there is a `navigation` button of type `next`, you MUST include an action to click it as the VERY LAST item in your array after answering all questions on this page.
 CRITICAL RULE 2: You MUST NEVER click a button of type `submit`. If you see `submit`, do not interact with it.
+Grid questions list their `rows`; click exactly one option per row of a `grid`, one or more per row of a `checkbox_grid`.
 Return ONLY a JSON array of actions to take. Actions must be strictly formatted as: [{{\"id\": \"element_id\", \"action\": \"click\"}}, {{\"id\": \"element_id\", \"action\": \"type\", \"value\": \"text here\"}}]. Do NOT return markdown or explanation.
 Form JSON:\n{}",
-                form_json
+                pending_json
             )
         } else {
             format!(
                 "You are an automated quiz solver filling out a Google Form. 
 Read the following JSON. It contains `questions` and `navigation` buttons. 
 CRITICAL RULE 1: You are in SINGLE-PAGE MODE. You MUST NOT interact with ANY navigation buttons. Do NOT click `next` or `submit`.
+Grid questions list their `rows`; click exactly one option per row of a `grid`, one or more per row of a `checkbox_grid`.
 Return ONLY a JSON array of actions to take to answer the questions on this page. Actions must be strictly formatted as: [{{\"id\": \"element_id\", \"action\": \"click\"}}, {{\"id\": \"element_id\", \"action\": \"type\", \"value\": \"text here\"}}]. Do NOT return markdown or explanation.
 Form JSON:\n{}",
-                form_json
+                pending_json
             )
         };
 
-        let llm_res = crate::llm::LlmClient::query(&prompt, &config).await?;
+        // Diagrams and graphs go along with the page when the model can see them
+        let page_images = if ModelCapabilities::supports_vision(&llm_config) {
+            images::download(&tab, &images::image_urls(&pending_json)).await
+        } else {
+            Vec::new()
+        };
+        let image_urls: Vec<String> = page_images.iter().map(|img| img.data_url.clone()).collect();
+        let prompt = if page_images.is_empty() {
+            prompt
+        } else {
+            let owners: Vec<String> = page_images.iter().map(|img| img.question.to_string()).collect();
+            format!("{}\n\nThe attached images, in order, belong to the questions with index: {}.", prompt, owners.join(", "))
+        };
 
-        // Clean markdown if present
-        let raw_actions = llm_res.replace("```json", "").replace("```", "").trim().to_string();
-        
-        println!("\n[DEBUG] LLM OUTPUT:\n{}", raw_actions);
+        // 7. Validate actions against the extracted page, re-prompting on bad output
+        let mut retry_prompt = prompt.clone();
+        let mut actions = None;
+        if !has_questions {
+            // Everything on this page came from saved progress or the cache; only navigation is left
+            actions = Some(schema.next_button().filter(|_| is_auto).into_iter().collect());
+        } else {
+            for attempt in 1..=MAX_ACTION_ATTEMPTS {
+                let llm_res = if image_urls.is_empty() {
+                    crate::llm::LlmClient::query(&retry_prompt, &llm_config).await?
+                } else {
+                    crate::llm::LlmClient::query_with_images(&retry_prompt, &image_urls, &llm_config).await?
+                };
 
-        send_ui(format!("⚡ Injecting Page {}...", page_count));
+                // Clean markdown if present
+                let raw_actions = llm_res.replace("```json", "").replace("```", "").trim().to_string();
 
-        // 7. Inject Actions
-        let injection_script = injector::build_injector_call(&raw_actions);
-        tab.evaluate(&injection_script, false)
-            .map_err(|e| anyhow!("Injection Script Error: {}", e))?;
+                println!("\n[DEBUG] LLM OUTPUT (attempt {}):\n{}", attempt, raw_actions);
+
+                match schema.validate(&raw_actions, is_auto) {
+                    Ok(valid) => {
+                        actions = Some(valid);
+                        break;
+                    }
+                    Err(e) => {
+                        eprintln!("[-] Rejected LLM actions: {}", e);
+                        error!("Form flow rejected LLM actions (attempt {}): {}", attempt, e);
+                        retry_prompt = format!(
+                            "{}\n\nYour previous answer was rejected: {}. Use only IDs from the Form JSON and return ONLY the JSON array.",
+                            prompt, e
+                        );
+                    }
+                }
+            }
+        }
+        let actions = actions.ok_or_else(|| anyhow!("LLM returned invalid actions {} times. Aborting.", MAX_ACTION_ATTEMPTS))?;
+        let actions = [replay, actions].concat();
+
+        let (actions, skipped) = hold_while_paused(&control, &ui_tx, &schema, actions, page_count).await;
+        log.record("planned", page_count, json!({ "actions": actions, "skipped": skipped }));
+
+        // Dry run: show what would be filled in and stop before touching the page
+        if config.browser.dry_run {
+            let preview = tf("browser.preview_page", &[("page", &page_count.to_string()), ("preview", &schema.preview(&actions))]);
+            println!("\n[DRY RUN]\n{}", preview);
+            if let Err(e) = crate::clipboard::ClipboardManager::write(&preview) {
+                eprintln!("[-] Could not copy the preview: {}", e);
+            }
+            let _ = ui_tx.send(UICommand::SetOverlayText(preview));
+            log.record("dry_run", page_count, Value::Null);
+            break;
+        }
+
+        send_ui(tf("browser.injecting_page", &[("page", &page_count.to_string())]));
+
+        // Answers first, checked and retried; Next is clicked only once they are on the page
+        let (navigation, answers): (Vec<FormAction>, Vec<FormAction>) =
+            actions.iter().cloned().partition(|a| schema.question_number(a).is_none());
+        let unfilled = inject_verified(&tab, &answers).await?;
+        let failed: Vec<&str> = unfilled.iter().map(|a| a.id.as_str()).collect();
+        let applied: Vec<&str> = answers.iter().map(|a| a.id.as_str()).filter(|id| !failed.contains(id)).collect();
+        log.record("injected", page_count, json!({ "applied": applied, "failed": failed, "next": !navigation.is_empty() }));
+        if config.general.answer_cache {
+            let applied_answers: Vec<FormAction> = answers.iter().filter(|a| applied.contains(&a.id.as_str())).cloned().collect();
+            for (question, answer) in schema.answered_pairs(&applied_answers) {
+                crate::answer_cache::insert(&cache_scope, &question, &answer);
+            }
+        }
+        record_applied(&mut progress, &schema, answers.iter().filter(|a| applied.contains(&a.id.as_str())));
+        if !unfilled.is_empty() {
+            let mut numbers: Vec<usize> = unfilled.iter().filter_map(|a| schema.question_number(a)).collect();
+            numbers.dedup();
+            let numbers: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+            let msg = tf("browser.unfilled_questions", &[("page", &page_count.to_string()), ("numbers", &numbers.join(", "))]);
+            eprintln!("[-] {}", msg);
+            error!("Form flow: {}", msg);
+            let _ = ui_tx.send(UICommand::SetOverlayText(msg));
+        }
+        let mut page_before = None;
+        if !navigation.is_empty() {
+            tab.evaluate(MARK_PAGE_JS, false)
+                .map_err(|e| anyhow!("Injection Script Error: {}", e))?;
+            page_before = page_state(&tab);
+            tab.evaluate(&injector::build_injector_call(&navigation), true)
+                .map_err(|e| anyhow!("Injection Script Error: {}", e))?;
+        }
 
         // 8. Determine if we should loop
         if !is_auto {
-            send_ui("✅ Single-Page Execution Complete.".to_string());
+            send_ui(t("browser.single_page_complete"));
             break;
         }
 
-        // We do a naive check: if the LLM output contained the `nav_next_btn` ID, we assume it pressed Next.
-        if raw_actions.contains("\"nav_next_btn\"") || raw_actions.contains("\"action\":\"click\"") && form_json.contains("\"nav_next_btn\"") {
-            send_ui("⏳ Waiting for Next Page...".to_string());
-            // Wait for autosave flush and DOM Page transition
-            sleep(Duration::from_secs(3)).await;
+        if let Some(before) = page_before {
+            send_ui(t("browser.waiting_for_next_page"));
+            let timeout = Duration::from_secs(config.browser.page_timeout_secs);
+            if !wait_for_next_page(&tab, &before, timeout).await {
+                return Err(anyhow!(
+                    "Still on page {} {}s after clicking Next (is a required question unanswered?). Aborting.",
+                    page_count, config.browser.page_timeout_secs
+                ));
+            }
+            // Let the new page finish rendering its questions
+            sleep(Duration::from_millis(config.browser.page_settle_ms)).await;
             page_count += 1;
             
-            if page_count > 10 {
-                return Err(anyhow!("Pagination limit exceeded (10 pages max). Aborting."));
+            if page_count > config.browser.max_pages {
+                return Err(anyhow!("Pagination limit exceeded ({} pages max). Aborting.", config.browser.max_pages));
             }
             continue;
         } else {
             // Reached the end (Submit button page, or LLM failed to click next)
-            send_ui("✅ Execution Complete. Review and Submit manually.".to_string());
+            progress.clear();
+            send_ui(t("browser.complete"));
             break;
         }
     }
 
     Ok(())
 }
+
+/// Overlay text for the flow's progress messages, shown only with `general.debug`
+fn debug_overlay(ui_tx: &Sender<UICommand>, debug: bool) -> impl Fn(String) {
+    let ui_tx = ui_tx.clone();
+    move |msg: String| {
+        if debug {
+            let _ = ui_tx.send(UICommand::SetOverlayText(msg));
+        }
+    }
+}
+
+/// Answers an earlier, interrupted run applied on this page, to replay instead of asking
+/// again, and the Form JSON of the questions still to ask. Saved answers that no longer
+/// fit the page are dropped and their questions asked again.
+fn replay_saved(progress: &progress::FormProgress, schema: &FormSchema, form_json: &str, page: usize) -> Result<(Vec<FormAction>, String)> {
+    let (replay, pending_json) = progress.split_page(form_json);
+    if replay.is_empty() {
+        return Ok((replay, pending_json));
+    }
+    match schema.validate(&serde_json::to_string(&replay)?, false) {
+        Ok(valid) => {
+            println!("[*] Replaying {} saved answer(s) on page {}", valid.len(), page);
+            Ok((valid, pending_json))
+        }
+        Err(e) => {
+            eprintln!("[-] Saved answers no longer match the page ({}); asking again", e);
+            Ok((Vec::new(), form_json.to_string()))
+        }
+    }
+}
+
+/// Manual override: while paused, show the page's plan and let the user toggle skips
+/// (digit keys). Returns the actions without the skipped questions, and those questions.
+async fn hold_while_paused(
+    control: &FlowControl,
+    ui_tx: &Sender<UICommand>,
+    schema: &FormSchema,
+    actions: Vec<FormAction>,
+    page: usize,
+) -> (Vec<FormAction>, HashSet<usize>) {
+    if control.is_paused() {
+        while control.is_paused() {
+            let plan = schema.describe_plan(&actions, &control.skipped());
+            let _ = ui_tx.send(UICommand::SetOverlayText(tf(
+                "browser.paused",
+                &[("page", &page.to_string()), ("plan", &plan)],
+            )));
+            sleep(Duration::from_millis(250)).await;
+        }
+        let _ = ui_tx.send(UICommand::ClearOverlayText);
+    }
+    let skipped = control.skipped();
+    control.skipped.lock().unwrap().clear();
+    (schema.without_skipped(actions, &skipped), skipped)
+}
+
+/// Save the answers that reached the page, grouped by question, for `browser.resume`
+fn record_applied<'a>(progress: &mut progress::FormProgress, schema: &FormSchema, applied: impl Iterator<Item = &'a FormAction>) {
+    progress.record(applied.filter_map(|a| Some((schema.container_of(a)?.to_string(), a.clone()))).collect());
+}
+
+/// Abort with an overlay message when the tab shows a sign-in, CAPTCHA or expired-session page
+fn check_access(tab: &Arc<Tab>, ui_tx: &Sender<UICommand>, log: &mut RunLog, page: usize) -> Result<()> {
+    if let Some(wall) = access::detect(tab) {
+        let msg = wall.message();
+        log.record("blocked", page, json!({ "url": tab.get_url(), "reason": format!("{:?}", wall) }));
+        let _ = ui_tx.send(UICommand::SetOverlayText(msg.clone()));
+        return Err(anyhow!(msg));
+    }
+    Ok(())
+}
+
+/// Run the extractor on the current page; returns the Form JSON
+fn extract_page(tab: &Arc<Tab>) -> Result<String> {
+    let extraction_res = tab.evaluate(injector::EXTRACTOR_JS, false)
+        .map_err(|e| anyhow!("Extraction Script Error: {}", e))?;
+    let json_val = extraction_res.value.ok_or(anyhow!("Extractor returned null"))?;
+    Ok(json_val.as_str().unwrap_or("[]").to_string())
+}
+
+fn page_state(tab: &Arc<Tab>) -> Option<Value> {
+    let result = tab.evaluate(PAGE_STATE_JS, false).ok()?;
+    serde_json::from_str(result.value?.as_str()?).ok()
+}
+
+/// Poll until the tab shows a different page than `before`: a new document (the mark is
+/// gone), another URL, or other questions. Evaluation fails while the page is loading.
+async fn wait_for_next_page(tab: &Arc<Tab>, before: &Value, timeout: Duration) -> bool {
+    let started = std::time::Instant::now();
+    while started.elapsed() < timeout {
+        sleep(Duration::from_millis(250)).await;
+        let Some(now) = page_state(tab) else { continue };
+        if now["ready"] != true {
+            continue;
+        }
+        if now["marked"] != true || now["url"] != before["url"] || now["first"] != before["first"] {
+            return true;
+        }
+    }
+    false
+}
+
+/// Inject answer actions, then re-read the page and retry the ones that didn't take.
+/// Returns what is still not applied after `MAX_INJECT_ATTEMPTS` passes.
+async fn inject_verified(tab: &Arc<Tab>, actions: &[FormAction]) -> Result<Vec<FormAction>> {
+    let mut pending = actions.to_vec();
+    for attempt in 1..=MAX_INJECT_ATTEMPTS {
+        if pending.is_empty() {
+            break;
+        }
+        tab.evaluate(&injector::build_injector_call(&pending), true)
+            .map_err(|e| anyhow!("Injection Script Error: {}", e))?;
+        // Forms updates aria-checked after its own click handlers run
+        sleep(Duration::from_millis(300)).await;
+
+        let verified = tab.evaluate(&injector::build_verify_call(&pending), false)
+            .map_err(|e| anyhow!("Verification Script Error: {}", e))?;
+        let result = verified.value.as_ref().and_then(|v| v.as_str()).unwrap_or("[]").to_string();
+        pending = injector::failed_actions(&pending, &result)?;
+        if !pending.is_empty() {
+            println!("[*] {} action(s) not applied after pass {}", pending.len(), attempt);
+        }
+    }
+    Ok(pending)
+}
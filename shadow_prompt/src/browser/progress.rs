@@ -0,0 +1,145 @@
+//! Form Progress
+//! With `browser.resume`, the answers applied to each question are saved per form in
+//! data/form_progress/ as the flow goes. If the browser disconnects or ShadowPrompt exits
+//! mid-form, the next run replays those answers instead of asking the LLM again, so it
+//! gets back to where it stopped. The file is removed once a run reaches the last page.
+
+use super::injector::FormAction;
+use crate::config::get_exe_dir;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub const PROGRESS_DIR: &str = "form_progress";
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct FormProgress {
+    /// Question container ID -> the answer actions applied to it
+    questions: HashMap<String, Vec<FormAction>>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl FormProgress {
+    /// Saved progress for the form at `url`; empty (and never written) when disabled
+    pub fn load(enabled: bool, url: &str) -> Self {
+        let Some(key) = enabled.then(|| form_key(url)).flatten() else {
+            return Self::default();
+        };
+        let path = get_exe_dir().join("data").join(PROGRESS_DIR).join(format!("{}.json", key));
+        let mut progress: Self = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        if !progress.questions.is_empty() {
+            println!("[*] Resuming form: {} question(s) answered in an earlier run", progress.questions.len());
+        }
+        progress.path = Some(path);
+        progress
+    }
+
+    /// Split an extracted page into the answers to replay for questions answered in an
+    /// earlier run and the Form JSON of the questions still to ask the LLM
+    pub fn split_page(&self, form_json: &str) -> (Vec<FormAction>, String) {
+        let Ok(mut form) = serde_json::from_str::<Value>(form_json) else {
+            return (Vec::new(), form_json.to_string());
+        };
+        let mut replay = Vec::new();
+        if let Some(questions) = form["questions"].as_array_mut() {
+            questions.retain(|q| {
+                match q["container_id"].as_str().and_then(|id| self.questions.get(id)) {
+                    Some(actions) => {
+                        replay.extend(actions.iter().cloned());
+                        false
+                    }
+                    None => true,
+                }
+            });
+        }
+        let remaining = if replay.is_empty() { form_json.to_string() } else { form.to_string() };
+        (replay, remaining)
+    }
+
+    /// Remember the applied answers, grouped by question container, and save
+    pub fn record(&mut self, answered: Vec<(String, FormAction)>) {
+        if self.path.is_none() || answered.is_empty() {
+            return;
+        }
+        for (container, action) in answered {
+            let actions = self.questions.entry(container).or_default();
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+        if let Err(e) = self.save() {
+            eprintln!("[-] Could not save form progress: {}", e);
+        }
+    }
+
+    /// The form is done; the next run starts fresh
+    pub fn clear(&mut self) {
+        self.questions.clear();
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else { return Ok(()) };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// File name for a form: its ID from docs.google.com/forms/d/e/<id>/viewform
+fn form_key(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let segments: Vec<&str> = parsed.path_segments()?.collect();
+    let d = segments.iter().position(|s| *s == "d")?;
+    let id = match segments.get(d + 1) {
+        Some(&"e") => segments.get(d + 2)?,
+        other => other?,
+    };
+    let key: String = id.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_').collect();
+    (!key.is_empty()).then_some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::injector::ActionKind;
+
+    fn click(id: &str) -> FormAction {
+        FormAction { id: id.to_string(), action: ActionKind::Click, value: None }
+    }
+
+    #[test]
+    fn test_form_key() {
+        assert_eq!(form_key("https://docs.google.com/forms/d/e/1FAIpQLSd_x-9/viewform?usp=sf_link").as_deref(), Some("1FAIpQLSd_x-9"));
+        assert_eq!(form_key("https://docs.google.com/forms/d/abc123/edit").as_deref(), Some("abc123"));
+        assert_eq!(form_key("https://forms.gle/xyz"), None);
+    }
+
+    #[test]
+    fn test_split_page_replays_answered_questions() {
+        let mut progress = FormProgress::default();
+        progress.questions.insert("111".to_string(), vec![click("111_opt_2")]);
+
+        let form = r#"{"title":"Quiz","questions":[
+            {"index":0,"container_id":"111","text":"Answered before","options":[{"text":"C","id":"111_opt_2"}]},
+            {"index":1,"container_id":"222","text":"New","options":[{"text":"A","id":"222_opt_0"}]}],"navigation":[]}"#;
+        let (replay, remaining) = progress.split_page(form);
+        assert_eq!(replay, vec![click("111_opt_2")]);
+        let remaining: Value = serde_json::from_str(&remaining).unwrap();
+        assert_eq!(remaining["questions"].as_array().unwrap().len(), 1);
+        assert_eq!(remaining["questions"][0]["container_id"], "222");
+
+        // Recording is a no-op without a file to write to
+        progress.record(vec![("222".to_string(), click("222_opt_0"))]);
+        assert!(!progress.questions.contains_key("222"));
+    }
+}
@@ -0,0 +1,81 @@
+//! Form Run Log
+//! Every form flow writes data/form_runs/<start time>.jsonl (`browser.run_log`): one JSON
+//! object per line with a timestamp, the page, and what happened (questions extracted,
+//! actions planned, what the injector could and could not apply), so a run can be
+//! reconstructed afterwards.
+
+use crate::config::get_exe_dir;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const RUN_DIR: &str = "form_runs";
+
+pub struct RunLog {
+    /// None when disabled or the file could not be created
+    file: Option<File>,
+}
+
+impl RunLog {
+    pub fn start(enabled: bool, mode: &str, url: Option<&str>) -> Self {
+        if !enabled {
+            return Self { file: None };
+        }
+        let file = match create_file() {
+            Ok(file) => Some(file),
+            Err(e) => {
+                eprintln!("[-] Form run log disabled: {}", e);
+                None
+            }
+        };
+        let mut log = Self { file };
+        log.record("start", 0, json!({ "mode": mode, "url": url }));
+        log
+    }
+
+    /// Append one event; `data` fields are merged into the line
+    pub fn record(&mut self, event: &str, page: usize, data: Value) {
+        let Some(file) = self.file.as_mut() else { return };
+        let line = entry(&chrono::Local::now().to_rfc3339(), event, page, data);
+        if let Err(e) = writeln!(file, "{}", line) {
+            eprintln!("[-] Could not write form run log: {}", e);
+            self.file = None;
+        }
+    }
+}
+
+fn create_file() -> std::io::Result<File> {
+    let dir = get_exe_dir().join("data").join(RUN_DIR);
+    std::fs::create_dir_all(&dir)?;
+    let stamp = chrono::Local::now().format("%Y-%m-%d_%H%M%S").to_string();
+    let mut path: PathBuf = dir.join(format!("{}.jsonl", stamp));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}_{}.jsonl", stamp, n));
+        n += 1;
+    }
+    File::create(path)
+}
+
+fn entry(timestamp: &str, event: &str, page: usize, data: Value) -> Value {
+    let mut line = json!({ "ts": timestamp, "event": event, "page": page });
+    if let (Some(line), Value::Object(fields)) = (line.as_object_mut(), data) {
+        line.extend(fields);
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_merges_fields() {
+        let line = entry("2026-01-01T00:00:00+00:00", "injected", 2, json!({ "failed": ["q1_input"] }));
+        assert_eq!(line["event"], "injected");
+        assert_eq!(line["failed"][0], "q1_input");
+        assert_eq!(line["ts"], "2026-01-01T00:00:00+00:00");
+        assert_eq!(entry("t", "finished", 3, Value::Null)["page"], 3);
+    }
+}
@@ -0,0 +1,331 @@
+//! Whole-Form Strategy
+//! `browser.strategy = "whole_form"` (auto mode): every page is read first by clicking Next
+//! without answering, all questions go to the LLM in one request so related answers agree,
+//! then the form is reloaded and the answers are filled in page by page. Forms with
+//! required questions cannot be paged through unanswered; use the per-page strategy there.
+
+use super::injector::{self, FormAction, FormSchema};
+use super::run_log::RunLog;
+use super::progress::FormProgress;
+use super::{
+    check_access, debug_overlay, hold_while_paused, images, inject_verified, page_state, record_applied, replay_saved,
+    wait_for_next_page, FlowControl, MARK_PAGE_JS, MAX_ACTION_ATTEMPTS,
+};
+use crate::capabilities::ModelCapabilities;
+use crate::config::Config;
+use crate::i18n::{t, tf};
+use crate::ui::UICommand;
+use anyhow::{anyhow, Result};
+use headless_chrome::Tab;
+use serde_json::{json, Value};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+pub const STRATEGY: &str = "whole_form";
+
+pub(super) async fn run(
+    tab: &Arc<Tab>,
+    config: &Config,
+    llm_config: &Config,
+    ui_tx: &Sender<UICommand>,
+    log: &mut RunLog,
+    control: &FlowControl,
+    progress: &mut FormProgress,
+) -> Result<()> {
+    let start_url = tab.get_url();
+    let send_ui = debug_overlay(ui_tx, config.general.debug);
+
+    // 1. Read every page without answering; questions answered in an interrupted run are
+    // replayed, not asked again
+    let mut pages: Vec<(String, FormSchema)> = Vec::new();
+    let mut replays: Vec<Vec<FormAction>> = Vec::new();
+    loop {
+        let page = pages.len() + 1;
+        check_access(tab, ui_tx, log, page)?;
+        send_ui(tf("browser.reading_page", &[("page", &page.to_string())]));
+        let form_json = super::extract_page(tab)?;
+        log.record("extracted", page, json!({
+            "url": tab.get_url(),
+            "form": serde_json::from_str::<Value>(&form_json).unwrap_or_else(|_| Value::String(form_json.clone())),
+        }));
+        let schema = FormSchema::from_form_json(&form_json)?;
+        let next = schema.next_button();
+        let (replay, pending_json) = replay_saved(progress, &schema, &form_json, page)?;
+        pages.push((pending_json, schema));
+        replays.push(replay);
+
+        let Some(next) = next else { break };
+        if pages.len() >= config.browser.max_pages {
+            return Err(anyhow!("Pagination limit exceeded ({} pages max). Aborting.", config.browser.max_pages));
+        }
+        if !click_next(tab, &next, config).await? {
+            return Err(anyhow!(
+                "Page {} does not advance without answers (required questions?). Use the per-page strategy for this form.",
+                page
+            ));
+        }
+    }
+
+    // 2. One request for the whole form
+    send_ui(tf("browser.calculating_pages", &[("count", &pages.len().to_string())]));
+    let prompt = build_prompt(&pages);
+    let question_images = if ModelCapabilities::supports_vision(llm_config) {
+        page_images(tab, &pages).await
+    } else {
+        Vec::new()
+    };
+    let image_urls: Vec<String> = question_images.iter().map(|(_, url)| url.clone()).collect();
+    let prompt = if question_images.is_empty() {
+        prompt
+    } else {
+        let owners: Vec<&str> = question_images.iter().map(|(owner, _)| owner.as_str()).collect();
+        format!("{}\n\nThe attached images, in order, belong to: {}.", prompt, owners.join(", "))
+    };
+
+    let schemas: Vec<&FormSchema> = pages.iter().map(|(_, schema)| schema).collect();
+    let mut retry_prompt = prompt.clone();
+    let mut planned = None;
+    for attempt in 1..=MAX_ACTION_ATTEMPTS {
+        let llm_res = if image_urls.is_empty() {
+            crate::llm::LlmClient::query(&retry_prompt, llm_config).await?
+        } else {
+            crate::llm::LlmClient::query_with_images(&retry_prompt, &image_urls, llm_config).await?
+        };
+        let raw_actions = llm_res.replace("```json", "").replace("```", "").trim().to_string();
+        println!("\n[DEBUG] LLM OUTPUT (attempt {}):\n{}", attempt, raw_actions);
+
+        match split_by_page(&schemas, &raw_actions) {
+            Ok(valid) => {
+                planned = Some(valid);
+                break;
+            }
+            Err(e) => {
+                eprintln!("[-] Rejected LLM actions: {}", e);
+                error!("Form flow rejected LLM actions (attempt {}): {}", attempt, e);
+                retry_prompt = format!(
+                    "{}\n\nYour previous answer was rejected: {}. Use only IDs from the Form JSON and return ONLY the JSON array.",
+                    prompt, e
+                );
+            }
+        }
+    }
+    let planned = planned.ok_or_else(|| anyhow!("LLM returned invalid actions {} times. Aborting.", MAX_ACTION_ATTEMPTS))?;
+    let planned: Vec<Vec<FormAction>> = replays.into_iter().zip(planned).map(|(replay, actions)| [replay, actions].concat()).collect();
+    for (i, actions) in planned.iter().enumerate() {
+        log.record("planned", i + 1, json!({ "actions": actions }));
+    }
+
+    if config.browser.dry_run {
+        let preview: Vec<String> = pages
+            .iter()
+            .zip(&planned)
+            .enumerate()
+            .map(|(i, ((_, schema), actions))| tf("browser.preview_page_heading", &[("page", &(i + 1).to_string()), ("preview", &schema.preview(actions))]))
+            .collect();
+        let preview = tf("browser.preview_whole_form", &[("preview", &preview.join("\n"))]);
+        println!("\n[DRY RUN]\n{}", preview);
+        if let Err(e) = crate::clipboard::ClipboardManager::write(&preview) {
+            eprintln!("[-] Could not copy the preview: {}", e);
+        }
+        let _ = ui_tx.send(UICommand::SetOverlayText(preview));
+        log.record("dry_run", 0, Value::Null);
+        return Ok(());
+    }
+
+    // 3. Back to the first page and fill it in
+    send_ui(t("browser.returning_to_first_page"));
+    tab.navigate_to(&start_url)
+        .map_err(|e| anyhow!("Failed to navigate: {}", e))?;
+    tab.wait_until_navigated()
+        .map_err(|e| anyhow!("Navigation timeout: {}", e))?;
+    sleep(Duration::from_secs(2)).await;
+
+    let cache_scope = crate::answer_cache::Scope::new(crate::answer_cache::Caller::Form, llm_config, "");
+    for (i, actions) in planned.iter().enumerate() {
+        let page = i + 1;
+        check_access(tab, ui_tx, log, page)?;
+        send_ui(tf("browser.injecting_page", &[("page", &page.to_string())]));
+
+        // Re-read the page so the extractor assigns the same IDs on the fresh document
+        let schema = FormSchema::from_form_json(&super::extract_page(tab)?)?;
+        let (present, missing): (Vec<FormAction>, Vec<FormAction>) =
+            actions.iter().cloned().partition(|a| schema.question_number(a).is_some());
+        if !missing.is_empty() {
+            println!("[*] Page {}: {} planned answer(s) not on the page (already answered?)", page, missing.len());
+        }
+        let (present, skipped) = hold_while_paused(control, ui_tx, &schema, present, page).await;
+        if !skipped.is_empty() {
+            log.record("skipped", page, json!({ "skipped": skipped }));
+        }
+        let unfilled = inject_verified(tab, &present).await?;
+        let failed: Vec<&str> = unfilled.iter().map(|a| a.id.as_str()).collect();
+        log.record("injected", page, json!({ "failed": failed, "missing": missing }));
+        let applied: Vec<FormAction> = present.iter().filter(|a| !failed.contains(&a.id.as_str())).cloned().collect();
+        record_applied(progress, &schema, applied.iter());
+        if config.general.answer_cache {
+            for (question, answer) in schema.answered_pairs(&applied) {
+                crate::answer_cache::insert(&cache_scope, &question, &answer);
+            }
+        }
+        if !unfilled.is_empty() {
+            let msg = tf("browser.unfilled_answers", &[("page", &page.to_string()), ("count", &unfilled.len().to_string())]);
+            eprintln!("[-] {}", msg);
+            error!("Form flow: {}", msg);
+            let _ = ui_tx.send(UICommand::SetOverlayText(msg));
+        }
+
+        if page == planned.len() {
+            break;
+        }
+        let next = schema.next_button().ok_or_else(|| anyhow!("Page {} has no Next button on the second pass. Aborting.", page))?;
+        if !click_next(tab, &next, config).await? {
+            return Err(anyhow!("Still on page {} after clicking Next (is a required question unanswered?). Aborting.", page));
+        }
+    }
+
+    progress.clear();
+    send_ui(t("browser.complete"));
+    Ok(())
+}
+
+/// Click Next and wait for the new page to render; false when the page didn't change
+async fn click_next(tab: &Arc<Tab>, next: &FormAction, config: &Config) -> Result<bool> {
+    tab.evaluate(MARK_PAGE_JS, false)
+        .map_err(|e| anyhow!("Injection Script Error: {}", e))?;
+    let Some(before) = page_state(tab) else {
+        return Ok(false);
+    };
+    tab.evaluate(&injector::build_injector_call(std::slice::from_ref(next)), true)
+        .map_err(|e| anyhow!("Injection Script Error: {}", e))?;
+    if !wait_for_next_page(tab, &before, Duration::from_secs(config.browser.page_timeout_secs)).await {
+        return Ok(false);
+    }
+    sleep(Duration::from_millis(config.browser.page_settle_ms)).await;
+    Ok(true)
+}
+
+fn build_prompt(pages: &[(String, FormSchema)]) -> String {
+    let pages_json: Vec<Value> = pages
+        .iter()
+        .enumerate()
+        .map(|(i, (form_json, _))| {
+            let form: Value = serde_json::from_str(form_json).unwrap_or(Value::Null);
+            let mut questions = form["questions"].clone();
+            qualify_ids(&mut questions, i + 1);
+            json!({ "page": i + 1, "title": form["title"], "questions": questions })
+        })
+        .collect();
+    format!(
+        "You are an automated quiz solver filling out a multi-page Google Form.
+Below is EVERY page of the form. Read all of it first: later questions may depend on earlier ones, so keep your answers consistent across pages.
+Answer every question on every page. Do NOT include any navigation buttons; paging is handled for you.
+Grid questions list their `rows`; click exactly one option per row of a `grid`, one or more per row of a `checkbox_grid`.
+Return ONLY one JSON array of actions for all pages. Actions must be strictly formatted as: [{{\"id\": \"element_id\", \"action\": \"click\"}}, {{\"id\": \"element_id\", \"action\": \"type\", \"value\": \"text here\"}}]. Do NOT return markdown or explanation.
+Form pages JSON:\n{}",
+        Value::Array(pages_json)
+    )
+}
+
+/// The extractor numbers IDs per page, so page 2 repeats page 1's "q0_opt_0". In the
+/// whole-form prompt every ID carries its page: "p2_q0_opt_0".
+fn qualify_ids(value: &mut Value, page: usize) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                match field {
+                    Value::String(id) if key == "id" => *id = format!("p{}_{}", page, id),
+                    _ => qualify_ids(field, page),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| qualify_ids(item, page)),
+        _ => {}
+    }
+}
+
+/// The page (0-based) and the page's own ID of a "p2_q0_opt_0" ID
+fn unqualify_id(id: &str) -> Option<(usize, &str)> {
+    let (page, id) = id.strip_prefix('p')?.split_once('_')?;
+    let page: usize = page.parse().ok()?;
+    Some((page.checked_sub(1)?, id))
+}
+
+/// Images of all pages, labelled "page P question I", up to `images::MAX_IMAGES` in total
+async fn page_images(tab: &Arc<Tab>, pages: &[(String, FormSchema)]) -> Vec<(String, String)> {
+    let mut labelled = Vec::new();
+    for (i, (form_json, _)) in pages.iter().enumerate() {
+        let budget = images::MAX_IMAGES.saturating_sub(labelled.len());
+        let urls: Vec<(u64, String)> = images::image_urls(form_json).into_iter().take(budget).collect();
+        for image in images::download(tab, &urls).await {
+            labelled.push((format!("page {} question {}", i + 1, image.question), image.data_url));
+        }
+    }
+    labelled
+}
+
+/// Assign each planned action to the page its ID names, back with the page's own ID, then
+/// validate every page's share like a single-page answer (navigation is never allowed here)
+fn split_by_page(schemas: &[&FormSchema], raw_actions: &str) -> Result<Vec<Vec<FormAction>>> {
+    let actions: Vec<FormAction> = serde_json::from_str(raw_actions)
+        .map_err(|e| anyhow!("Output is not a valid action array: {}", e))?;
+    let mut by_page: Vec<Vec<FormAction>> = vec![Vec::new(); schemas.len()];
+    for (i, action) in actions.into_iter().enumerate() {
+        let not_found = || anyhow!("Action {}: \"{}\" is not a question on any page", i, action.id);
+        let (page, id) = unqualify_id(&action.id).filter(|(page, _)| *page < schemas.len()).ok_or_else(not_found)?;
+        let action = FormAction { id: id.to_string(), ..action.clone() };
+        if schemas[page].question_number(&action).is_none() {
+            return Err(not_found());
+        }
+        by_page[page].push(action);
+    }
+    for (schema, actions) in schemas.iter().zip(&mut by_page) {
+        *actions = schema.validate(&serde_json::to_string(actions)?, false)?;
+    }
+    Ok(by_page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_by_page() {
+        let page1 = FormSchema::from_form_json(r#"{"questions":[
+            {"index":0,"type":"radio","text":"Pick","options":[{"text":"A","id":"q0_opt_0"}]}],
+            "navigation":[{"type":"next","id":"nav_next_btn"}]}"#).unwrap();
+        let page2 = FormSchema::from_form_json(r#"{"questions":[
+            {"index":0,"type":"text","text":"Why?","id":"q5_input"}],
+            "navigation":[{"type":"submit","id":"nav_submit_btn"}]}"#).unwrap();
+        let schemas = [&page1, &page2];
+
+        let raw = r#"[{"id":"p2_q5_input","action":"type","value":"Because"},{"id":"p1_q0_opt_0","action":"click"}]"#;
+        let split = split_by_page(&schemas, raw).unwrap();
+        assert_eq!(split[0].len(), 1);
+        assert_eq!(split[1][0].id, "q5_input");
+
+        assert!(split_by_page(&schemas, r#"[{"id":"p1_nav_next_btn","action":"click"}]"#).is_err());
+        assert!(split_by_page(&schemas, r#"[{"id":"p2_q5_input","action":"type"}]"#).is_err());
+        assert!(split_by_page(&schemas, r#"[{"id":"q0_opt_0","action":"click"}]"#).is_err());
+        assert!(split_by_page(&schemas, r#"[{"id":"p3_q0_opt_0","action":"click"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_pages_with_the_same_ids() {
+        let page = r#"{"questions":[
+            {"index":0,"type":"radio","text":"Pick","options":[{"text":"A","id":"q0_opt_0"},{"text":"B","id":"q0_opt_1"}]}]}"#;
+        let pages = vec![
+            (page.to_string(), FormSchema::from_form_json(page).unwrap()),
+            (page.to_string(), FormSchema::from_form_json(page).unwrap()),
+        ];
+        let prompt = build_prompt(&pages);
+        assert!(prompt.contains("\"p1_q0_opt_0\"") && prompt.contains("\"p2_q0_opt_1\""));
+        assert!(!prompt.contains("\"q0_opt_0\""));
+
+        let schemas: Vec<&FormSchema> = pages.iter().map(|(_, schema)| schema).collect();
+        let raw = r#"[{"id":"p1_q0_opt_1","action":"click"},{"id":"p2_q0_opt_0","action":"click"}]"#;
+        let split = split_by_page(&schemas, raw).unwrap();
+        assert_eq!(split[0].iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), ["q0_opt_1"]);
+        assert_eq!(split[1].iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), ["q0_opt_0"]);
+    }
+}
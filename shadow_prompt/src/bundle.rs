@@ -0,0 +1,241 @@
+//! Settings Bundle
+//! `--export-settings <file.zip>` packs config.toml, system_prompt.txt and the setup marker
+//! (plus the knowledge folder with `--with-knowledge`) so a tuned setup can be moved to
+//! another USB stick in one step; `--without-keys` blanks every `api_key` first.
+//! `--import-settings <file.zip>` unpacks it next to the exe, keeping config.toml.bak.
+
+use crate::config::{get_exe_dir, rewrite_key_values, Config};
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Files under config/ that go into every bundle (when present)
+const CONFIG_FILES: &[&str] = &["config.toml", "system_prompt.txt", ".setup_complete"];
+
+/// Folder inside the zip for the knowledge files, whatever `rag.knowledge_path` is called
+const KNOWLEDGE_DIR: &str = "knowledge";
+
+pub enum Command {
+    Export { path: PathBuf, with_knowledge: bool, without_keys: bool },
+    Import { path: PathBuf },
+}
+
+impl Command {
+    pub fn from_args(args: &[String]) -> Option<Result<Self>> {
+        let value_of = |flag: &str| {
+            let position = args.iter().position(|a| a == flag)?;
+            Some(
+                args.get(position + 1)
+                    .filter(|a| !a.starts_with("--"))
+                    .map(PathBuf::from)
+                    .ok_or_else(|| anyhow!("{} needs a .zip path", flag)),
+            )
+        };
+        if let Some(path) = value_of("--export-settings") {
+            return Some(path.map(|path| Command::Export {
+                path,
+                with_knowledge: args.iter().any(|a| a == "--with-knowledge"),
+                without_keys: args.iter().any(|a| a == "--without-keys"),
+            }));
+        }
+        value_of("--import-settings").map(|path| path.map(|path| Command::Import { path }))
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let root = get_exe_dir();
+        match self {
+            Command::Export { path, with_knowledge, without_keys } => {
+                let knowledge = with_knowledge.then(|| root.join(Config::load_raw().unwrap_or_default().rag.knowledge_path));
+                let count = export(&root, knowledge.as_deref(), path, *without_keys)?;
+                println!("[+] Exported {} file(s) to {}", count, path.display());
+                if *without_keys {
+                    println!("[*] API keys were left out; enter them again after importing.");
+                }
+            }
+            Command::Import { path } => {
+                let count = import(&root, path)?;
+                println!("[+] Imported {} file(s) from {}", count, path.display());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn export(root: &Path, knowledge: Option<&Path>, out: &Path, without_keys: bool) -> Result<usize> {
+    let mut zip = zip::ZipWriter::new(fs::File::create(out).context("Failed to create the bundle")?);
+    let options = zip::write::SimpleFileOptions::default();
+    let mut count = 0;
+
+    let config_dir = root.join("config");
+    let mut config_files: Vec<&str> = CONFIG_FILES.to_vec();
+    // ${VAR} keys resolve from config/.env, so it only travels along with the keys
+    if !without_keys {
+        config_files.push(".env");
+    }
+    for name in config_files {
+        let Ok(mut content) = fs::read(config_dir.join(name)) else { continue };
+        if name == "config.toml" && without_keys {
+            content = strip_keys(&String::from_utf8_lossy(&content))?.into_bytes();
+        }
+        zip.start_file(format!("config/{}", name), options)?;
+        zip.write_all(&content)?;
+        count += 1;
+    }
+
+    if let Some(knowledge) = knowledge {
+        for file in walk(knowledge)? {
+            let relative = file.strip_prefix(knowledge)?.to_string_lossy().replace('\\', "/");
+            zip.start_file(format!("{}/{}", KNOWLEDGE_DIR, relative), options)?;
+            zip.write_all(&fs::read(&file)?)?;
+            count += 1;
+        }
+    }
+    zip.finish()?;
+    Ok(count)
+}
+
+fn import(root: &Path, bundle: &Path) -> Result<usize> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(bundle).context("Failed to open the bundle")?)
+        .context("Not a valid settings bundle (zip) file")?;
+
+    let config_path = root.join("config").join("config.toml");
+    if config_path.exists() && archive.by_name("config/config.toml").is_ok() {
+        fs::copy(&config_path, config_path.with_extension("toml.bak"))?;
+    }
+
+    // Knowledge entries go wherever the imported config keeps its knowledge folder
+    let mut knowledge = Vec::new();
+    let mut count = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(name) = entry.enclosed_name() else { continue };
+        if entry.is_dir() {
+            continue;
+        }
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        if let Ok(relative) = name.strip_prefix(KNOWLEDGE_DIR) {
+            knowledge.push((relative.to_path_buf(), content));
+            continue;
+        }
+        let file = name.file_name().and_then(|f| f.to_str()).unwrap_or_default();
+        if name.parent() != Some(Path::new("config")) || !(CONFIG_FILES.contains(&file) || file == ".env") {
+            continue;
+        }
+        write_file(&root.join(&name), &content)?;
+        count += 1;
+    }
+
+    if !knowledge.is_empty() {
+        let knowledge_path = fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| toml::from_str::<Config>(&content).ok())
+            .map(|config| config.rag.knowledge_path)
+            .unwrap_or_else(|| KNOWLEDGE_DIR.to_string());
+        let knowledge_dir = root.join(import_knowledge_dir(&knowledge_path));
+        for (relative, content) in knowledge {
+            write_file(&knowledge_dir.join(relative), &content)?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+/// Where imported knowledge files go: the bundle's own `knowledge_path` if it stays inside
+/// the ShadowPrompt folder, else `knowledge/` (a shared bundle must not write elsewhere)
+fn import_knowledge_dir(knowledge_path: &str) -> &str {
+    let path = Path::new(knowledge_path);
+    let inside = !knowledge_path.trim().is_empty()
+        && !knowledge_path.contains(':')
+        && path.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if inside {
+        knowledge_path
+    } else {
+        eprintln!("[!] Bundle knowledge_path '{}' leaves the ShadowPrompt folder; importing into {}/", knowledge_path, KNOWLEDGE_DIR);
+        KNOWLEDGE_DIR
+    }
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn walk(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(walk(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// config.toml with every `api_key` / `*_api_key` value emptied, encrypted ones included
+fn strip_keys(content: &str) -> Result<String> {
+    rewrite_key_values(content, |_| Ok(Some(String::new())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_keys() {
+        let content = "[models]\napi_key = \"gsk_secret\"  # groq\nopenrouter_api_key = \"enc:AQAA\"\nprovider = \"groq\"\n";
+        assert_eq!(
+            strip_keys(content).unwrap(),
+            "[models]\napi_key = \"\"  # groq\nopenrouter_api_key = \"\"\nprovider = \"groq\"\n"
+        );
+
+        // Literal, escaped and multi-line strings hold keys just as well
+        let content = "api_key = 'gsk_secret' # groq\nserper_api_key = \"a\\\"b\"\nbing_api_key = \"\"\"\nabc\ndef\"\"\"\nmodel_id = 'llama'\n";
+        assert_eq!(
+            strip_keys(content).unwrap(),
+            "api_key = \"\" # groq\nserper_api_key = \"\"\nbing_api_key = \"\"\nmodel_id = 'llama'\n"
+        );
+    }
+
+    #[test]
+    fn test_import_knowledge_dir() {
+        assert_eq!(import_knowledge_dir("notes/bio"), "notes/bio");
+        assert_eq!(import_knowledge_dir("./knowledge"), "./knowledge");
+        assert_eq!(import_knowledge_dir("../.."), KNOWLEDGE_DIR);
+        assert_eq!(import_knowledge_dir("notes/../../x"), KNOWLEDGE_DIR);
+        assert_eq!(import_knowledge_dir("C:/Users/x/AppData/Roaming"), KNOWLEDGE_DIR);
+        assert_eq!(import_knowledge_dir("/etc"), KNOWLEDGE_DIR);
+        assert_eq!(import_knowledge_dir(""), KNOWLEDGE_DIR);
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let temp = std::env::temp_dir().join(format!("shadow_prompt_test_{}", uuid::Uuid::new_v4()));
+        let (source, target) = (temp.join("source"), temp.join("target"));
+        write_file(&source.join("config/config.toml"), b"[models]\napi_key = \"gsk_secret\"\n")?;
+        write_file(&source.join("config/.env"), b"GROQ_API_KEY=gsk_secret\n")?;
+        write_file(&source.join("knowledge/bio/cells.md"), b"Cells are small.")?;
+        write_file(&target.join("config/config.toml"), b"# old\n")?;
+
+        let bundle = temp.join("bundle.zip");
+        assert_eq!(export(&source, Some(&source.join("knowledge")), &bundle, true)?, 2);
+        assert_eq!(import(&target, &bundle)?, 2);
+
+        assert_eq!(fs::read_to_string(target.join("config/config.toml"))?, "[models]\napi_key = \"\"\n");
+        assert_eq!(fs::read_to_string(target.join("config/config.toml.bak"))?, "# old\n");
+        assert!(!target.join("config/.env").exists());
+        assert_eq!(fs::read_to_string(target.join("knowledge/bio/cells.md"))?, "Cells are small.");
+
+        let _ = fs::remove_dir_all(temp);
+        Ok(())
+    }
+}
@@ -51,7 +51,6 @@ impl ModelCapabilities {
         }
     }
 
-    #[allow(dead_code)]
     pub fn get_current_model_id(config: &Config) -> Option<String> {
         match config.models.provider.as_str() {
             "groq" => config.models.groq.as_ref().map(|c| c.model_id.clone()),
@@ -0,0 +1,149 @@
+//! Windows Graphics Capture
+//! Alternative to BitBlt for screen regions. BitBlt returns black on some
+//! hardware-accelerated/protected surfaces and washed-out pixels on HDR displays;
+//! WGC reads the compositor's output instead. Requires Windows 10 1903+.
+
+use anyhow::{Context, Result};
+use std::time::{Duration, Instant};
+use windows::core::Interface;
+use windows::Graphics::Capture::{Direct3D11CaptureFramePool, GraphicsCaptureItem};
+use windows::Graphics::DirectX::Direct3D11::IDirect3DDevice;
+use windows::Graphics::DirectX::DirectXPixelFormat;
+use windows::Win32::Foundation::{HMODULE, POINT};
+use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_HARDWARE;
+use windows::Win32::Graphics::Direct3D11::{
+    D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, ID3D11Texture2D, D3D11_BOX,
+    D3D11_CPU_ACCESS_READ, D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_MAPPED_SUBRESOURCE,
+    D3D11_MAP_READ, D3D11_SDK_VERSION, D3D11_TEXTURE2D_DESC, D3D11_USAGE_STAGING,
+};
+use windows::Win32::Graphics::Dxgi::Common::{DXGI_FORMAT_B8G8R8A8_UNORM, DXGI_SAMPLE_DESC};
+use windows::Win32::Graphics::Dxgi::IDXGIDevice;
+use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITOR_DEFAULTTONEAREST};
+use windows::Win32::System::WinRT::Direct3D11::{CreateDirect3D11DeviceFromDXGIDevice, IDirect3DDxgiInterfaceAccess};
+use windows::Win32::System::WinRT::Graphics::Capture::IGraphicsCaptureItemInterop;
+
+const FRAME_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Capture a screen region as top-down BGRA pixels (same layout as the BitBlt path).
+/// The region must lie on a single monitor.
+pub fn capture_pixels(x: i32, y: i32, width: i32, height: i32) -> Result<Vec<u8>> {
+    unsafe {
+        // 1. Monitor containing the region
+        let monitor = MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST);
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            anyhow::bail!("GetMonitorInfoW failed");
+        }
+        let bounds = info.rcMonitor;
+        let (left, top) = (x - bounds.left, y - bounds.top);
+        if left < 0 || top < 0 || x + width > bounds.right || y + height > bounds.bottom {
+            anyhow::bail!("Region spans multiple monitors");
+        }
+
+        // 2. D3D11 device shared with WinRT
+        let mut device: Option<ID3D11Device> = None;
+        let mut context: Option<ID3D11DeviceContext> = None;
+        D3D11CreateDevice(
+            None,
+            D3D_DRIVER_TYPE_HARDWARE,
+            HMODULE::default(),
+            D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+            None,
+            D3D11_SDK_VERSION,
+            Some(&mut device),
+            None,
+            Some(&mut context),
+        )?;
+        let device = device.context("D3D11 device missing")?;
+        let context = context.context("D3D11 context missing")?;
+        let dxgi_device: IDXGIDevice = device.cast()?;
+        let winrt_device: IDirect3DDevice = CreateDirect3D11DeviceFromDXGIDevice(&dxgi_device)?.cast()?;
+
+        // 3. Capture one frame of the monitor
+        let interop = windows::core::factory::<GraphicsCaptureItem, IGraphicsCaptureItemInterop>()?;
+        let item: GraphicsCaptureItem = interop.CreateForMonitor(monitor)?;
+        let frame_pool = Direct3D11CaptureFramePool::CreateFreeThreaded(
+            &winrt_device,
+            DirectXPixelFormat::B8G8R8A8UIntNormalized,
+            1,
+            item.Size()?,
+        )?;
+        let session = frame_pool.CreateCaptureSession(&item)?;
+        // Not available before Windows 10 2004; the cursor just ends up in the frame there
+        let _ = session.SetIsCursorCaptureEnabled(false);
+        session.StartCapture()?;
+
+        let started = Instant::now();
+        let frame = loop {
+            if let Ok(frame) = frame_pool.TryGetNextFrame() {
+                break frame;
+            }
+            if started.elapsed() > FRAME_TIMEOUT {
+                let _ = session.Close();
+                let _ = frame_pool.Close();
+                anyhow::bail!("Timed out waiting for a capture frame");
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+
+        let access: IDirect3DDxgiInterfaceAccess = frame.Surface()?.cast()?;
+        let source: ID3D11Texture2D = access.GetInterface()?;
+
+        // 4. Copy the region into a CPU-readable texture
+        let desc = D3D11_TEXTURE2D_DESC {
+            Width: width as u32,
+            Height: height as u32,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: DXGI_FORMAT_B8G8R8A8_UNORM,
+            SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
+            Usage: D3D11_USAGE_STAGING,
+            BindFlags: 0,
+            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32,
+            MiscFlags: 0,
+        };
+        let mut staging: Option<ID3D11Texture2D> = None;
+        device.CreateTexture2D(&desc, None, Some(&mut staging))?;
+        let staging = staging.context("Staging texture missing")?;
+
+        let region = D3D11_BOX {
+            left: left as u32,
+            top: top as u32,
+            front: 0,
+            right: (left + width) as u32,
+            bottom: (top + height) as u32,
+            back: 1,
+        };
+        context.CopySubresourceRegion(&staging, 0, 0, 0, 0, &source, 0, Some(&region));
+
+        let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+        context.Map(&staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))?;
+
+        // Rows are padded to RowPitch; repack tightly
+        let row_bytes = (width * 4) as usize;
+        let mut pixels = vec![0u8; row_bytes * height as usize];
+        let src = mapped.pData as *const u8;
+        for row in 0..height as usize {
+            std::ptr::copy_nonoverlapping(
+                src.add(row * mapped.RowPitch as usize),
+                pixels.as_mut_ptr().add(row * row_bytes),
+                row_bytes,
+            );
+        }
+        context.Unmap(&staging, 0);
+
+        let _ = frame.Close();
+        let _ = session.Close();
+        let _ = frame_pool.Close();
+
+        // WGC leaves alpha undefined on some drivers; OCR and PNG encoding expect opaque pixels
+        for px in pixels.chunks_exact_mut(4) {
+            px[3] = 255;
+        }
+
+        Ok(pixels)
+    }
+}
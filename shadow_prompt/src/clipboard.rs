@@ -1,7 +1,41 @@
 use arboard::Clipboard;
 use anyhow::Result;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
 use std::thread;
 use std::time::Duration;
+use windows::core::w;
+use windows::Win32::Foundation::{HGLOBAL, HWND};
+use windows::Win32::System::DataExchange::{
+    CloseClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard, RegisterClipboardFormatW,
+};
+use windows::Win32::System::Memory::{GlobalLock, GlobalSize, GlobalUnlock};
+
+/// Recent texts ShadowPrompt put on (or took from) the clipboard, newest first
+struct History {
+    entries: VecDeque<String>,
+    capacity: usize,
+    /// Position of the last entry restored by `cycle_history`
+    cursor: Option<usize>,
+}
+
+/// The user's clipboard from before an answer was written, waiting to be put back
+struct PendingRestore {
+    generation: u64,
+    original: String,
+    written: String,
+}
+
+static PENDING_RESTORE: Mutex<Option<PendingRestore>> = Mutex::new(None);
+static RESTORE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+static HISTORY: Mutex<History> = Mutex::new(History {
+    entries: VecDeque::new(),
+    capacity: 0,
+    cursor: None,
+});
 
 pub struct ClipboardManager {
     // arboard::Clipboard is not thread safe by default in all OS, but we can wrap it
@@ -24,7 +58,37 @@ impl ClipboardManager {
         anyhow::bail!("Failed to read clipboard")
     }
 
+    /// Read a copied question, preferring the CF_HTML version (browsers) since its
+    /// structure survives better than the plain-text flattening
+    pub fn read_question() -> Result<String> {
+        if let Some(text) = read_html().map(|html| html_to_text(&html)).filter(|t| !t.trim().is_empty()) {
+            return Ok(text);
+        }
+        Self::read()
+    }
+
     pub fn write(text: &str) -> Result<()> {
+        Self::write_raw(text)?;
+        Self::remember(text);
+        Ok(())
+    }
+
+    /// Write an answer as HTML (bold, line breaks) with the plain text as fallback format
+    pub fn write_formatted(text: &str) -> Result<()> {
+        let html = text_to_html(text);
+        for _ in 0..3 {
+            if let Ok(mut clipboard) = Clipboard::new() {
+                if clipboard.set_html(html.as_str(), Some(text)).is_ok() {
+                    Self::remember(text);
+                    return Ok(());
+                }
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        anyhow::bail!("Failed to write to clipboard")
+    }
+
+    fn write_raw(text: &str) -> Result<()> {
         for _ in 0..3 {
             if let Ok(mut clipboard) = Clipboard::new() {
                 if clipboard.set_text(text).is_ok() {
@@ -39,6 +103,220 @@ impl ClipboardManager {
     pub fn clear() -> Result<()> {
         // Clearing is writing a space or empty string
         // Windows doesn't like empty clipboard sometimes, but we can try empty string
-        Self::write(" ") 
+        let mut history = HISTORY.lock().unwrap();
+        history.entries.clear();
+        history.cursor = None;
+        drop(history);
+        Self::write_raw(" ")
+    }
+
+    /// Remember `original` so it can replace `written` later. Returns a generation
+    /// id; a newer call supersedes older ones.
+    pub fn arm_restore(original: String, written: &str) -> u64 {
+        let generation = RESTORE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+        *PENDING_RESTORE.lock().unwrap() = Some(PendingRestore {
+            generation,
+            original,
+            written: written.to_string(),
+        });
+        generation
+    }
+
+    /// Put the original clipboard back if it is still pending (and matches `generation`
+    /// when given). Skipped when the user has copied something else in the meantime.
+    pub fn restore_pending(generation: Option<u64>) -> Result<bool> {
+        let mut pending = PENDING_RESTORE.lock().unwrap();
+        let matches = pending.as_ref().is_some_and(|p| generation.map_or(true, |g| g == p.generation));
+        if !matches {
+            return Ok(false);
+        }
+        let restore = pending.take().unwrap();
+        drop(pending);
+
+        if Self::read().ok().as_deref() != Some(restore.written.as_str()) {
+            return Ok(false);
+        }
+        Self::write_raw(&restore.original)?;
+        Ok(true)
+    }
+
+    /// Keep up to `capacity` entries for `cycle_history` (0 disables the history)
+    pub fn init_history(capacity: usize) {
+        let mut history = HISTORY.lock().unwrap();
+        history.capacity = capacity;
+        history.entries.truncate(capacity);
+    }
+
+    /// Add text to the history without touching the clipboard (e.g. the question itself)
+    pub fn remember(text: &str) {
+        let mut history = HISTORY.lock().unwrap();
+        if history.capacity == 0 || text.trim().is_empty() {
+            return;
+        }
+        history.cursor = None;
+        if history.entries.front().map(String::as_str) == Some(text) {
+            return;
+        }
+        history.entries.push_front(text.to_string());
+        let capacity = history.capacity;
+        history.entries.truncate(capacity);
+    }
+
+    /// Put the next-older history entry back on the clipboard, wrapping around.
+    /// Returns (1-based position, entry count) or None when the history is empty.
+    pub fn cycle_history() -> Result<Option<(usize, usize)>> {
+        let mut history = HISTORY.lock().unwrap();
+        if history.entries.is_empty() {
+            return Ok(None);
+        }
+
+        let next = match history.cursor {
+            Some(i) => (i + 1) % history.entries.len(),
+            // The newest entry is usually what is on the clipboard already
+            None if history.entries.len() > 1 => 1,
+            None => 0,
+        };
+        history.cursor = Some(next);
+        let text = history.entries[next].clone();
+        let count = history.entries.len();
+        drop(history);
+
+        Self::write_raw(&text)?;
+        Ok(Some((next + 1, count)))
+    }
+}
+
+/// Raw CF_HTML fragment, if the clipboard holds one
+fn read_html() -> Option<String> {
+    unsafe {
+        let format = RegisterClipboardFormatW(w!("HTML Format"));
+        if format == 0 || IsClipboardFormatAvailable(format).is_err() {
+            return None;
+        }
+        OpenClipboard(HWND::default()).ok()?;
+
+        let bytes = GetClipboardData(format).ok().and_then(|handle| {
+            let global = HGLOBAL(handle.0);
+            let ptr = GlobalLock(global) as *const u8;
+            if ptr.is_null() {
+                return None;
+            }
+            let bytes = std::slice::from_raw_parts(ptr, GlobalSize(global)).to_vec();
+            let _ = GlobalUnlock(global);
+            Some(bytes)
+        });
+        let _ = CloseClipboard();
+
+        html_fragment(&bytes?)
+    }
+}
+
+/// CF_HTML is UTF-8 with a "StartFragment:<byte offset>" header; return just the copied fragment
+fn html_fragment(raw: &[u8]) -> Option<String> {
+    let header_end = raw.iter().position(|&b| b == b'<').unwrap_or(raw.len());
+    let header = String::from_utf8_lossy(&raw[..header_end]);
+    let offset = |name: &str| {
+        header
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|value| value.trim().parse::<usize>().ok())
+    };
+
+    let body = match (offset("StartFragment:"), offset("EndFragment:")) {
+        (Some(start), Some(end)) if start < end && end <= raw.len() => &raw[start..end],
+        _ => &raw[header_end..],
+    };
+    let html = String::from_utf8_lossy(body).trim_end_matches('\0').to_string();
+    Some(html)
+}
+
+struct HtmlPatterns {
+    hidden: Regex,
+    line_break: Regex,
+    list_item: Regex,
+    tag: Regex,
+    blank_lines: Regex,
+}
+
+fn html_patterns() -> &'static HtmlPatterns {
+    static PATTERNS: OnceLock<HtmlPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| HtmlPatterns {
+        hidden: Regex::new(r"(?is)<(script|style|head)\b.*?</(script|style|head)\s*>|<!--.*?-->").unwrap(),
+        line_break: Regex::new(r"(?i)<br\s*/?>|</(p|div|li|tr|h[1-6]|label|option)\s*>").unwrap(),
+        list_item: Regex::new(r"(?i)<li\b[^>]*>").unwrap(),
+        tag: Regex::new(r"(?s)<[^>]*>").unwrap(),
+        blank_lines: Regex::new(r"\n[ \t]*(\n[ \t]*)+").unwrap(),
+    })
+}
+
+/// Strip tags but keep the block structure (paragraphs, list items, table rows) as lines
+pub fn html_to_text(html: &str) -> String {
+    let p = html_patterns();
+    let text = p.hidden.replace_all(html, "");
+    let text = text.replace(['\r', '\n'], " ");
+    let text = p.line_break.replace_all(&text, "\n");
+    let text = p.list_item.replace_all(&text, "\n- ");
+    let text = p.tag.replace_all(&text, "");
+    let text = decode_entities(&text);
+    let text = text.lines().map(|l| l.split_whitespace().collect::<Vec<_>>().join(" ")).collect::<Vec<_>>().join("\n");
+    p.blank_lines.replace_all(&text, "\n").trim().to_string()
+}
+
+fn decode_entities(text: &str) -> String {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+    let entity = ENTITY.get_or_init(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap());
+    entity
+        .replace_all(text, |caps: &regex::Captures| {
+            let name = &caps[1];
+            let decoded = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ if name.starts_with("#x") => u32::from_str_radix(&name[2..], 16).ok().and_then(char::from_u32),
+                _ if name.starts_with('#') => name[1..].parse().ok().and_then(char::from_u32),
+                _ => None,
+            };
+            decoded.map_or_else(|| caps[0].to_string(), |c| c.to_string())
+        })
+        .into_owned()
+}
+
+/// Minimal HTML for an LLM answer: escaped text, **bold**, and line breaks
+fn text_to_html(text: &str) -> String {
+    static BOLD: OnceLock<Regex> = OnceLock::new();
+    let bold = BOLD.get_or_init(|| Regex::new(r"\*\*(.+?)\*\*").unwrap());
+
+    let escaped = text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let lines: Vec<String> = escaped.lines().map(|line| bold.replace_all(line, "<b>$1</b>").into_owned()).collect();
+    format!("<div>{}</div>", lines.join("<br>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_text_keeps_structure() {
+        let html = "<style>p{color:red}</style><p>Which planet is largest?</p><ul><li>Mars</li><li>Jupiter &amp; moons</li></ul>";
+        assert_eq!(html_to_text(html), "Which planet is largest?\n- Mars\n- Jupiter & moons");
+        assert_eq!(html_to_text("2 &lt; 3&nbsp;is <b>true</b>"), "2 < 3 is true");
+    }
+
+    #[test]
+    fn test_html_fragment_offsets() {
+        let body = "<html><body><!--StartFragment--><b>Q1</b><!--EndFragment--></body></html>";
+        let header_len = "Version:0.9\r\nStartFragment:0000000000\r\nEndFragment:0000000000\r\n".len();
+        let start = header_len + body.find("<b>").unwrap();
+        let end = header_len + body.find("<!--EndFragment").unwrap();
+        let raw = format!("Version:0.9\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n{}", start, end, body);
+        assert_eq!(html_fragment(raw.as_bytes()).unwrap(), "<b>Q1</b>");
+    }
+
+    #[test]
+    fn test_text_to_html_escapes() {
+        assert_eq!(text_to_html("**A)** x < y\nmore"), "<div><b>A)</b> x &lt; y<br>more</div>");
     }
 }
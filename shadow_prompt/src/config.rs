@@ -14,6 +14,8 @@ pub struct Config {
     pub safety: SafetyConfig,
     #[serde(default)]
     pub http: HttpConfig,
+    #[serde(default)]
+    pub browser: BrowserConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -25,6 +27,31 @@ pub struct SearchConfig {
     pub engine: String,
     #[serde(default)]
     pub serper_api_key: Option<String>,
+    /// Azure "Bing Search v7" resource key, for `engine = "bing"`
+    #[serde(default)]
+    pub bing_api_key: Option<String>,
+    /// Base URL of a SearXNG instance with the JSON format enabled, for `engine = "searxng"`
+    #[serde(default)]
+    pub searxng_url: String,
+    /// Optional; raises the Semantic Scholar rate limit for `engine = "academic"` / `!papers`
+    #[serde(default)]
+    pub semantic_scholar_api_key: Option<String>,
+    /// Fetch this many top results (0-2) and add the relevant part of each page
+    #[serde(default)]
+    pub fetch_pages: usize,
+    #[serde(default = "default_page_chars")]
+    pub page_chars: usize,
+    /// "day", "week", "month" or "year" to skip older results ("" = any time)
+    #[serde(default)]
+    pub recency: String,
+    /// Append "Sources: <url> ..." to answers that used search results
+    #[serde(default)]
+    pub append_sources: bool,
+    /// Add Wikipedia lead sections for names/terms found in the question
+    #[serde(default)]
+    pub wikipedia: bool,
+    #[serde(default = "default_wikipedia_lang")]
+    pub wikipedia_lang: String,
 }
 
 impl Default for SearchConfig {
@@ -34,6 +61,15 @@ impl Default for SearchConfig {
             max_results: 3,
             engine: default_search_engine(),
             serper_api_key: None,
+            bing_api_key: None,
+            searxng_url: String::new(),
+            semantic_scholar_api_key: None,
+            fetch_pages: 0,
+            page_chars: default_page_chars(),
+            recency: String::new(),
+            append_sources: false,
+            wikipedia: false,
+            wikipedia_lang: default_wikipedia_lang(),
         }
     }
 }
@@ -42,6 +78,14 @@ fn default_search_engine() -> String {
     "serper".to_string()
 }
 
+fn default_page_chars() -> usize {
+    1500
+}
+
+fn default_wikipedia_lang() -> String {
+    "en".to_string()
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
 pub struct GeneralConfig {
@@ -67,6 +111,63 @@ pub struct GeneralConfig {
     pub key_browser_abort: String,
     #[serde(default = "default_browser_incognito")]
     pub key_browser_incognito: String,
+    /// Pauses the auto form flow before the next page is filled
+    #[serde(default = "default_browser_pause")]
+    pub key_browser_pause: String,
+    /// Restores older clipboard entries written by ShadowPrompt, one step per press
+    #[serde(default = "default_clipboard_cycle_key")]
+    pub key_clipboard_cycle: String,
+    /// Questions/answers kept for `key_clipboard_cycle` (0 disables)
+    #[serde(default = "default_clipboard_history_size")]
+    pub clipboard_history_size: usize,
+    /// Saves the last OCR result (or the clipboard) into knowledge/captured/ and embeds it
+    #[serde(default = "default_rag_capture_key")]
+    pub key_rag_capture: String,
+    /// Opens the live log window (debug builds only)
+    #[serde(default = "default_log_viewer_key")]
+    pub key_log_viewer: String,
+    /// Copies the last few hundred log lines, see `log_dump_target`
+    #[serde(default = "default_log_dump_key")]
+    pub key_log_dump: String,
+    /// Where `key_log_dump` puts them: "clipboard" or "file" (data/logs/dump-<time>.log)
+    #[serde(default = "default_log_dump_target")]
+    pub log_dump_target: String,
+    /// Global hook implementation: "rdev" or "win32" (native low-level hooks)
+    #[serde(default = "default_input_backend")]
+    pub input_backend: String,
+    /// Max gap between taps for "Double-tap <Key>" bindings
+    #[serde(default = "default_double_tap_window")]
+    pub double_tap_window_ms: u64,
+    /// Screen capture for OCR: "bitblt" or "wgc" (Windows Graphics Capture, falls back to bitblt)
+    #[serde(default = "default_capture_backend")]
+    pub capture_backend: String,
+    /// Longer questions are cut down to the last question-looking block (0 = no limit)
+    #[serde(default = "default_max_input_chars")]
+    pub max_input_chars: usize,
+    /// Opt-in local usage counters (data/analytics.db, never uploaded); see `--stats`
+    #[serde(default)]
+    pub local_analytics: bool,
+    /// Reuse answers to questions already answered this session (hotkey and form flow)
+    #[serde(default = "default_true")]
+    pub answer_cache: bool,
+    /// TOML snippet fetched at startup and merged over this config (cached for offline starts)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_config_url: Option<String>,
+    /// SHA-256 (hex) the fetched snippet must have; without it the remote config is not used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_config_sha256: Option<String>,
+    /// Wizard and overlay language: a file stem in config/locales (English is built in)
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Write questions, search queries and answers to data/logs/error.log (keys are always masked)
+    #[serde(default)]
+    pub log_content: bool,
+    /// After a crash (logged to data/logs/crash.log), start a fresh copy
+    #[serde(default)]
+    pub restart_on_crash: bool,
+    /// Exit (clearing the clipboard and overlays) after this long without a hotkey (0 = never)
+    #[serde(default)]
+    pub idle_exit_minutes: u64,
 }
 
 impl Default for GeneralConfig {
@@ -85,15 +186,47 @@ impl Default for GeneralConfig {
             key_browser_exec_single: default_browser_exec_single(),
             key_browser_abort: default_browser_abort(),
             key_browser_incognito: default_browser_incognito(),
+            key_browser_pause: default_browser_pause(),
+            key_clipboard_cycle: default_clipboard_cycle_key(),
+            clipboard_history_size: default_clipboard_history_size(),
+            key_rag_capture: default_rag_capture_key(),
+            key_log_viewer: default_log_viewer_key(),
+            key_log_dump: default_log_dump_key(),
+            log_dump_target: default_log_dump_target(),
+            input_backend: default_input_backend(),
+            double_tap_window_ms: default_double_tap_window(),
+            capture_backend: default_capture_backend(),
+            max_input_chars: default_max_input_chars(),
+            local_analytics: false,
+            answer_cache: true,
+            remote_config_url: None,
+            remote_config_sha256: None,
+            language: default_language(),
+            log_content: false,
+            restart_on_crash: false,
+            idle_exit_minutes: 0,
         }
     }
 }
 
+fn default_language() -> String { crate::i18n::DEFAULT_LANGUAGE.to_string() }
+
 fn default_browser_pass() -> String { "Ctrl+Shift+8".to_string() }
 fn default_browser_exec() -> String { "Ctrl+Shift+9".to_string() }
 fn default_browser_exec_single() -> String { "Ctrl+Shift+7".to_string() }
 fn default_browser_abort() -> String { "Ctrl+Shift+0".to_string() }
 fn default_browser_incognito() -> String { "Ctrl+Shift+I".to_string() }
+fn default_browser_pause() -> String { String::new() }
+fn default_clipboard_cycle_key() -> String { String::new() }
+fn default_clipboard_history_size() -> usize { 10 }
+fn default_rag_capture_key() -> String { String::new() }
+fn default_log_viewer_key() -> String { String::new() }
+fn default_log_dump_key() -> String { String::new() }
+fn default_log_dump_target() -> String { "clipboard".to_string() }
+fn default_input_backend() -> String { "rdev".to_string() }
+fn default_double_tap_window() -> u64 { 300 }
+fn default_capture_backend() -> String { "bitblt".to_string() }
+fn default_max_input_chars() -> usize { 12000 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
@@ -136,6 +269,14 @@ pub struct VisualsConfig {
     #[serde(default = "default_color_false")]
     pub color_false: String,
 
+    /// Main indicator after a crash (see crash.rs)
+    #[serde(default = "default_color_crash")]
+    pub color_crash: String,
+
+    /// Ready indicator once today's spend passes `safety.spend_warning_usd`
+    #[serde(default = "default_color_spend_warning")]
+    pub color_spend_warning: String,
+
     #[serde(default = "default_true")]
     pub text_overlay_enabled: bool,
 
@@ -162,6 +303,54 @@ pub struct VisualsConfig {
 
     #[serde(default = "default_hide_key")]
     pub hide_key: String,
+
+    #[serde(default = "default_color_scheme")]
+    pub color_scheme: String,
+
+    /// Seconds before the MCQ/TF indicator falls back to the neutral color (0 = until next query)
+    #[serde(default)]
+    pub secondary_indicator_hold_secs: u64,
+
+    /// Where answers go: "clipboard", "toast", or "both"
+    #[serde(default = "default_output_mode")]
+    pub output_mode: String,
+    #[serde(default = "default_toast_duration")]
+    pub toast_duration_secs: u64,
+    /// Clipboard text shape: "raw", "clean", "answer_only", or a template such as
+    /// "Answer: {letter} — {text}" (see output_format.rs)
+    #[serde(default = "default_output_template")]
+    pub output_template: String,
+    /// Also write answers as HTML so bold/line breaks survive pasting into rich editors
+    #[serde(default)]
+    pub formatted_output: bool,
+    /// Put the user's previous clipboard back: "off", "after_paste" (next Ctrl+V), or "timeout"
+    #[serde(default = "default_clipboard_restore")]
+    pub clipboard_restore: String,
+    /// Restore deadline for "timeout", and the fallback for "after_paste"
+    #[serde(default = "default_clipboard_restore_secs")]
+    pub clipboard_restore_secs: u64,
+
+    /// Setup/settings window: "dark" or "light"
+    #[serde(default = "default_wizard_theme")]
+    pub wizard_theme: String,
+    /// Setup/settings window zoom (1.0 = 100%)
+    #[serde(default = "default_wizard_scale")]
+    pub wizard_scale: f32,
+    /// Black/white panels, heavier outlines in the setup/settings window
+    #[serde(default)]
+    pub wizard_high_contrast: bool,
+
+    /// Per-state alpha/size, keyed by ready, processing, mcq_a..mcq_d, mcq_none, true, false, crash
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub state_overrides: HashMap<String, IndicatorOverride>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct IndicatorOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<i32>,
 }
 
 impl Default for VisualsConfig {
@@ -183,6 +372,8 @@ impl Default for VisualsConfig {
             cursor_change: false,
             color_true: default_color_true(),
             color_false: default_color_false(),
+            color_crash: default_color_crash(),
+            color_spend_warning: default_color_spend_warning(),
             text_overlay_enabled: true,
             text_overlay_position: default_text_overlay_position(),
             text_overlay_font_size: default_text_overlay_font_size(),
@@ -192,10 +383,176 @@ impl Default for VisualsConfig {
             text_overlay_x_axis: 0,
             text_overlay_y_axis: 0,
             hide_key: default_hide_key(),
+            color_scheme: default_color_scheme(),
+            secondary_indicator_hold_secs: 0,
+            output_mode: default_output_mode(),
+            toast_duration_secs: default_toast_duration(),
+            output_template: default_output_template(),
+            formatted_output: false,
+            clipboard_restore: default_clipboard_restore(),
+            clipboard_restore_secs: default_clipboard_restore_secs(),
+            wizard_theme: default_wizard_theme(),
+            wizard_scale: default_wizard_scale(),
+            wizard_high_contrast: false,
+            state_overrides: HashMap::new(),
+        }
+    }
+}
+
+/// Named indicator palettes. "custom" keeps the individual color fields as-is.
+pub const COLOR_SCHEMES: &[&str] = &["custom", "default", "deuteranopia", "protanopia", "high-contrast"];
+
+struct ColorScheme {
+    ready: &'static str,
+    processing: &'static str,
+    mcq_a: &'static str,
+    mcq_b: &'static str,
+    mcq_c: &'static str,
+    mcq_d: &'static str,
+    mcq_none: &'static str,
+    color_true: &'static str,
+    color_false: &'static str,
+    spend_warning: &'static str,
+    crash: &'static str,
+}
+
+fn color_scheme_preset(name: &str) -> Option<ColorScheme> {
+    match name {
+        "default" => Some(ColorScheme {
+            ready: "#00FF00",
+            processing: "#FF0000",
+            mcq_a: "#00FFFF",
+            mcq_b: "#FF00FF",
+            mcq_c: "#FFFF00",
+            mcq_d: "#000000",
+            mcq_none: "#FFFFFF",
+            color_true: "#00FF00",
+            color_false: "#800000",
+            spend_warning: "#FFBF00",
+            crash: "#8000FF",
+        }),
+        // Okabe-Ito palette, avoids red/green pairs
+        "deuteranopia" => Some(ColorScheme {
+            ready: "#0072B2",
+            processing: "#E69F00",
+            mcq_a: "#56B4E9",
+            mcq_b: "#CC79A7",
+            mcq_c: "#F0E442",
+            mcq_d: "#000000",
+            mcq_none: "#FFFFFF",
+            color_true: "#0072B2",
+            color_false: "#D55E00",
+            spend_warning: "#009E73",
+            crash: "#999999",
+        }),
+        // Reds look dark to protanopes, so processing uses yellow instead
+        "protanopia" => Some(ColorScheme {
+            ready: "#0072B2",
+            processing: "#F0E442",
+            mcq_a: "#56B4E9",
+            mcq_b: "#CC79A7",
+            mcq_c: "#E69F00",
+            mcq_d: "#000000",
+            mcq_none: "#FFFFFF",
+            color_true: "#56B4E9",
+            color_false: "#E69F00",
+            spend_warning: "#009E73",
+            crash: "#999999",
+        }),
+        "high-contrast" => Some(ColorScheme {
+            ready: "#00FF00",
+            processing: "#FF0000",
+            mcq_a: "#0000FF",
+            mcq_b: "#FFFF00",
+            mcq_c: "#FF00FF",
+            mcq_d: "#000000",
+            mcq_none: "#FFFFFF",
+            color_true: "#FFFFFF",
+            color_false: "#000000",
+            spend_warning: "#00FFFF",
+            crash: "#808080",
+        }),
+        _ => None,
+    }
+}
+
+impl VisualsConfig {
+    /// Pixel size and alpha for an indicator state, falling back to `size` and fully opaque.
+    pub fn state_style(&self, state_key: &str) -> (i32, u8) {
+        let o = self.state_overrides.get(state_key);
+        let size = o.and_then(|o| o.size).unwrap_or(self.size).max(1);
+        let alpha = o.and_then(|o| o.alpha).unwrap_or(255);
+        (size, alpha)
+    }
+
+    /// Overwrite all status and MCQ colors from the selected `color_scheme`.
+    /// Returns false (and changes nothing) for "custom" or unknown names.
+    pub fn apply_color_scheme(&mut self) -> bool {
+        self.apply_color_scheme_keeping(&[]).is_some()
+    }
+
+    /// `apply_color_scheme`, leaving the color fields named in `keep` alone. Returns the
+    /// fields whose value the preset changed, or None for "custom" or unknown names.
+    fn apply_color_scheme_keeping(&mut self, keep: &[&str]) -> Option<Vec<&'static str>> {
+        let scheme = color_scheme_preset(&self.color_scheme)?;
+        let fields = [
+            ("ready_color", &mut self.ready_color, scheme.ready),
+            ("color_processing", &mut self.color_processing, scheme.processing),
+            ("indicator_color", &mut self.indicator_color, scheme.processing),
+            ("color_mcq_a", &mut self.color_mcq_a, scheme.mcq_a),
+            ("color_mcq_b", &mut self.color_mcq_b, scheme.mcq_b),
+            ("color_mcq_c", &mut self.color_mcq_c, scheme.mcq_c),
+            ("color_mcq_d", &mut self.color_mcq_d, scheme.mcq_d),
+            ("color_mcq_none", &mut self.color_mcq_none, scheme.mcq_none),
+            ("color_true", &mut self.color_true, scheme.color_true),
+            ("color_false", &mut self.color_false, scheme.color_false),
+            ("color_spend_warning", &mut self.color_spend_warning, scheme.spend_warning),
+            ("color_crash", &mut self.color_crash, scheme.crash),
+        ];
+        let mut replaced = Vec::new();
+        for (name, field, color) in fields {
+            if keep.contains(&name) || field.eq_ignore_ascii_case(color) {
+                continue;
+            }
+            *field = color.to_string();
+            replaced.push(name);
+        }
+        Some(replaced)
+    }
+
+    /// Apply the preset at load time and say which configured colors it replaced
+    fn apply_color_scheme_reporting(&mut self, keep: &[&str]) {
+        let Some(replaced) = self.apply_color_scheme_keeping(keep) else { return };
+        if !replaced.is_empty() {
+            println!("[*] color_scheme '{}' replaces visuals.{}", self.color_scheme, replaced.join(", visuals."));
         }
     }
 }
 
+fn default_color_scheme() -> String {
+    "custom".to_string()
+}
+
+fn default_output_mode() -> String {
+    "clipboard".to_string()
+}
+
+fn default_toast_duration() -> u64 {
+    6
+}
+
+fn default_output_template() -> String {
+    "raw".to_string()
+}
+
+fn default_clipboard_restore() -> String {
+    "off".to_string()
+}
+
+fn default_clipboard_restore_secs() -> u64 {
+    30
+}
+
 fn default_position() -> String {
     "top-right".to_string()
 }
@@ -237,6 +594,14 @@ fn default_color_false() -> String {
     "#800000".to_string()
 }
 
+fn default_color_crash() -> String {
+    "#8000FF".to_string()
+}
+
+fn default_color_spend_warning() -> String {
+    "#FFBF00".to_string()
+}
+
 #[allow(dead_code)]
 fn default_text_size() -> i32 {
     12
@@ -267,6 +632,14 @@ fn default_hide_key() -> String {
     "Ctrl+Shift+H".to_string()
 }
 
+fn default_wizard_theme() -> String {
+    "dark".to_string()
+}
+
+fn default_wizard_scale() -> f32 {
+    1.0
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
 pub struct ModelConfig {
@@ -352,8 +725,33 @@ pub struct RagConfig {
     pub index_path: String,
     pub max_results: usize,
     pub min_score: f32,
+    /// "fastembed" (local ONNX model, downloaded on first run) or "ollama"
+    #[serde(default = "default_embedding_backend")]
+    pub embedding_backend: String,
+    #[serde(default = "default_ollama_embedding_model")]
+    pub ollama_embedding_model: String,
+    /// Re-fetch pages listed in knowledge/urls.txt once they are this old (0 = fetch once)
+    #[serde(default = "default_url_refresh_hours")]
+    pub url_refresh_hours: u64,
+    /// Append "Sources: [file §chunk] ..." to answers that used the knowledge base
+    #[serde(default)]
+    pub append_sources: bool,
+    /// Knowledge subfolder searched when a question has no "@name" prefix ("" = all)
+    #[serde(default)]
+    pub default_collection: String,
+    /// Encrypt index.bin (the full text of your notes) with AES-256-GCM
+    #[serde(default)]
+    pub encrypt_index: bool,
+    /// "passphrase" (asked at startup, portable) or "dpapi" (key tied to this Windows account)
+    #[serde(default = "default_index_key_source")]
+    pub index_key_source: String,
 }
 
+fn default_embedding_backend() -> String { "fastembed".to_string() }
+fn default_ollama_embedding_model() -> String { "nomic-embed-text".to_string() }
+fn default_url_refresh_hours() -> u64 { 24 }
+fn default_index_key_source() -> String { "passphrase".to_string() }
+
 impl Default for RagConfig {
     fn default() -> Self {
         Self {
@@ -362,6 +760,13 @@ impl Default for RagConfig {
             index_path: "data/rag_index".to_string(),
             max_results: 3,
             min_score: 0.5,
+            embedding_backend: default_embedding_backend(),
+            ollama_embedding_model: default_ollama_embedding_model(),
+            url_refresh_hours: default_url_refresh_hours(),
+            append_sources: false,
+            default_collection: String::new(),
+            encrypt_index: false,
+            index_key_source: default_index_key_source(),
         }
     }
 }
@@ -369,17 +774,34 @@ impl Default for RagConfig {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
 pub struct SafetyConfig {
+    /// Groq/OpenRouter are refused for the rest of the day once this is spent (0 = no limit)
     pub daily_spend_limit_usd: f64,
+    /// The ready indicator turns `color_spend_warning` past this (0 = off)
+    #[serde(default = "default_spend_warning")]
+    pub spend_warning_usd: f64,
+    /// Prices for estimating Groq's cost (OpenRouter reports its own)
+    #[serde(default = "default_input_price")]
+    pub input_usd_per_million: f64,
+    #[serde(default = "default_output_price")]
+    pub output_usd_per_million: f64,
 }
 
 impl Default for SafetyConfig {
     fn default() -> Self {
         Self {
             daily_spend_limit_usd: 0.50,
+            spend_warning_usd: default_spend_warning(),
+            input_usd_per_million: default_input_price(),
+            output_usd_per_million: default_output_price(),
         }
     }
 }
 
+fn default_spend_warning() -> f64 { 0.40 }
+// llama-3.3-70b-versatile on Groq
+fn default_input_price() -> f64 { 0.59 }
+fn default_output_price() -> f64 { 0.79 }
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[allow(dead_code)]
 pub struct HttpConfig {
@@ -405,16 +827,198 @@ fn default_read_timeout() -> u64 {
     30
 }
 
+/// Overrides for the browser form-flow prompts, which need stricter JSON output
+/// than quick answers. Unset fields fall back to `[models]`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BrowserConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Model ID for the chosen provider (ignored with provider "auto")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Chrome/Edge/Brave/Chromium executable; detected when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_path: Option<String>,
+    /// Auto mode: "per_page" asks the LLM page by page; "whole_form" reads every page
+    /// first and answers the whole form in one request
+    #[serde(default = "default_strategy")]
+    pub strategy: String,
+    /// Put the planned answers on the clipboard/overlay instead of filling the form
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Auto mode stops after this many pages
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+    /// How long to wait for the next page after clicking Next
+    #[serde(default = "default_page_timeout_secs")]
+    pub page_timeout_secs: u64,
+    /// Pause after a new page loads, before it is read
+    #[serde(default = "default_page_settle_ms")]
+    pub page_settle_ms: u64,
+    /// Record each run's questions and answers in data/form_runs/
+    #[serde(default = "default_true")]
+    pub run_log: bool,
+    /// Save applied answers per form in data/form_progress/ and replay them after an interruption
+    #[serde(default = "default_true")]
+    pub resume: bool,
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            provider: None,
+            model: None,
+            binary_path: None,
+            strategy: default_strategy(),
+            dry_run: false,
+            max_pages: default_max_pages(),
+            page_timeout_secs: default_page_timeout_secs(),
+            page_settle_ms: default_page_settle_ms(),
+            run_log: true,
+            resume: true,
+        }
+    }
+}
+
+fn default_strategy() -> String {
+    "per_page".to_string()
+}
+
+fn default_max_pages() -> usize {
+    10
+}
+
+fn default_page_timeout_secs() -> u64 {
+    15
+}
+
+fn default_page_settle_ms() -> u64 {
+    500
+}
+
 impl Config {
+    /// Copy of this config with the `[browser]` provider/model override applied
+    pub fn for_form_flow(&self) -> Config {
+        let mut config = self.clone();
+        if let Some(provider) = self.browser.provider.as_ref().filter(|p| !p.trim().is_empty()) {
+            config.models.provider = provider.trim().to_lowercase();
+        }
+
+        if let Some(model) = self.browser.model.as_ref().filter(|m| !m.trim().is_empty()) {
+            let model = model.trim().to_string();
+            match config.models.provider.as_str() {
+                "groq" => if let Some(groq) = config.models.groq.as_mut() { groq.model_id = model },
+                "openrouter" => if let Some(or) = config.models.openrouter.as_mut() { or.model_id = model },
+                "ollama" => if let Some(ollama) = config.models.ollama.as_mut() { ollama.model_id = model },
+                other => eprintln!("Warning: [browser] model ignored for provider '{}'", other),
+            }
+        }
+        config
+    }
+
+    /// Load config.toml merged with config/override.<hostname>.toml, `${VAR}` references
+    /// resolved from the environment or config/.env and `enc:` values (see `encrypt_keys`) decrypted
     pub fn load() -> Result<Self> {
+        let mut table = Self::read_table()?;
+        if let Some(path) = machine_override_path().filter(|p| p.exists()) {
+            let overlay: toml::Table = fs::read_to_string(&path)
+                .context(format!("Failed to read {:?}", path))?
+                .parse()
+                .context(format!("Failed to parse {:?}", path))?;
+            println!("[*] Applying machine override {}", path.display());
+            merge_tables(&mut table, overlay);
+        }
+        let mut value = toml::Value::Table(table);
+        resolve_secrets(&mut value);
+
+        let mut config: Config = value.try_into().context("Failed to parse config.toml")?;
+        config.visuals.apply_color_scheme_reporting(&[]);
+
+        Ok(config)
+    }
+
+    /// Load config.toml as written, `${VAR}` references untouched, for editing and saving
+    /// (so resolved secrets never end up in the file)
+    pub fn load_raw() -> Result<Self> {
+        let mut config: Config = toml::Value::Table(Self::read_table()?).try_into().context("Failed to parse config.toml")?;
+        config.visuals.apply_color_scheme_reporting(&[]);
+        Ok(config)
+    }
+
+    /// A copy with `${VAR}` references and `enc:` values resolved as `load` does, for a
+    /// `load_raw` config that is about to make network calls. Never save it.
+    pub fn resolved(&self) -> Self {
+        let Ok(mut value) = toml::Value::try_from(self) else { return self.clone() };
+        resolve_secrets(&mut value);
+        value.try_into().unwrap_or_else(|_| self.clone())
+    }
+
+    fn read_table() -> Result<toml::Table> {
         let config_path = get_config_path();
 
         let content = fs::read_to_string(&config_path)
             .context(format!("Failed to read config.toml at {:?}", config_path))?;
 
-        let config: Config = toml::from_str(&content).context("Failed to parse config.toml")?;
+        toml::from_str(&content).context("Failed to parse config.toml")
+    }
 
-        Ok(config)
+    /// Apply `--set section.key=value` arguments for this run only; they are never saved.
+    /// Bad overrides are reported and skipped.
+    pub fn with_cli_overrides(self) -> Self {
+        let args: Vec<String> = std::env::args().collect();
+        let overrides = cli_overrides(&args);
+        if overrides.is_empty() {
+            return self;
+        }
+        let mut config = self;
+        let mut set_visuals = Vec::new();
+        for raw in overrides {
+            match config.apply_override(&raw) {
+                Ok(next) => {
+                    println!("[*] Config override: {}", raw);
+                    config = next;
+                    if let Some(key) = raw.split_once('=').and_then(|(k, _)| k.trim().strip_prefix("visuals.")) {
+                        set_visuals.push(key.trim().to_string());
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[!] Ignoring --set {}: {}", raw, e);
+                    log::error!("Ignoring --set {}: {}", raw, e);
+                }
+            }
+        }
+        // A color given with --set wins over the preset, whichever came first
+        let keep: Vec<&str> = set_visuals.iter().map(String::as_str).collect();
+        config.visuals.apply_color_scheme_reporting(&keep);
+        config
+    }
+
+    /// A copy with one "dotted.key=value" applied; the value is read as TOML
+    /// (true, 3, 0.5, "quoted"), anything else as a plain string
+    fn apply_override(&self, raw: &str) -> Result<Self> {
+        let (key, value) = raw.split_once('=').context("expected key=value")?;
+        let path: Vec<&str> = key.trim().split('.').map(str::trim).collect();
+        if path.len() < 2 || path.iter().any(|p| p.is_empty()) {
+            anyhow::bail!("key must look like section.name");
+        }
+        let value = value.trim();
+        let value = toml::from_str::<toml::Table>(&format!("v = {}", value))
+            .ok()
+            .and_then(|mut t| t.remove("v"))
+            .unwrap_or_else(|| toml::Value::String(value.to_string()));
+
+        let mut root = toml::Value::try_from(self).context("Failed to serialize config")?;
+        let (leaf, parents) = path.split_last().unwrap();
+        let mut table = &mut root;
+        for part in parents {
+            table = table.get_mut(*part).with_context(|| format!("unknown section '{}'", part))?;
+        }
+        let table = table.as_table_mut().context("not a section")?;
+        if !table.contains_key(*leaf) {
+            eprintln!("[!] --set {}: '{}' is not set in the current config (typo?)", raw, leaf);
+        }
+        table.insert(leaf.to_string(), value);
+        root.try_into().context("value has the wrong type")
     }
 
     #[allow(dead_code)]
@@ -456,6 +1060,246 @@ impl Config {
     }
 }
 
+/// config/override.<hostname>.toml next to config.toml, for settings that differ per
+/// machine on a portable install (display offsets, browser path)
+pub fn machine_override_path() -> Option<std::path::PathBuf> {
+    let host = std::env::var("COMPUTERNAME").or_else(|_| std::env::var("HOSTNAME")).ok()?;
+    let host: String = host.trim().to_lowercase().chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_').collect();
+    (!host.is_empty()).then(|| get_config_path().with_file_name(format!("override.{}.toml", host)))
+}
+
+/// Merge `overlay` into `base`: tables merge key by key, anything else is replaced
+pub fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(incoming)) => merge_tables(existing, incoming),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// `${VAR}` references resolved from the environment or config/.env, then `enc:` values decrypted
+fn resolve_secrets(value: &mut toml::Value) {
+    let dotenv = read_dotenv(&get_config_path().with_file_name(".env"));
+    expand_env_vars(value, &|name| std::env::var(name).ok().or_else(|| dotenv.get(name).cloned()));
+    decrypt_secrets(value);
+}
+
+/// One raw setting (an API key field as typed in the wizard) resolved like `resolved`
+pub fn resolve_value(raw: &str) -> String {
+    let mut value = toml::Value::String(raw.to_string());
+    resolve_secrets(&mut value);
+    value.as_str().unwrap_or(raw).to_string()
+}
+
+/// Replace `${NAME}` in every string value; unknown names are left as written
+pub fn expand_env_vars(value: &mut toml::Value, lookup: &dyn Fn(&str) -> Option<String>) {
+    match value {
+        toml::Value::String(s) if s.contains("${") => *s = expand_str(s, lookup),
+        toml::Value::Array(items) => items.iter_mut().for_each(|v| expand_env_vars(v, lookup)),
+        toml::Value::Table(table) => table.values_mut().for_each(|v| expand_env_vars(v, lookup)),
+        _ => {}
+    }
+}
+
+fn expand_str(s: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else { break };
+        let name = &rest[start + 2..start + 2 + len];
+        out.push_str(&rest[..start]);
+        match lookup(name) {
+            Some(value) => out.push_str(&value),
+            None => {
+                eprintln!("[!] Config: environment variable '{}' is not set", name);
+                out.push_str(&rest[start..start + len + 3]);
+            }
+        }
+        rest = &rest[start + len + 3..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Prefix of values protected with DPAPI by `--encrypt-keys`
+pub const ENCRYPTED_PREFIX: &str = "enc:";
+
+/// Decrypt every `enc:` string. One that can't be decrypted (another Windows account,
+/// another machine) is cleared with a warning rather than sent as a key.
+fn decrypt_secrets(value: &mut toml::Value) {
+    use base64::Engine;
+    match value {
+        toml::Value::String(s) if s.starts_with(ENCRYPTED_PREFIX) => {
+            let decrypted = base64::engine::general_purpose::STANDARD
+                .decode(&s[ENCRYPTED_PREFIX.len()..])
+                .map_err(anyhow::Error::from)
+                .and_then(|blob| crate::dpapi::unprotect(&blob))
+                .and_then(|plain| Ok(String::from_utf8(plain)?));
+            *s = match decrypted {
+                Ok(plain) => plain,
+                Err(e) => {
+                    eprintln!("[!] Config: could not decrypt a key ({}). Was it encrypted by another Windows account?", e);
+                    log::error!("Could not decrypt an encrypted config value: {}", e);
+                    String::new()
+                }
+            };
+        }
+        toml::Value::Array(items) => items.iter_mut().for_each(decrypt_secrets),
+        toml::Value::Table(table) => table.values_mut().for_each(decrypt_secrets),
+        _ => {}
+    }
+}
+
+/// `--encrypt-keys`: replace every plaintext `api_key` / `*_api_key` value in config.toml
+/// with its DPAPI-protected `enc:` form, editing the lines in place so comments survive.
+/// Returns how many keys were encrypted.
+pub fn encrypt_keys() -> Result<usize> {
+    let path = get_config_path();
+    let content = fs::read_to_string(&path).context(format!("Failed to read config.toml at {:?}", path))?;
+    let (updated, count) = encrypt_key_lines(&content, &|plain| {
+        use base64::Engine;
+        let blob = crate::dpapi::protect(plain.as_bytes())?;
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, base64::engine::general_purpose::STANDARD.encode(blob)))
+    })?;
+    if count > 0 {
+        fs::write(&path, updated).context("Failed to write config.toml")?;
+    }
+    Ok(count)
+}
+
+/// config.toml text with the value of every `api_key` / `*_api_key` line passed through
+/// `rewrite`; `None` leaves the line as it was. Basic, literal and multi-line strings are
+/// all recognized, and a rewritten value is written back as a basic string, so it must not
+/// need escaping. Everything else, comments included, is kept byte for byte.
+pub fn rewrite_key_values(content: &str, mut rewrite: impl FnMut(&str) -> Result<Option<String>>) -> Result<String> {
+    let key_line = regex::Regex::new(r#"^(\s*(?:\w+_)?api_key\s*=\s*)(.*)$"#).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let start = i;
+        i += 1;
+        let Some(cap) = key_line.captures(lines[start]) else {
+            out.push(lines[start].to_string());
+            continue;
+        };
+        let Some(delimiter) = ["\"\"\"", "'''", "\"", "'"].into_iter().find(|d| cap[2].starts_with(*d)) else {
+            out.push(lines[start].to_string());
+            continue;
+        };
+        // Only the triple-quoted forms may run on past the end of the line
+        let mut text = cap[2].to_string();
+        let end = loop {
+            if let Some(end) = string_end(&text, delimiter) {
+                break Some(end);
+            }
+            if delimiter.len() == 1 || i == lines.len() {
+                break None;
+            }
+            text.push('\n');
+            text.push_str(lines[i]);
+            i += 1;
+        };
+        let value = end.and_then(|end| {
+            let table: toml::Table = toml::from_str(&format!("v = {}", &text[..end])).ok()?;
+            table.get("v")?.as_str().map(str::to_string)
+        });
+        match (end, value) {
+            (Some(end), Some(value)) => match rewrite(&value)? {
+                Some(new) => out.push(format!("{}\"{}\"{}", &cap[1], new, &text[end..])),
+                None => out.extend(lines[start..i].iter().map(|line| line.to_string())),
+            },
+            _ => {
+                // Unterminated or unparsable: leave it, and re-scan whatever followed it
+                out.push(lines[start].to_string());
+                i = start + 1;
+            }
+        }
+    }
+    let mut updated = out.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+/// Byte offset just past the string `text` opens with `delimiter`; None if it doesn't close.
+/// Quoted (`"`) forms skip backslash escapes, literal (`'`) forms have none.
+fn string_end(text: &str, delimiter: &str) -> Option<usize> {
+    let escapes = delimiter.starts_with('"');
+    let mut i = delimiter.len();
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with(delimiter) {
+            return Some(i + delimiter.len());
+        }
+        let mut chars = rest.chars();
+        let c = chars.next()?;
+        i += c.len_utf8();
+        if escapes && c == '\\' {
+            i += chars.next().map_or(0, char::len_utf8);
+        }
+    }
+    None
+}
+
+fn encrypt_key_lines(content: &str, encrypt: &dyn Fn(&str) -> Result<String>) -> Result<(String, usize)> {
+    let mut count = 0;
+    let updated = rewrite_key_values(content, |value| {
+        if !is_plain_secret(value) {
+            return Ok(None);
+        }
+        count += 1;
+        encrypt(value).map(Some)
+    })?;
+    Ok((updated, count))
+}
+
+/// Empty values, placeholders, `${VAR}` references and already encrypted values are left alone
+fn is_plain_secret(value: &str) -> bool {
+    !value.is_empty() && !value.starts_with(ENCRYPTED_PREFIX) && !value.contains("${") && !value.starts_with("YOUR_")
+}
+
+/// KEY=VALUE lines of a .env file (# comments, optional quotes); empty if there is none
+fn read_dotenv(path: &std::path::Path) -> HashMap<String, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.trim_start_matches("export ").split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"').and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// The values of every `--set key=value` / `--set=key=value` argument
+pub fn cli_overrides(args: &[String]) -> Vec<String> {
+    let mut overrides = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--set" {
+            if let Some(value) = iter.next() {
+                overrides.push(value.clone());
+            }
+        } else if let Some(value) = arg.strip_prefix("--set=") {
+            overrides.push(value.to_string());
+        }
+    }
+    overrides
+}
+
 pub fn get_exe_dir() -> std::path::PathBuf {
     std::env::current_exe()
         .ok()
@@ -500,3 +1344,111 @@ pub fn ensure_directories() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolved_copy() {
+        std::env::set_var("SHADOW_PROMPT_TEST_RESOLVED_KEY", "gsk_resolved");
+        let mut raw = Config::default();
+        raw.models.groq.as_mut().unwrap().api_key = "${SHADOW_PROMPT_TEST_RESOLVED_KEY}".to_string();
+        assert_eq!(raw.resolved().models.groq.unwrap().api_key, "gsk_resolved");
+        assert_eq!(raw.models.groq.unwrap().api_key, "${SHADOW_PROMPT_TEST_RESOLVED_KEY}");
+        assert_eq!(resolve_value("${SHADOW_PROMPT_TEST_RESOLVED_KEY}"), "gsk_resolved");
+    }
+
+    #[test]
+    fn test_color_scheme_states_distinct() {
+        for name in COLOR_SCHEMES.iter().filter(|n| **n != "custom" && **n != "default") {
+            let s = color_scheme_preset(name).unwrap();
+            let others = [s.ready, s.processing, s.mcq_a, s.mcq_b, s.mcq_c, s.mcq_d, s.mcq_none, s.color_true, s.color_false];
+            assert!(!others.contains(&s.spend_warning), "{name} spend_warning");
+            assert!(!others.contains(&s.crash) && s.crash != s.spend_warning, "{name} crash");
+        }
+    }
+
+    #[test]
+    fn test_color_scheme_keeps_explicit_colors() {
+        let mut visuals = VisualsConfig { color_scheme: "high-contrast".to_string(), ..VisualsConfig::default() };
+        visuals.ready_color = "#123456".to_string();
+        let replaced = visuals.apply_color_scheme_keeping(&["ready_color"]).unwrap();
+        assert_eq!(visuals.ready_color, "#123456");
+        assert_eq!(visuals.color_mcq_a, "#0000FF");
+        assert!(replaced.contains(&"color_mcq_a") && !replaced.contains(&"ready_color"));
+        assert_eq!(visuals.apply_color_scheme_keeping(&[]).unwrap(), vec!["ready_color"]);
+
+        visuals.color_scheme = "custom".to_string();
+        assert!(visuals.apply_color_scheme_keeping(&[]).is_none());
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        let lookup = |name: &str| (name == "GROQ_API_KEY").then(|| "gsk_123".to_string());
+        assert_eq!(expand_str("${GROQ_API_KEY}", &lookup), "gsk_123");
+        assert_eq!(expand_str("Bearer ${GROQ_API_KEY}!", &lookup), "Bearer gsk_123!");
+        assert_eq!(expand_str("${MISSING} and ${", &lookup), "${MISSING} and ${");
+
+        let mut value: toml::Value = toml::from_str("[models.groq]\napi_key = \"${GROQ_API_KEY}\"\nlist = [\"${GROQ_API_KEY}\"]").unwrap();
+        expand_env_vars(&mut value, &lookup);
+        assert_eq!(value["models"]["groq"]["api_key"].as_str(), Some("gsk_123"));
+        assert_eq!(value["models"]["groq"]["list"][0].as_str(), Some("gsk_123"));
+    }
+
+    #[test]
+    fn test_encrypt_key_lines() {
+        let content = "[models.groq]\napi_key = \"gsk_123\"  # key\nmodel_id = \"llama\"\n\n[search]\nserper_api_key = \"${SERPER}\"\nbing_api_key = \"abc\"\n[models.openrouter]\napi_key = \"YOUR_OPENROUTER_API_KEY_HERE\"\n";
+        let (updated, count) = encrypt_key_lines(content, &|plain| Ok(format!("enc:{}", plain.len()))).unwrap();
+        assert_eq!(count, 2);
+        assert!(updated.contains("api_key = \"enc:7\"  # key\n"));
+        assert!(updated.contains("bing_api_key = \"enc:3\""));
+        assert!(updated.contains("serper_api_key = \"${SERPER}\""));
+        assert!(updated.contains("YOUR_OPENROUTER_API_KEY_HERE"));
+
+        // Already encrypted values are not encrypted twice
+        let (_, again) = encrypt_key_lines(&updated, &|plain| Ok(format!("enc:{}", plain))).unwrap();
+        assert_eq!(again, 0);
+
+        // Single-quoted and multi-line keys are encrypted too, whole
+        let content = "api_key = 'gsk_1234'  # key\nserper_api_key = \"\"\"\nabcdef\"\"\"\nmodel_id = 'llama'\n";
+        let (updated, count) = encrypt_key_lines(content, &|plain| Ok(format!("enc:{}", plain.len()))).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(updated, "api_key = \"enc:8\"  # key\nserper_api_key = \"enc:6\"\nmodel_id = 'llama'\n");
+    }
+
+    #[test]
+    fn test_merge_tables() {
+        let mut base: toml::Table = "[visuals]\nx_axis = 0\nsize = 5\n[browser]\ndry_run = false".parse().unwrap();
+        let overlay: toml::Table = "[visuals]\nx_axis = 1920\n[browser]\nbinary_path = 'C:\\Chromium\\chrome.exe'".parse().unwrap();
+        merge_tables(&mut base, overlay);
+        assert_eq!(base["visuals"]["x_axis"].as_integer(), Some(1920));
+        assert_eq!(base["visuals"]["size"].as_integer(), Some(5));
+        assert_eq!(base["browser"]["dry_run"].as_bool(), Some(false));
+        assert!(base["browser"]["binary_path"].as_str().unwrap().ends_with("chrome.exe"));
+    }
+
+    #[test]
+    fn test_cli_overrides() {
+        let args: Vec<String> = ["shadow_prompt.exe", "--debug", "--set", "models.provider=ollama", "--set=search.enabled=false"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(cli_overrides(&args), vec!["models.provider=ollama", "search.enabled=false"]);
+    }
+
+    #[test]
+    fn test_apply_override() {
+        let config = Config::default();
+        let config = config.apply_override("models.provider=ollama").unwrap();
+        assert_eq!(config.models.provider, "ollama");
+        let config = config.apply_override("search.max_results = 7").unwrap();
+        assert_eq!(config.search.max_results, 7);
+        let config = config.apply_override("browser.dry_run=true").unwrap();
+        assert!(config.browser.dry_run);
+
+        assert!(config.apply_override("search.max_results=lots").is_err());
+        assert!(config.apply_override("nosuch.key=1").is_err());
+        assert!(config.apply_override("provider=ollama").is_err());
+    }
+}
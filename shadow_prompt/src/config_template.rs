@@ -0,0 +1,194 @@
+//! Documented Default Config
+//! `--init-config [path]` writes every field of `Config::default()` with a comment taken
+//! from config/config.example.toml, which is the one place config keys are documented.
+//! Optional keys that are unset by default are written commented out.
+
+use crate::config::{get_exe_dir, Config};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const EXAMPLE: &str = include_str!("../config/config.example.toml");
+
+/// Comments for one key of the example file
+#[derive(Default)]
+struct KeyDoc {
+    /// Full-line comments directly above the key
+    above: Vec<String>,
+    /// Trailing "# ..." on the key's line, plus indented comment lines right after it
+    inline: Vec<String>,
+    /// The example line itself, for keys the defaults leave unset
+    line: String,
+}
+
+/// `--init-config [path]`; refuses to overwrite an existing file
+pub fn run(args: &[String]) -> Result<PathBuf> {
+    let path = args
+        .iter()
+        .skip_while(|a| *a != "--init-config")
+        .nth(1)
+        .filter(|a| !a.starts_with("--"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| get_exe_dir().join("config").join("config.toml"));
+    if path.exists() {
+        anyhow::bail!("{} already exists. Pass another path: --init-config <file>", path.display());
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&path, documented_default()?).context("Failed to write the config file")?;
+    Ok(path)
+}
+
+pub fn documented_default() -> Result<String> {
+    let defaults = toml::to_string_pretty(&Config::default()).context("Failed to serialize the default config")?;
+    Ok(annotate(&defaults, &parse_example(EXAMPLE)))
+}
+
+fn key_line() -> Regex {
+    Regex::new(r"^\s*(#\s*)?([A-Za-z_][A-Za-z0-9_]*)\s*=\s*(.*?)(?:\s{2,}#\s*(.*))?$").unwrap()
+}
+
+fn section_line() -> Regex {
+    Regex::new(r"^\s*(?:#\s*)?\[([A-Za-z0-9_.]+)\]\s*$").unwrap()
+}
+
+/// (section, key) -> comments, in example order per section
+fn parse_example(example: &str) -> HashMap<(String, String), KeyDoc> {
+    let (key_re, section_re) = (key_line(), section_line());
+    let mut docs: HashMap<(String, String), KeyDoc> = HashMap::new();
+    let mut section = String::new();
+    let mut pending: Vec<String> = Vec::new();
+    let mut last_key: Option<(String, String)> = None;
+
+    for line in example.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            pending.clear();
+            last_key = None;
+        } else if let Some(cap) = section_re.captures(line) {
+            section = cap[1].to_string();
+            pending.clear();
+            last_key = None;
+        } else if let Some(cap) = key_re.captures(line) {
+            let key = (section.clone(), cap[2].to_string());
+            let doc = docs.entry(key.clone()).or_default();
+            doc.above = std::mem::take(&mut pending);
+            doc.inline = cap.get(4).map(|c| vec![c.as_str().trim().to_string()]).unwrap_or_default();
+            doc.line = if cap.get(1).is_some() { line.trim().to_string() } else { format!("# {}", line.trim()) };
+            last_key = Some(key);
+        } else if let Some(comment) = trimmed.strip_prefix('#') {
+            let comment = comment.trim().to_string();
+            match &last_key {
+                // Indented comment right under a key continues that key's comment
+                Some(key) if line.starts_with(char::is_whitespace) => {
+                    docs.get_mut(key).unwrap().inline.push(comment);
+                }
+                _ => {
+                    last_key = None;
+                    pending.push(comment);
+                }
+            }
+        }
+    }
+    docs
+}
+
+/// The serialized defaults with the example's comments; documented keys missing from the
+/// defaults (unset options) are added commented out at the end of their section
+fn annotate(defaults: &str, docs: &HashMap<(String, String), KeyDoc>) -> String {
+    let (key_re, section_re) = (key_line(), section_line());
+    let mut out = String::from(
+        "# ShadowPrompt configuration with every setting at its default (shadow_prompt.exe --init-config)\n\
+         # Commented-out keys are optional and unset by default.\n",
+    );
+    let mut section = String::new();
+    let mut written: Vec<String> = Vec::new();
+
+    let flush_unset = |out: &mut String, section: &str, written: &[String]| {
+        let mut unset: Vec<(&String, &KeyDoc)> = docs
+            .iter()
+            .filter(|((s, k), _)| s == section && !written.contains(k))
+            .map(|((_, k), doc)| (k, doc))
+            .collect();
+        unset.sort_by(|a, b| a.0.cmp(b.0));
+        for (_, doc) in unset {
+            out.push_str(&doc.line);
+            out.push('\n');
+        }
+    };
+
+    for line in defaults.lines() {
+        if let Some(cap) = section_re.captures(line) {
+            flush_unset(&mut out, &section, &written);
+            section = cap[1].to_string();
+            written.clear();
+            out.push('\n');
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        }
+        let documented = key_re
+            .captures(line)
+            .filter(|cap| cap.get(1).is_none())
+            .and_then(|cap| {
+                let key = cap[2].to_string();
+                written.push(key.clone());
+                docs.get(&(section.clone(), key))
+            });
+        match documented {
+            Some(doc) => {
+                for comment in &doc.above {
+                    out.push_str(&format!("# {}\n", comment));
+                }
+                out.push_str(line);
+                if !doc.inline.is_empty() {
+                    out.push_str(&format!("  # {}", doc.inline.join(" ")));
+                }
+                out.push('\n');
+            }
+            None if line.trim().is_empty() => {}
+            None => {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    flush_unset(&mut out, &section, &written);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_SNIPPET: &str = "[general]\nmode = \"stealth\"\n# Set any hotkey to \"\" to disable it\nwake_key = \"Ctrl+Shift+Space\"    # OCR capture mode\n\n[visuals]\ncolor = \"#FF0000\"\noutput_template = \"raw\"          # \"raw\" or \"clean\"\n                                 # Placeholders: {answer}\n\n# [browser]\n# binary_path = 'C:\\brave.exe'  # Detected when unset\n";
+
+    #[test]
+    fn test_parse_example() {
+        let docs = parse_example(EXAMPLE_SNIPPET);
+        let wake = &docs[&("general".to_string(), "wake_key".to_string())];
+        assert_eq!(wake.above, vec!["Set any hotkey to \"\" to disable it"]);
+        assert_eq!(wake.inline, vec!["OCR capture mode"]);
+        // A '#' inside a value is not a comment
+        assert!(docs[&("visuals".to_string(), "color".to_string())].inline.is_empty());
+        assert_eq!(docs[&("visuals".to_string(), "output_template".to_string())].inline.len(), 2);
+        assert_eq!(docs[&("browser".to_string(), "binary_path".to_string())].line, "# binary_path = 'C:\\brave.exe'  # Detected when unset");
+    }
+
+    #[test]
+    fn test_annotate_adds_comments_and_unset_keys() {
+        let docs = parse_example(EXAMPLE_SNIPPET);
+        let out = annotate("[general]\nmode = \"stealth\"\nwake_key = \"Ctrl+Shift+Space\"\n\n[browser]\ndry_run = false\n", &docs);
+        assert!(out.contains("# Set any hotkey to \"\" to disable it\nwake_key = \"Ctrl+Shift+Space\"  # OCR capture mode\n"));
+        assert!(out.contains("[browser]\ndry_run = false\n# binary_path = 'C:\\brave.exe'"));
+    }
+
+    #[test]
+    fn test_documented_default_round_trips() {
+        let text = documented_default().unwrap();
+        let parsed: Config = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.general.mode, Config::default().general.mode);
+    }
+}
@@ -0,0 +1,129 @@
+//! Crash Reporting
+//! The release build has no console, so a panic would otherwise vanish. The hook appends
+//! the message and a backtrace to data/logs/crash.log, turns the indicator
+//! `visuals.color_crash` while the UI thread is still up, and with
+//! `general.restart_on_crash` starts a fresh copy (at most `MAX_RESTARTS` in a row).
+
+use crate::config::get_exe_dir;
+use crate::ui::{IndicatorState, UICommand};
+use std::backtrace::Backtrace;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+pub const CRASH_LOG: &str = "crash.log";
+
+/// Restarts counted across relaunches, so a crash at startup doesn't loop forever
+const RESTARTS_ENV: &str = "SHADOW_PROMPT_CRASH_RESTARTS";
+const MAX_RESTARTS: u32 = 3;
+/// A copy that ran this long before crashing starts the count again
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+/// How long a dying main thread keeps the process (and the crash color) up
+const LAST_GASP: Duration = Duration::from_secs(3);
+
+static RESTART: AtomicBool = AtomicBool::new(false);
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+fn ui() -> &'static Mutex<Option<Sender<UICommand>>> {
+    static UI: OnceLock<Mutex<Option<Sender<UICommand>>>> = OnceLock::new();
+    UI.get_or_init(|| Mutex::new(None))
+}
+
+/// Install the hook; call once, right after the logger
+pub fn install() {
+    STARTED.get_or_init(Instant::now);
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "(no message)".to_string());
+        let location = info.location().map(|l| l.to_string()).unwrap_or_else(|| "unknown location".to_string());
+        let thread = std::thread::current().name().unwrap_or("unnamed").to_string();
+        let report = report(&thread, &location, &message, &Backtrace::force_capture().to_string());
+        write_report(&report);
+        log::error!("{}", report.lines().next().unwrap_or("panic"));
+        eprintln!("{}", report);
+
+        // try_lock: the panic may have happened while the sender was being swapped
+        let flashed = ui()
+            .try_lock()
+            .ok()
+            .and_then(|ui| ui.as_ref().map(|tx| tx.send(UICommand::SetState(IndicatorState::Crashed)).is_ok()))
+            .unwrap_or(false);
+
+        if RESTART.load(Ordering::Relaxed) && relaunch() {
+            std::process::exit(1);
+        }
+        // The main loop unwinding takes the indicator window with it; give it a moment
+        if flashed && std::thread::current().name() == Some("main") {
+            std::thread::sleep(LAST_GASP);
+        }
+        default_hook(info);
+    }));
+}
+
+/// Let the hook reach the indicator, and pick up `general.restart_on_crash`
+pub fn attach_ui(ui_tx: Sender<UICommand>, restart_on_crash: bool) {
+    *ui().lock().unwrap() = Some(ui_tx);
+    RESTART.store(restart_on_crash, Ordering::Relaxed);
+}
+
+/// One crash.log entry; panic messages can quote a request, so keys are masked
+fn report(thread: &str, location: &str, message: &str, backtrace: &str) -> String {
+    format!(
+        "[{}] v{} panicked on thread '{}' at {}: {}\n{}\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        env!("CARGO_PKG_VERSION"),
+        thread,
+        location,
+        crate::logger::redact(message),
+        backtrace
+    )
+}
+
+fn write_report(report: &str) {
+    let dir = get_exe_dir().join("data").join("logs");
+    let _ = std::fs::create_dir_all(&dir);
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(dir.join(CRASH_LOG)) {
+        let _ = writeln!(file, "{}", report);
+    }
+}
+
+/// Start a new copy with the same arguments; false once `MAX_RESTARTS` is used up
+fn relaunch() -> bool {
+    let stable = STARTED.get().is_some_and(|started| started.elapsed() >= STABLE_UPTIME);
+    let restarts: u32 = if stable {
+        0
+    } else {
+        std::env::var(RESTARTS_ENV).ok().and_then(|v| v.parse().ok()).unwrap_or(0)
+    };
+    if restarts >= MAX_RESTARTS {
+        eprintln!("[!] Crashed {} times in a row; not restarting again", restarts);
+        return false;
+    }
+    let Ok(exe) = std::env::current_exe() else { return false };
+    std::process::Command::new(exe)
+        .args(std::env::args().skip(1))
+        .env(RESTARTS_ENV, (restarts + 1).to_string())
+        .spawn()
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let report = report("tokio-runtime-worker", "src/llm.rs:42:9", "bad key gsk_abcdefgh12345678", "   0: main\n");
+        let first = report.lines().next().unwrap();
+        assert!(first.ends_with("panicked on thread 'tokio-runtime-worker' at src/llm.rs:42:9: bad key gsk_***"));
+        assert!(report.contains("   0: main"));
+    }
+}
@@ -0,0 +1,35 @@
+//! DPAPI
+//! Thin wrapper over CryptProtectData/CryptUnprotectData. Protected blobs can only be
+//! unprotected by the same Windows account, so they don't travel with the USB drive.
+
+use anyhow::Result;
+use windows::Win32::Foundation::{LocalFree, HLOCAL};
+use windows::Win32::Security::Cryptography::{
+    CryptProtectData, CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+};
+use windows::core::PCWSTR;
+
+pub fn protect(data: &[u8]) -> Result<Vec<u8>> {
+    let input = CRYPT_INTEGER_BLOB { cbData: data.len() as u32, pbData: data.as_ptr() as *mut u8 };
+    let mut output = CRYPT_INTEGER_BLOB::default();
+    unsafe {
+        CryptProtectData(&input, PCWSTR::null(), None, None, None, CRYPTPROTECT_UI_FORBIDDEN, &mut output)?;
+        Ok(take_blob(output))
+    }
+}
+
+pub fn unprotect(data: &[u8]) -> Result<Vec<u8>> {
+    let input = CRYPT_INTEGER_BLOB { cbData: data.len() as u32, pbData: data.as_ptr() as *mut u8 };
+    let mut output = CRYPT_INTEGER_BLOB::default();
+    unsafe {
+        CryptUnprotectData(&input, None, None, None, None, CRYPTPROTECT_UI_FORBIDDEN, &mut output)?;
+        Ok(take_blob(output))
+    }
+}
+
+/// Copy a DPAPI-allocated blob and release it
+unsafe fn take_blob(blob: CRYPT_INTEGER_BLOB) -> Vec<u8> {
+    let bytes = std::slice::from_raw_parts(blob.pbData, blob.cbData as usize).to_vec();
+    let _ = LocalFree(HLOCAL(blob.pbData as _));
+    bytes
+}
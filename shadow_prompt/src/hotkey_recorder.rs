@@ -2,6 +2,8 @@
 //! Provides UI component for recording keyboard shortcuts during setup.
 //! Uses egui's native input handling for capturing hotkeys when focused.
 
+use crate::config::Config;
+use crate::input::HotkeyBindings;
 use eframe::egui;
 use std::collections::HashSet;
 
@@ -16,6 +18,8 @@ pub struct HotkeyRecorder {
     is_recording: bool,
     current_keys: HashSet<egui::Key>,
     current_modifiers: egui::Modifiers,
+    /// Middle / side mouse buttons, in press order
+    current_mouse: Vec<&'static str>,
     recorded_result: Option<RecordedHotkey>,
 }
 
@@ -31,6 +35,7 @@ impl HotkeyRecorder {
             is_recording: false,
             current_keys: HashSet::new(),
             current_modifiers: egui::Modifiers::NONE,
+            current_mouse: Vec::new(),
             recorded_result: None,
         }
     }
@@ -40,12 +45,14 @@ impl HotkeyRecorder {
         self.recorded_result = None;
         self.current_keys.clear();
         self.current_modifiers = egui::Modifiers::NONE;
+        self.current_mouse.clear();
     }
 
     pub fn cancel(&mut self) {
         self.is_recording = false;
         self.current_keys.clear();
         self.current_modifiers = egui::Modifiers::NONE;
+        self.current_mouse.clear();
     }
 
     /// Process input from egui and return the recorded hotkey if complete
@@ -60,18 +67,27 @@ impl HotkeyRecorder {
 
             // Track pressed keys
             for event in &i.events {
-                if let egui::Event::Key { key, pressed, .. } = event {
-                    if *pressed {
+                match event {
+                    egui::Event::Key { key, pressed: true, .. } => {
                         self.current_keys.insert(*key);
                     }
+                    egui::Event::PointerButton { button, pressed: true, .. } => {
+                        if let Some(name) = mouse_button_name(*button) {
+                            if !self.current_mouse.contains(&name) {
+                                self.current_mouse.push(name);
+                            }
+                        }
+                    }
+                    _ => {}
                 }
             }
 
-            // Check if any key was released this frame
-            let any_key_released = i
-                .events
-                .iter()
-                .any(|e| matches!(e, egui::Event::Key { pressed: false, .. }));
+            // Check if any key (or a bindable mouse button) was released this frame
+            let any_key_released = i.events.iter().any(|e| match e {
+                egui::Event::Key { pressed: false, .. } => true,
+                egui::Event::PointerButton { button, pressed: false, .. } => mouse_button_name(*button).is_some(),
+                _ => false,
+            });
 
             // When a key is released, check if we have a valid combination
             if any_key_released && self.has_valid_combination() {
@@ -92,8 +108,8 @@ impl HotkeyRecorder {
             || self.current_modifiers.alt;
         let has_key = !self.current_keys.is_empty();
 
-        // Valid: modifier + any key, or 2+ regular keys
-        (has_modifier && has_key) || self.current_keys.len() >= 2
+        // Valid: modifier + any key, or 2+ regular keys, or any extra mouse button
+        (has_modifier && has_key) || self.current_keys.len() >= 2 || !self.current_mouse.is_empty()
     }
 
     pub fn is_recording(&self) -> bool {
@@ -119,6 +135,10 @@ impl HotkeyRecorder {
             parts.push(key_to_string(*key));
         }
 
+        for button in &self.current_mouse {
+            parts.push(button.to_string());
+        }
+
         if parts.is_empty() {
             "Press a key combination...".to_string()
         } else {
@@ -127,6 +147,16 @@ impl HotkeyRecorder {
     }
 }
 
+/// Mouse buttons that can be bound (left/right stay reserved for normal use and OCR selection)
+fn mouse_button_name(button: egui::PointerButton) -> Option<&'static str> {
+    match button {
+        egui::PointerButton::Middle => Some("Middle"),
+        egui::PointerButton::Extra1 => Some("Mouse4"),
+        egui::PointerButton::Extra2 => Some("Mouse5"),
+        _ => None,
+    }
+}
+
 /// UI widget for hotkey recording
 pub fn hotkey_field(
     ui: &mut egui::Ui,
@@ -169,6 +199,7 @@ pub fn hotkey_field(
             // Normal mode
             ui.add(
                 egui::TextEdit::singleline(current_value)
+                    .hint_text("Disabled")
                     .desired_width(200.0)
                     .interactive(false),
             );
@@ -176,38 +207,34 @@ pub fn hotkey_field(
             if ui.button("Record").clicked() {
                 recorder.start_recording();
             }
+            if !current_value.is_empty() && ui.button("Clear").on_hover_text("Disable this hotkey").clicked() {
+                current_value.clear();
+                changed = true;
+            }
         }
     });
 
     changed
 }
 
-/// Validate hotkeys don't conflict
-pub fn validate_hotkeys(
-    wake: &str,
-    model: &str,
-    panic: &str,
-    hide: Option<&str>,
-) -> Result<(), String> {
-    if wake == model {
-        return Err("Wake and Model hotkeys cannot be the same".to_string());
-    }
-    if wake == panic {
-        return Err("Wake and Panic hotkeys cannot be the same".to_string());
-    }
-    if model == panic {
-        return Err("Model and Panic hotkeys cannot be the same".to_string());
+/// Validate that no two hotkeys (every `HotkeyBindings::named` binding) are the same. An
+/// empty binding disables that hotkey and never conflicts; only the panic key must stay bound.
+pub fn validate_hotkeys(config: &Config) -> Result<(), String> {
+    if config.general.panic_key.trim().is_empty() {
+        return Err("Panic hotkey cannot be disabled".to_string());
     }
-    if let Some(hide_key) = hide {
-        if !hide_key.is_empty() {
-            if wake == hide_key {
-                return Err("Wake and Hide hotkeys cannot be the same".to_string());
-            }
-            if model == hide_key {
-                return Err("Model and Hide hotkeys cannot be the same".to_string());
-            }
-            if panic == hide_key {
-                return Err("Panic and Hide hotkeys cannot be the same".to_string());
+
+    // "Ctrl + Shift + Z" and "ctrl+shift+z" are the same binding
+    let normalize = |binding: &str| binding.split_whitespace().collect::<String>().to_lowercase();
+    let bindings: Vec<(&str, String)> = HotkeyBindings::named(config)
+        .into_iter()
+        .map(|(name, binding)| (name, normalize(binding)))
+        .filter(|(_, binding)| !binding.is_empty())
+        .collect();
+    for (i, (name_a, key_a)) in bindings.iter().enumerate() {
+        for (name_b, key_b) in &bindings[i + 1..] {
+            if key_a == key_b {
+                return Err(format!("{} and {} cannot be the same hotkey", name_a, name_b));
             }
         }
     }
@@ -287,3 +314,52 @@ fn key_to_string(key: egui::Key) -> String {
     }
     .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::validate_hotkeys;
+    use crate::config::Config;
+
+    /// Only the panic key bound, everything else disabled
+    fn unbound() -> Config {
+        let mut config = Config::default();
+        let general = &mut config.general;
+        for key in [
+            &mut general.wake_key, &mut general.model_key, &mut general.key_browser_pass, &mut general.key_browser_exec,
+            &mut general.key_browser_exec_single, &mut general.key_browser_abort, &mut general.key_browser_incognito,
+            &mut general.key_browser_pause, &mut general.key_clipboard_cycle, &mut general.key_rag_capture,
+            &mut general.key_log_viewer, &mut general.key_log_dump,
+        ] {
+            key.clear();
+        }
+        config.visuals.hide_key.clear();
+        config
+    }
+
+    #[test]
+    fn test_empty_bindings_are_disabled_not_conflicts() {
+        assert!(validate_hotkeys(&Config::default()).is_ok());
+        assert!(validate_hotkeys(&unbound()).is_ok());
+
+        let mut config = unbound();
+        config.general.panic_key.clear();
+        assert!(validate_hotkeys(&config).is_err());
+
+        let mut config = unbound();
+        config.general.wake_key = "Ctrl+Shift+Z".to_string();
+        config.general.model_key = "ctrl + shift + z".to_string();
+        assert_eq!(validate_hotkeys(&config), Err("wake_key and model_key cannot be the same hotkey".to_string()));
+    }
+
+    #[test]
+    fn test_every_binding_is_checked() {
+        let mut config = unbound();
+        config.general.key_log_dump = "Ctrl+Alt+J".to_string();
+        config.general.key_rag_capture = "Ctrl+Alt+J".to_string();
+        assert_eq!(validate_hotkeys(&config), Err("key_rag_capture and key_log_dump cannot be the same hotkey".to_string()));
+
+        let mut config = unbound();
+        config.general.key_browser_pause = "Ctrl+Shift+F12".to_string();
+        assert!(validate_hotkeys(&config).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! Localization
+//! User-facing wizard and overlay text lives in config/locales/<lang>.toml, picked with
+//! `general.language`. English is compiled in, so a missing file or key falls back to it.
+//! Nested tables are flattened to dotted keys: `[wizard.nav] next = "..."` is `wizard.nav.next`.
+
+use crate::config::get_exe_dir;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+const EN: &str = include_str!("../config/locales/en.toml");
+
+struct Locale {
+    strings: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+fn locale() -> &'static RwLock<Locale> {
+    static LOCALE: OnceLock<RwLock<Locale>> = OnceLock::new();
+    LOCALE.get_or_init(|| {
+        let fallback = parse(EN);
+        RwLock::new(Locale { strings: HashMap::new(), fallback })
+    })
+}
+
+/// Switch to `language` (a file stem under config/locales); unknown languages keep English
+pub fn set_language(language: &str) {
+    let language = language.trim();
+    let strings = if language.is_empty() || language == DEFAULT_LANGUAGE {
+        HashMap::new()
+    } else {
+        let path = get_exe_dir().join("config").join("locales").join(format!("{}.toml", language));
+        match std::fs::read_to_string(&path) {
+            Ok(content) => parse(&content),
+            Err(e) => {
+                eprintln!("[-] Language '{}' not available ({}), using English", language, e);
+                HashMap::new()
+            }
+        }
+    };
+    locale().write().unwrap().strings = strings;
+}
+
+/// Languages with a file in config/locales, English first
+pub fn available() -> Vec<String> {
+    let mut languages: Vec<String> = std::fs::read_dir(get_exe_dir().join("config").join("locales"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("toml"))
+                .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+                .filter(|stem| stem != DEFAULT_LANGUAGE)
+                .collect()
+        })
+        .unwrap_or_default();
+    languages.sort();
+    languages.insert(0, DEFAULT_LANGUAGE.to_string());
+    languages
+}
+
+/// The text for `key` in the current language, else English, else the key itself
+pub fn t(key: &str) -> String {
+    let locale = locale().read().unwrap();
+    locale
+        .strings
+        .get(key)
+        .or_else(|| locale.fallback.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// `t` with `{name}` placeholders filled in
+pub fn tf(key: &str, args: &[(&str, &str)]) -> String {
+    fill(&t(key), args)
+}
+
+fn fill(text: &str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(text.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+fn parse(content: &str) -> HashMap<String, String> {
+    let mut strings = HashMap::new();
+    match content.parse::<toml::Table>() {
+        Ok(table) => flatten("", &table, &mut strings),
+        Err(e) => eprintln!("[-] Invalid locale file: {}", e),
+    }
+    strings
+}
+
+fn flatten(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            toml::Value::Table(inner) => flatten(&key, inner, out),
+            toml::Value::String(text) => {
+                out.insert(key, text.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_fill() {
+        let strings = parse("[wizard.nav]\nnext = \"Weiter →\"\n[overlay]\nhistory = \"Verlauf {position}/{count}\"\n");
+        assert_eq!(strings["wizard.nav.next"], "Weiter →");
+        assert_eq!(fill(&strings["overlay.history"], &[("position", "2"), ("count", "5")]), "Verlauf 2/5");
+    }
+
+    #[test]
+    fn test_english_fallback() {
+        set_language("no-such-language");
+        assert_eq!(t("wizard.nav.next"), "Next →");
+        assert_eq!(t("wizard.no_such_key"), "wizard.no_such_key");
+    }
+}
@@ -1,7 +1,15 @@
-use rdev::{listen, Button, Event, EventType, Key};
+use rdev::{listen, Button, EventType, Key};
 use std::collections::HashSet;
-use std::sync::mpsc::Sender;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
+use crate::config::Config;
+use crate::utils::{key_matches, mouse_button_key, parse_double_tap, parse_keys, unknown_keys};
+
+/// Minimum gap between selection preview updates (~60 fps)
+const DRAG_UPDATE_INTERVAL: Duration = Duration::from_millis(16);
 
 pub enum InputEvent {
     Wake,
@@ -9,126 +17,423 @@ pub enum InputEvent {
     Panic,
     OCRClick1,
     OCRRect(i32, i32, i32, i32), // x, y, w, h
+    OCRDrag(i32, i32, i32, i32), // Live selection preview, same layout as OCRRect
     HideToggle,
     BrowserPass,
     BrowserExec,
     BrowserExecSingle,
     BrowserAbort,
     BrowserIncognito,
+    BrowserPause,
+    ClipboardCycle,
+    RagCapture,
+    /// Plain Ctrl+V, used to restore the user's clipboard after an answer is pasted
+    Pasted,
+    /// Lone digit key 1-9 (skips that question while the form flow is paused)
+    SkipQuestion(usize),
+    LogViewerToggle,
+    LogDump,
 }
 
-#[allow(dead_code)]
-pub struct InputManager {
-    wake_key_combo: Vec<Key>,
-    model_key_combo: Vec<Key>,
-    panic_key_combo: Vec<Key>,
-    sender: Sender<InputEvent>,
+impl InputEvent {
+    /// The `HotkeyBindings::named` key whose hotkey produced this event
+    pub fn binding_name(&self) -> Option<&'static str> {
+        Some(match self {
+            InputEvent::Wake => "wake_key",
+            InputEvent::Model => "model_key",
+            InputEvent::Panic => "panic_key",
+            InputEvent::HideToggle => "hide_key",
+            InputEvent::BrowserPass => "key_browser_pass",
+            InputEvent::BrowserExec => "key_browser_exec",
+            InputEvent::BrowserExecSingle => "key_browser_exec_single",
+            InputEvent::BrowserAbort => "key_browser_abort",
+            InputEvent::BrowserIncognito => "key_browser_incognito",
+            InputEvent::BrowserPause => "key_browser_pause",
+            InputEvent::ClipboardCycle => "key_clipboard_cycle",
+            InputEvent::RagCapture => "key_rag_capture",
+            InputEvent::LogViewerToggle => "key_log_viewer",
+            InputEvent::LogDump => "key_log_dump",
+            _ => return None,
+        })
+    }
+}
+
+/// Which global hook implementation feeds the input state machine (`general.input_backend`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputBackend {
+    Rdev,
+    /// Native SetWindowsHookEx low-level keyboard/mouse hooks
+    Win32,
+}
+
+impl InputBackend {
+    pub fn from_config(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "win32" | "native" => InputBackend::Win32,
+            "rdev" => InputBackend::Rdev,
+            other => {
+                eprintln!("Warning: Unknown input backend '{}', using rdev", other);
+                InputBackend::Rdev
+            }
+        }
+    }
+}
+
+/// How a binding fires: holding a key combo, or tapping one key twice quickly
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trigger {
+    Combo(Vec<Key>),
+    DoubleTap(Key),
+}
+
+impl Trigger {
+    pub fn parse(config_str: &str) -> Self {
+        match parse_double_tap(config_str) {
+            Some(key) => Trigger::DoubleTap(key),
+            None => Trigger::Combo(parse_keys(config_str)),
+        }
+    }
+
+    fn fired(&self, pressed: &HashSet<Key>, double_tapped: Option<Key>) -> bool {
+        match self {
+            Trigger::Combo(keys) => check_combo(pressed, keys),
+            Trigger::DoubleTap(key) => double_tapped.is_some_and(|tapped| key_matches(*key, tapped)),
+        }
+    }
+}
+
+pub struct HotkeyBindings {
+    pub wake: Trigger,
+    pub model: Trigger,
+    pub panic: Trigger,
+    pub hide: Trigger,
+    pub browser_pass: Trigger,
+    pub browser_exec: Trigger,
+    pub browser_exec_single: Trigger,
+    pub browser_abort: Trigger,
+    pub browser_incognito: Trigger,
+    pub browser_pause: Trigger,
+    pub clipboard_cycle: Trigger,
+    pub rag_capture: Trigger,
+    pub log_viewer: Trigger,
+    pub log_dump: Trigger,
+    /// Max gap between the two presses of a double-tap
+    pub double_tap_window: Duration,
+    /// Lone digits become `SkipQuestion` only while this is set (the form flow's pause flag)
+    pub skip_digits: Arc<AtomicBool>,
+}
+
+impl HotkeyBindings {
+    /// Every bindable hotkey as (config key, binding string)
+    pub fn named(config: &Config) -> Vec<(&'static str, &str)> {
+        vec![
+            ("wake_key", config.general.wake_key.as_str()),
+            ("model_key", config.general.model_key.as_str()),
+            ("panic_key", config.general.panic_key.as_str()),
+            ("hide_key", config.visuals.hide_key.as_str()),
+            ("key_browser_pass", config.general.key_browser_pass.as_str()),
+            ("key_browser_exec", config.general.key_browser_exec.as_str()),
+            ("key_browser_exec_single", config.general.key_browser_exec_single.as_str()),
+            ("key_browser_abort", config.general.key_browser_abort.as_str()),
+            ("key_browser_incognito", config.general.key_browser_incognito.as_str()),
+            ("key_browser_pause", config.general.key_browser_pause.as_str()),
+            ("key_clipboard_cycle", config.general.key_clipboard_cycle.as_str()),
+            ("key_rag_capture", config.general.key_rag_capture.as_str()),
+            ("key_log_viewer", config.general.key_log_viewer.as_str()),
+            ("key_log_dump", config.general.key_log_dump.as_str()),
+        ]
+    }
+
+    /// One message per binding containing keys `parse_keys` does not know
+    pub fn invalid(config: &Config) -> Vec<String> {
+        Self::named(config)
+            .into_iter()
+            .filter(|(_, binding)| parse_double_tap(binding).is_none())
+            .filter_map(|(name, binding)| {
+                let unknown = unknown_keys(binding);
+                (!unknown.is_empty()).then(|| format!("{} \"{}\": unknown key(s) {}", name, binding, unknown.join(", ")))
+            })
+            .collect()
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            wake: Trigger::parse(&config.general.wake_key),
+            model: Trigger::parse(&config.general.model_key),
+            panic: Trigger::parse(&config.general.panic_key),
+            hide: Trigger::parse(&config.visuals.hide_key),
+            browser_pass: Trigger::parse(&config.general.key_browser_pass),
+            browser_exec: Trigger::parse(&config.general.key_browser_exec),
+            browser_exec_single: Trigger::parse(&config.general.key_browser_exec_single),
+            browser_abort: Trigger::parse(&config.general.key_browser_abort),
+            browser_incognito: Trigger::parse(&config.general.key_browser_incognito),
+            browser_pause: Trigger::parse(&config.general.key_browser_pause),
+            clipboard_cycle: Trigger::parse(&config.general.key_clipboard_cycle),
+            rag_capture: Trigger::parse(&config.general.key_rag_capture),
+            log_viewer: Trigger::parse(&config.general.key_log_viewer),
+            log_dump: Trigger::parse(&config.general.key_log_dump),
+            double_tap_window: Duration::from_millis(config.general.double_tap_window_ms),
+            skip_digits: Arc::new(AtomicBool::new(false)),
+        }
+    }
 }
 
+pub struct InputManager;
+
 impl InputManager {
-    pub fn start(
-        wake_keys: Vec<Key>,
-        model_keys: Vec<Key>,
-        panic_keys: Vec<Key>,
-        hide_keys: Vec<Key>,
-        b_pass_keys: Vec<Key>,
-        b_exec_keys: Vec<Key>,
-        b_exec_single_keys: Vec<Key>,
-        b_abort_keys: Vec<Key>,
-        b_incognito_keys: Vec<Key>,
-        sender: Sender<InputEvent>,
-    ) {
+    pub fn start(bindings: HotkeyBindings, backend: InputBackend, sender: Sender<InputEvent>) {
+        Self::spawn(bindings, backend, sender, None);
+    }
+
+    /// Like `start`, but bindings sent on the returned channel replace the current ones while
+    /// the hook keeps running (hooks can't be removed; the wizard's test page re-binds instead)
+    pub fn start_rebindable(bindings: HotkeyBindings, backend: InputBackend, sender: Sender<InputEvent>) -> Sender<HotkeyBindings> {
+        let (rebind_tx, rebind_rx) = mpsc::channel();
+        Self::spawn(bindings, backend, sender, Some(rebind_rx));
+        rebind_tx
+    }
+
+    fn spawn(bindings: HotkeyBindings, backend: InputBackend, sender: Sender<InputEvent>, rebind: Option<mpsc::Receiver<HotkeyBindings>>) {
         thread::spawn(move || {
-            let mut pressed_keys = HashSet::new();
-            let mut is_selecting = false;
-            let mut p1: Option<(f64, f64)> = None;
-            let mut current_pos = (0.0, 0.0);
-
-            // This closure needs to handle the state
-            let callback = move |event: Event| {
-                match event.event_type {
-                    EventType::KeyPress(key) => {
-                        pressed_keys.insert(key);
-
-                        // Check combos
-                        if check_combo(&pressed_keys, &panic_keys) {
-                            let _ = sender.send(InputEvent::Panic);
-                            is_selecting = false;
-                            p1 = None; // Reset
-                        } else if check_combo(&pressed_keys, &wake_keys) {
-                            let _ = sender.send(InputEvent::Wake);
-                            is_selecting = true; // Enter Selection Mode
-                            p1 = None;
-                            println!("[*] Input: Entering OCR Selection Mode");
-                        } else if check_combo(&pressed_keys, &model_keys) {
-                            let _ = sender.send(InputEvent::Model);
-                            is_selecting = false;
-                            p1 = None;
-                        } else if check_combo(&pressed_keys, &hide_keys) {
-                            let _ = sender.send(InputEvent::HideToggle);
-                            is_selecting = false;
-                            p1 = None;
-                        } else if check_combo(&pressed_keys, &b_pass_keys) {
-                            let _ = sender.send(InputEvent::BrowserPass);
-                            is_selecting = false;
-                            p1 = None;
-                        } else if check_combo(&pressed_keys, &b_exec_keys) {
-                            let _ = sender.send(InputEvent::BrowserExec);
-                            is_selecting = false;
-                            p1 = None;
-                        } else if check_combo(&pressed_keys, &b_exec_single_keys) {
-                            let _ = sender.send(InputEvent::BrowserExecSingle);
-                            is_selecting = false;
-                            p1 = None;
-                        } else if check_combo(&pressed_keys, &b_abort_keys) {
-                            let _ = sender.send(InputEvent::BrowserAbort);
-                            is_selecting = false;
-                            p1 = None;
-                        } else if check_combo(&pressed_keys, &b_incognito_keys) {
-                            let _ = sender.send(InputEvent::BrowserIncognito);
-                            is_selecting = false;
-                            p1 = None;
-                        }
+            let mut state = InputState::new(bindings, sender);
+            let mut handle = move |event: EventType| {
+                if let Some(bindings) = rebind.as_ref().and_then(|rx| rx.try_iter().last()) {
+                    state.bindings = bindings;
+                }
+                state.handle(event);
+            };
+
+            match backend {
+                InputBackend::Rdev => {
+                    if let Err(error) = listen(move |event| handle(event.event_type)) {
+                        eprintln!("Error: {:?}", error);
                     }
-                    EventType::KeyRelease(key) => {
-                        pressed_keys.remove(&key);
+                }
+                InputBackend::Win32 => {
+                    if let Err(error) = crate::input_win32::listen(handle) {
+                        eprintln!("Error: {:?}", error);
                     }
-                    EventType::MouseMove { x, y } => {
-                        current_pos = (x, y);
+                }
+            }
+        });
+    }
+}
+
+/// Hotkey and OCR-selection state machine shared by both hook backends
+struct InputState {
+    bindings: HotkeyBindings,
+    sender: Sender<InputEvent>,
+    pressed_keys: HashSet<Key>,
+    is_selecting: bool,
+    p1: Option<(f64, f64)>,
+    current_pos: (f64, f64),
+    last_drag_update: Instant,
+    /// Last lone key press, for double-tap detection
+    last_tap: Option<(Key, Instant)>,
+    /// Keys held when a hotkey last fired; set until one of them is released
+    latched: Option<HashSet<Key>>,
+}
+
+impl InputState {
+    fn new(bindings: HotkeyBindings, sender: Sender<InputEvent>) -> Self {
+        Self {
+            bindings,
+            sender,
+            pressed_keys: HashSet::new(),
+            is_selecting: false,
+            p1: None,
+            current_pos: (0.0, 0.0),
+            last_drag_update: Instant::now(),
+            last_tap: None,
+            latched: None,
+        }
+    }
+
+    /// Returns the key when this press completes a double-tap. Only presses of a key
+    /// held on its own count; auto-repeat and presses inside a chord reset the tap.
+    fn register_tap(&mut self, key: Key, is_repeat: bool) -> Option<Key> {
+        if is_repeat {
+            return None;
+        }
+        if self.pressed_keys.len() != 1 {
+            self.last_tap = None;
+            return None;
+        }
+
+        let now = Instant::now();
+        match self.last_tap {
+            Some((last, at)) if last == key && now.duration_since(at) <= self.bindings.double_tap_window => {
+                self.last_tap = None;
+                Some(key)
+            }
+            _ => {
+                self.last_tap = Some((key, now));
+                None
+            }
+        }
+    }
+
+    fn handle(&mut self, event: EventType) {
+        // Bindable mouse buttons behave exactly like keys in combos
+        let event = match event {
+            EventType::ButtonPress(button) => mouse_button_key(button).map_or(event, EventType::KeyPress),
+            EventType::ButtonRelease(button) => mouse_button_key(button).map_or(event, EventType::KeyRelease),
+            other => other,
+        };
+
+        match event {
+            EventType::KeyPress(key) => {
+                let is_repeat = !self.pressed_keys.insert(key);
+                let tapped = self.register_tap(key, is_repeat);
+
+                // A held combo fires once; nothing but panic fires again until one of its keys is released
+                if self.latched.is_some() && !self.bindings.panic.fired(&self.pressed_keys, tapped) {
+                    return;
+                }
+
+                // Check combos
+                let b = &self.bindings;
+                let pressed = &self.pressed_keys;
+                let fired = if b.panic.fired(pressed, tapped) {
+                    Some(InputEvent::Panic)
+                } else if b.wake.fired(pressed, tapped) {
+                    Some(InputEvent::Wake)
+                } else if b.model.fired(pressed, tapped) {
+                    Some(InputEvent::Model)
+                } else if b.hide.fired(pressed, tapped) {
+                    Some(InputEvent::HideToggle)
+                } else if b.browser_pass.fired(pressed, tapped) {
+                    Some(InputEvent::BrowserPass)
+                } else if b.browser_exec.fired(pressed, tapped) {
+                    Some(InputEvent::BrowserExec)
+                } else if b.browser_exec_single.fired(pressed, tapped) {
+                    Some(InputEvent::BrowserExecSingle)
+                } else if b.browser_abort.fired(pressed, tapped) {
+                    Some(InputEvent::BrowserAbort)
+                } else if b.browser_incognito.fired(pressed, tapped) {
+                    Some(InputEvent::BrowserIncognito)
+                } else if b.browser_pause.fired(pressed, tapped) {
+                    Some(InputEvent::BrowserPause)
+                } else if b.clipboard_cycle.fired(pressed, tapped) {
+                    Some(InputEvent::ClipboardCycle)
+                } else if b.rag_capture.fired(pressed, tapped) {
+                    Some(InputEvent::RagCapture)
+                } else if b.log_viewer.fired(pressed, tapped) {
+                    Some(InputEvent::LogViewerToggle)
+                } else if b.log_dump.fired(pressed, tapped) {
+                    Some(InputEvent::LogDump)
+                } else if !is_repeat && is_plain_paste(pressed) {
+                    Some(InputEvent::Pasted)
+                } else if let (true, false, 1, Some(digit)) = (b.skip_digits.load(Ordering::SeqCst), is_repeat, pressed.len(), digit_value(key)) {
+                    Some(InputEvent::SkipQuestion(digit))
+                } else {
+                    None
+                };
+
+                let Some(fired) = fired else { return };
+                match fired {
+                    InputEvent::Wake => {
+                        self.is_selecting = true; // Enter Selection Mode
+                        self.p1 = None;
+                        println!("[*] Input: Entering OCR Selection Mode");
                     }
-                    EventType::ButtonPress(Button::Left) => {
-                        if is_selecting {
-                            if let Some(start) = p1 {
-                                // Second Click -> P2
-                                println!("[*] Input: Point 2 Captured at {:?}", current_pos);
-                                let x = start.0.min(current_pos.0) as i32;
-                                let y = start.1.min(current_pos.1) as i32;
-                                let w = (start.0 - current_pos.0).abs() as i32;
-                                let h = (start.1 - current_pos.1).abs() as i32;
-
-                                if w > 0 && h > 0 {
-                                    let _ = sender.send(InputEvent::OCRRect(x, y, w, h));
-                                }
-
-                                // Reset
-                                is_selecting = false;
-                                p1 = None;
-                            } else {
-                                // First Click -> P1
-                                println!("[*] Input: Point 1 Captured at {:?}", current_pos);
-                                p1 = Some(current_pos);
-                                let _ = sender.send(InputEvent::OCRClick1);
-                            }
-                        }
+                    InputEvent::LogViewerToggle | InputEvent::LogDump | InputEvent::BrowserPause | InputEvent::ClipboardCycle
+                    | InputEvent::RagCapture | InputEvent::Pasted | InputEvent::SkipQuestion(_) => {}
+                    _ => {
+                        self.is_selecting = false;
+                        self.p1 = None; // Reset
                     }
-                    _ => {}
                 }
-            };
+                self.latched = Some(self.pressed_keys.clone());
+                let _ = self.sender.send(fired);
+            }
+            EventType::KeyRelease(key) => {
+                self.pressed_keys.remove(&key);
+                if self.latched.as_ref().is_some_and(|held| held.contains(&key)) {
+                    self.latched = None;
+                }
+            }
+            EventType::MouseMove { x, y } => {
+                self.current_pos = (x, y);
 
-            if let Err(error) = listen(callback) {
-                eprintln!("Error: {:?}", error);
+                if let (true, Some(start)) = (self.is_selecting, self.p1) {
+                    if self.last_drag_update.elapsed() >= DRAG_UPDATE_INTERVAL {
+                        self.last_drag_update = Instant::now();
+                        let (rx, ry, rw, rh) = selection_rect(start, self.current_pos);
+                        let _ = self.sender.send(InputEvent::OCRDrag(rx, ry, rw, rh));
+                    }
+                }
             }
-        });
+            EventType::ButtonPress(Button::Left) => {
+                if self.is_selecting {
+                    if let Some(start) = self.p1 {
+                        // Second Click -> P2
+                        println!("[*] Input: Point 2 Captured at {:?}", self.current_pos);
+                        let (x, y, w, h) = selection_rect(start, self.current_pos);
+
+                        if w > 0 && h > 0 {
+                            let _ = self.sender.send(InputEvent::OCRRect(x, y, w, h));
+                        }
+
+                        // Reset
+                        self.is_selecting = false;
+                        self.p1 = None;
+                    } else {
+                        // First Click -> P1
+                        println!("[*] Input: Point 1 Captured at {:?}", self.current_pos);
+                        self.p1 = Some(self.current_pos);
+                        let _ = self.sender.send(InputEvent::OCRClick1);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Install a throwaway global hook; rdev only returns from `listen` on failure,
+/// so still listening after `timeout` means the hook is registered (`--self-test`).
+pub fn probe_hook(timeout: Duration) -> anyhow::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let result = listen(|_| {});
+        let _ = tx.send(result);
+    });
+
+    match rx.recv_timeout(timeout) {
+        Ok(Err(e)) => anyhow::bail!("Hook registration failed: {:?}", e),
+        Ok(Ok(())) => anyhow::bail!("Hook exited unexpectedly"),
+        Err(RecvTimeoutError::Timeout) => Ok(()),
+        Err(RecvTimeoutError::Disconnected) => anyhow::bail!("Hook thread panicked"),
+    }
+}
+
+fn selection_rect(start: (f64, f64), end: (f64, f64)) -> (i32, i32, i32, i32) {
+    let x = start.0.min(end.0) as i32;
+    let y = start.1.min(end.1) as i32;
+    let w = (start.0 - end.0).abs() as i32;
+    let h = (start.1 - end.1).abs() as i32;
+    (x, y, w, h)
+}
+
+fn is_plain_paste(pressed: &HashSet<Key>) -> bool {
+    pressed.len() == 2
+        && pressed.contains(&Key::KeyV)
+        && pressed.iter().any(|&k| key_matches(Key::ControlLeft, k))
+}
+
+fn digit_value(key: Key) -> Option<usize> {
+    match key {
+        Key::Num1 => Some(1),
+        Key::Num2 => Some(2),
+        Key::Num3 => Some(3),
+        Key::Num4 => Some(4),
+        Key::Num5 => Some(5),
+        Key::Num6 => Some(6),
+        Key::Num7 => Some(7),
+        Key::Num8 => Some(8),
+        Key::Num9 => Some(9),
+        _ => None,
     }
 }
 
@@ -136,5 +441,72 @@ fn check_combo(pressed: &HashSet<Key>, target: &[Key]) -> bool {
     if target.is_empty() {
         return false;
     }
-    target.iter().all(|k| pressed.contains(k))
+    target.iter().all(|&t| pressed.iter().any(|&k| key_matches(t, k)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings() -> HotkeyBindings {
+        let mut config = Config::default();
+        config.general.model_key = "Ctrl+Shift+V".to_string();
+        HotkeyBindings::from_config(&config)
+    }
+
+    fn model_events(rx: &mpsc::Receiver<InputEvent>) -> usize {
+        rx.try_iter().filter(|e| matches!(e, InputEvent::Model)).count()
+    }
+
+    #[test]
+    fn test_every_binding_has_an_event() {
+        let events = [
+            InputEvent::Wake, InputEvent::Model, InputEvent::Panic, InputEvent::HideToggle,
+            InputEvent::BrowserPass, InputEvent::BrowserExec, InputEvent::BrowserExecSingle,
+            InputEvent::BrowserAbort, InputEvent::BrowserIncognito, InputEvent::BrowserPause,
+            InputEvent::ClipboardCycle, InputEvent::RagCapture, InputEvent::LogViewerToggle,
+            InputEvent::LogDump,
+        ];
+        let mut names: Vec<&str> = events.iter().filter_map(InputEvent::binding_name).collect();
+        let mut bound: Vec<&str> = HotkeyBindings::named(&Config::default()).into_iter().map(|(name, _)| name).collect();
+        names.sort();
+        bound.sort();
+        assert_eq!(names, bound);
+        assert_eq!(InputEvent::Pasted.binding_name(), None);
+    }
+
+    #[test]
+    fn test_held_combo_fires_once() {
+        let (tx, rx) = mpsc::channel();
+        let mut state = InputState::new(bindings(), tx);
+
+        state.handle(EventType::KeyPress(Key::ControlLeft));
+        state.handle(EventType::KeyPress(Key::ShiftLeft));
+        // Auto-repeat while held
+        for _ in 0..5 {
+            state.handle(EventType::KeyPress(Key::KeyV));
+        }
+        assert_eq!(model_events(&rx), 1);
+
+        // Releasing one key of the combo re-arms it
+        state.handle(EventType::KeyRelease(Key::KeyV));
+        state.handle(EventType::KeyPress(Key::KeyV));
+        assert_eq!(model_events(&rx), 1);
+    }
+
+    #[test]
+    fn test_digits_only_while_paused() {
+        let (tx, rx) = mpsc::channel();
+        let bindings = bindings();
+        let paused = bindings.skip_digits.clone();
+        let mut state = InputState::new(bindings, tx);
+
+        state.handle(EventType::KeyPress(Key::Num3));
+        state.handle(EventType::KeyRelease(Key::Num3));
+        assert_eq!(rx.try_iter().count(), 0);
+
+        paused.store(true, Ordering::SeqCst);
+        state.handle(EventType::KeyPress(Key::Num3));
+        assert!(matches!(rx.try_iter().collect::<Vec<_>>()[..], [InputEvent::SkipQuestion(3)]));
+    }
 }
@@ -0,0 +1,204 @@
+//! Native Input Backend
+//! Low-level keyboard/mouse hooks via SetWindowsHookEx, translated into rdev `EventType`s
+//! so the same state machine in input.rs drives both backends.
+
+use rdev::{Button, EventType, Key};
+use std::cell::RefCell;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, DispatchMessageW, GetMessageW, SetWindowsHookExW, TranslateMessage,
+    UnhookWindowsHookEx, HHOOK, KBDLLHOOKSTRUCT, MSG, MSLLHOOKSTRUCT, WH_KEYBOARD_LL,
+    WH_MOUSE_LL, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN,
+    WM_MBUTTONUP, WM_MOUSEMOVE, WM_SYSKEYDOWN, WM_SYSKEYUP, WM_XBUTTONDOWN, WM_XBUTTONUP,
+};
+
+type Handler = Box<dyn FnMut(EventType)>;
+
+// Hook procedures run on the thread that installed them, so a thread-local is enough
+thread_local! {
+    static HANDLER: RefCell<Option<Handler>> = RefCell::new(None);
+}
+
+/// Install the hooks and pump messages on the current thread; only returns on failure.
+pub fn listen(handler: impl FnMut(EventType) + 'static) -> anyhow::Result<()> {
+    HANDLER.with(|h| *h.borrow_mut() = Some(Box::new(handler)));
+
+    unsafe {
+        let module = HINSTANCE(GetModuleHandleW(PCWSTR::null())?.0);
+        let keyboard = SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), module, 0)?;
+        let mouse = match SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), module, 0) {
+            Ok(hook) => hook,
+            Err(e) => {
+                let _ = UnhookWindowsHookEx(keyboard);
+                return Err(e.into());
+            }
+        };
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+
+        let _ = UnhookWindowsHookEx(keyboard);
+        let _ = UnhookWindowsHookEx(mouse);
+    }
+
+    anyhow::bail!("Input message loop exited")
+}
+
+fn dispatch(event: EventType) {
+    HANDLER.with(|h| {
+        // try_borrow_mut: a re-entrant hook call while the handler runs is dropped, not a panic
+        if let Ok(mut handler) = h.try_borrow_mut() {
+            if let Some(handler) = handler.as_mut() {
+                handler(event);
+            }
+        }
+    });
+}
+
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        let key = key_from_vk(info.vkCode);
+        match wparam.0 as u32 {
+            WM_KEYDOWN | WM_SYSKEYDOWN => dispatch(EventType::KeyPress(key)),
+            WM_KEYUP | WM_SYSKEYUP => dispatch(EventType::KeyRelease(key)),
+            _ => {}
+        }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code >= 0 {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        match wparam.0 as u32 {
+            WM_MOUSEMOVE => dispatch(EventType::MouseMove {
+                x: info.pt.x as f64,
+                y: info.pt.y as f64,
+            }),
+            WM_LBUTTONDOWN => dispatch(EventType::ButtonPress(Button::Left)),
+            WM_LBUTTONUP => dispatch(EventType::ButtonRelease(Button::Left)),
+            WM_MBUTTONDOWN => dispatch(EventType::ButtonPress(Button::Middle)),
+            WM_MBUTTONUP => dispatch(EventType::ButtonRelease(Button::Middle)),
+            // HIWORD(mouseData) is 1 for XBUTTON1, 2 for XBUTTON2 (same codes rdev reports)
+            WM_XBUTTONDOWN => dispatch(EventType::ButtonPress(Button::Unknown((info.mouseData >> 16) as u8))),
+            WM_XBUTTONUP => dispatch(EventType::ButtonRelease(Button::Unknown((info.mouseData >> 16) as u8))),
+            _ => {}
+        }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+/// Virtual-key code -> rdev Key, covering everything `parse_keys` can produce
+fn key_from_vk(vk: u32) -> Key {
+    match vk {
+        0x11 | 0xA2 => Key::ControlLeft,
+        0xA3 => Key::ControlRight,
+        0x10 | 0xA0 => Key::ShiftLeft,
+        0xA1 => Key::ShiftRight,
+        0x12 | 0xA4 => Key::Alt,
+        0xA5 => Key::AltGr,
+        0x5B => Key::MetaLeft,
+        0x5C => Key::MetaRight,
+        0x20 => Key::Space,
+        0x0D => Key::Return,
+        0x1B => Key::Escape,
+        0x09 => Key::Tab,
+        0x08 => Key::Backspace,
+        0x14 => Key::CapsLock,
+        0x2C => Key::PrintScreen,
+        0x91 => Key::ScrollLock,
+        0x13 => Key::Pause,
+        0x90 => Key::NumLock,
+        0x2D => Key::Insert,
+        0x2E => Key::Delete,
+        0x24 => Key::Home,
+        0x23 => Key::End,
+        0x21 => Key::PageUp,
+        0x22 => Key::PageDown,
+        0x26 => Key::UpArrow,
+        0x28 => Key::DownArrow,
+        0x25 => Key::LeftArrow,
+        0x27 => Key::RightArrow,
+        0xBD => Key::Minus,
+        0xBB => Key::Equal,
+        0xDB => Key::LeftBracket,
+        0xDD => Key::RightBracket,
+        0xBA => Key::SemiColon,
+        0xDE => Key::Quote,
+        0xC0 => Key::BackQuote,
+        0xDC => Key::BackSlash,
+        0xBC => Key::Comma,
+        0xBE => Key::Dot,
+        0xBF => Key::Slash,
+        0x60 => Key::Kp0,
+        0x61 => Key::Kp1,
+        0x62 => Key::Kp2,
+        0x63 => Key::Kp3,
+        0x64 => Key::Kp4,
+        0x65 => Key::Kp5,
+        0x66 => Key::Kp6,
+        0x67 => Key::Kp7,
+        0x68 => Key::Kp8,
+        0x69 => Key::Kp9,
+        0x6A => Key::KpMultiply,
+        0x6B => Key::KpPlus,
+        0x6D => Key::KpMinus,
+        0x6E => Key::KpDelete,
+        0x6F => Key::KpDivide,
+        0x70 => Key::F1,
+        0x71 => Key::F2,
+        0x72 => Key::F3,
+        0x73 => Key::F4,
+        0x74 => Key::F5,
+        0x75 => Key::F6,
+        0x76 => Key::F7,
+        0x77 => Key::F8,
+        0x78 => Key::F9,
+        0x79 => Key::F10,
+        0x7A => Key::F11,
+        0x7B => Key::F12,
+        0x30 => Key::Num0,
+        0x31 => Key::Num1,
+        0x32 => Key::Num2,
+        0x33 => Key::Num3,
+        0x34 => Key::Num4,
+        0x35 => Key::Num5,
+        0x36 => Key::Num6,
+        0x37 => Key::Num7,
+        0x38 => Key::Num8,
+        0x39 => Key::Num9,
+        0x41 => Key::KeyA,
+        0x42 => Key::KeyB,
+        0x43 => Key::KeyC,
+        0x44 => Key::KeyD,
+        0x45 => Key::KeyE,
+        0x46 => Key::KeyF,
+        0x47 => Key::KeyG,
+        0x48 => Key::KeyH,
+        0x49 => Key::KeyI,
+        0x4A => Key::KeyJ,
+        0x4B => Key::KeyK,
+        0x4C => Key::KeyL,
+        0x4D => Key::KeyM,
+        0x4E => Key::KeyN,
+        0x4F => Key::KeyO,
+        0x50 => Key::KeyP,
+        0x51 => Key::KeyQ,
+        0x52 => Key::KeyR,
+        0x53 => Key::KeyS,
+        0x54 => Key::KeyT,
+        0x55 => Key::KeyU,
+        0x56 => Key::KeyV,
+        0x57 => Key::KeyW,
+        0x58 => Key::KeyX,
+        0x59 => Key::KeyY,
+        0x5A => Key::KeyZ,
+        other => Key::Unknown(other),
+    }
+}
@@ -0,0 +1,229 @@
+//! Academic Search
+//! Titles and abstracts of papers instead of web snippets, for questions where the web is
+//! all blog spam. Used for `[search] engine = "academic"` or a leading `!papers` in the
+//! question. Semantic Scholar is tried first, then arXiv, then Crossref.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use reqwest::{header, Client};
+use std::sync::OnceLock;
+use crate::config::SearchConfig;
+use super::search::SearchHit;
+
+/// Abstracts are cut to roughly this many characters
+const MAX_ABSTRACT_CHARS: usize = 700;
+
+const USER_AGENT: &str = concat!("ShadowPrompt/", env!("CARGO_PKG_VERSION"));
+
+pub async fn search(query: &str, config: &SearchConfig) -> Result<Vec<SearchHit>> {
+    log::info!("[Search] Academic query: '{}', Max Results: {}", crate::logger::content(query), config.max_results);
+    let client = Client::builder().user_agent(USER_AGENT).build()?;
+
+    let providers = ["Semantic Scholar", "arXiv", "Crossref"];
+    let mut last_error = None;
+    for name in providers {
+        let result = match name {
+            "Semantic Scholar" => semantic_scholar(&client, query, config).await,
+            "arXiv" => arxiv(&client, query, config.max_results).await,
+            _ => crossref(&client, query, config.max_results).await,
+        };
+        match result {
+            Ok(hits) if !hits.is_empty() => {
+                log::info!("[Search] {} returned {} papers", name, hits.len());
+                return Ok(hits);
+            }
+            Ok(_) => log::warn!("[Search] {} found no papers, trying the next provider...", name),
+            Err(e) => {
+                log::error!("[Search] {} failed: {}. Trying the next provider...", name, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) => Err(e.context("All academic search providers failed")),
+        None => Ok(Vec::new()),
+    }
+}
+
+async fn semantic_scholar(client: &Client, query: &str, config: &SearchConfig) -> Result<Vec<SearchHit>> {
+    let limit = config.max_results.to_string();
+    let mut request = client.get("https://api.semanticscholar.org/graph/v1/paper/search")
+        .query(&[("query", query), ("limit", limit.as_str()), ("fields", "title,abstract,year,url")]);
+    // Works without a key, but the shared unauthenticated pool is often rate-limited
+    if let Some(key) = config.semantic_scholar_api_key.as_ref().filter(|k| !k.is_empty()) {
+        request = request.header("x-api-key", key);
+    }
+
+    let res = request.send().await.context("Semantic Scholar network error")?;
+    if res.status().as_u16() == 429 {
+        anyhow::bail!("Semantic Scholar rate limit reached (set semantic_scholar_api_key in config.toml)");
+    }
+    let json: serde_json::Value = res.error_for_status()?.json().await?;
+    Ok(parse_semantic_scholar(&json))
+}
+
+fn parse_semantic_scholar(json: &serde_json::Value) -> Vec<SearchHit> {
+    let Some(papers) = json["data"].as_array() else {
+        return Vec::new();
+    };
+    papers
+        .iter()
+        .filter_map(|paper| {
+            paper_hit(
+                paper["title"].as_str()?,
+                paper["year"].as_u64().map(|y| y.to_string()).as_deref(),
+                paper["url"].as_str().unwrap_or(""),
+                paper["abstract"].as_str().unwrap_or(""),
+            )
+        })
+        .collect()
+}
+
+async fn arxiv(client: &Client, query: &str, max_results: usize) -> Result<Vec<SearchHit>> {
+    let search_query = format!("all:{}", query);
+    let max = max_results.to_string();
+    let res = client.get("https://export.arxiv.org/api/query")
+        .query(&[("search_query", search_query.as_str()), ("max_results", max.as_str())])
+        .send()
+        .await
+        .context("arXiv network error")?;
+    let body = res.error_for_status()?.text().await?;
+    Ok(parse_arxiv(&body))
+}
+
+/// The arXiv API answers with an Atom feed, one <entry> per paper
+fn parse_arxiv(atom: &str) -> Vec<SearchHit> {
+    static ENTRY: OnceLock<Regex> = OnceLock::new();
+    let entry = ENTRY.get_or_init(|| Regex::new(r"(?s)<entry>(.*?)</entry>").unwrap());
+
+    entry
+        .captures_iter(atom)
+        .filter_map(|cap| {
+            let body = &cap[1];
+            paper_hit(
+                &element(body, "title")?,
+                element(body, "published").as_deref().and_then(|date| date.get(..4)),
+                &element(body, "id").unwrap_or_default(),
+                &element(body, "summary").unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+async fn crossref(client: &Client, query: &str, max_results: usize) -> Result<Vec<SearchHit>> {
+    let rows = max_results.to_string();
+    let res = client.get("https://api.crossref.org/works")
+        .header(header::ACCEPT, "application/json")
+        .query(&[("query", query), ("rows", rows.as_str()), ("select", "title,abstract,URL,issued")])
+        .send()
+        .await
+        .context("Crossref network error")?;
+    let json: serde_json::Value = res.error_for_status()?.json().await?;
+    Ok(parse_crossref(&json))
+}
+
+fn parse_crossref(json: &serde_json::Value) -> Vec<SearchHit> {
+    let Some(items) = json["message"]["items"].as_array() else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            // Abstracts are JATS XML (<jats:p>...) when the publisher deposited one at all
+            let abstract_text = strip_tags(item["abstract"].as_str().unwrap_or(""));
+            paper_hit(
+                item["title"][0].as_str()?,
+                item["issued"]["date-parts"][0][0].as_u64().map(|y| y.to_string()).as_deref(),
+                item["URL"].as_str().unwrap_or(""),
+                &abstract_text,
+            )
+        })
+        .collect()
+}
+
+/// "Title (year)" and a whitespace-collapsed, trimmed abstract
+fn paper_hit(title: &str, year: Option<&str>, url: &str, abstract_text: &str) -> Option<SearchHit> {
+    let title = collapse_whitespace(title);
+    if title.is_empty() {
+        return None;
+    }
+    let abstract_text = collapse_whitespace(abstract_text);
+    let mut snippet: String = abstract_text.chars().take(MAX_ABSTRACT_CHARS).collect();
+    if snippet.len() < abstract_text.len() {
+        snippet.push_str("...");
+    }
+    if snippet.is_empty() {
+        snippet = "(no abstract available)".to_string();
+    }
+
+    Some(SearchHit {
+        title: match year {
+            Some(year) => format!("{} ({})", title, year),
+            None => title,
+        },
+        url: url.to_string(),
+        snippet,
+        page: None,
+    })
+}
+
+fn element(xml: &str, tag: &str) -> Option<String> {
+    let re = Regex::new(&format!(r"(?s)<{0}[^>]*>(.*?)</{0}>", tag)).ok()?;
+    re.captures(xml).map(|cap| decode_xml(&cap[1]))
+}
+
+fn strip_tags(text: &str) -> String {
+    static TAGS: OnceLock<Regex> = OnceLock::new();
+    let tags = TAGS.get_or_init(|| Regex::new(r"<[^>]*>").unwrap());
+    decode_xml(&tags.replace_all(text, " "))
+}
+
+fn decode_xml(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_arxiv() {
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>ArXiv Query</title>
+            <entry><id>http://arxiv.org/abs/1706.03762v7</id><published>2017-06-12T17:57:34Z</published>
+            <title>Attention Is All
+              You Need</title><summary>  The dominant sequence transduction models &amp; more.
+            </summary></entry></feed>"#;
+        assert_eq!(parse_arxiv(atom), vec![SearchHit {
+            title: "Attention Is All You Need (2017)".to_string(),
+            url: "http://arxiv.org/abs/1706.03762v7".to_string(),
+            snippet: "The dominant sequence transduction models & more.".to_string(),
+            page: None,
+        }]);
+    }
+
+    #[test]
+    fn test_parse_semantic_scholar_and_crossref() {
+        let s2 = serde_json::json!({ "total": 2, "data": [
+            { "paperId": "a", "title": "Deep Residual Learning", "year": 2016, "url": "https://www.semanticscholar.org/paper/a", "abstract": null },
+            { "paperId": "b", "title": null }
+        ]});
+        let hits = parse_semantic_scholar(&s2);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title, "Deep Residual Learning (2016)");
+        assert_eq!(hits[0].snippet, "(no abstract available)");
+
+        let crossref = serde_json::json!({ "message": { "items": [{
+            "title": ["CRISPR-Cas9"], "URL": "https://doi.org/10.1/x",
+            "issued": { "date-parts": [[2014, 11]] },
+            "abstract": "<jats:title>Abstract</jats:title><jats:p>Genome editing with Cas9.</jats:p>"
+        }]}});
+        let hits = parse_crossref(&crossref);
+        assert_eq!(hits[0].title, "CRISPR-Cas9 (2014)");
+        assert_eq!(hits[0].snippet, "Abstract Genome editing with Cas9.");
+    }
+}
@@ -0,0 +1,235 @@
+//! Index Encryption
+//! With `[rag] encrypt_index`, index.bin (which holds the full text of every chunk) is sealed
+//! with AES-256-GCM. The key is derived from a passphrase with Argon2id (salt stored in the
+//! file), or is a random key kept DPAPI-protected in data/index.key (`index_key_source =
+//! "dpapi"`, bound to this Windows account). index.vec stays plain so it can be memory-mapped.
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result};
+use argon2::Argon2;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use crate::config::{get_exe_dir, Config};
+
+const ENCRYPTED_MAGIC: &[u8; 4] = b"SPRE";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = 4 + SALT_LEN + NONCE_LEN;
+
+/// Wrong-passphrase retries before RAG is locked for the session
+const MAX_ATTEMPTS: usize = 3;
+
+/// Lets the passphrase be supplied without a prompt (e.g. scripted `--ingest`)
+pub const PASSPHRASE_ENV: &str = "SHADOWPROMPT_INDEX_PASSPHRASE";
+
+enum Secret {
+    Passphrase(String),
+    /// Random key from data/index.key; the salt is unused
+    Key([u8; 32]),
+}
+
+struct Cipher {
+    secret: Secret,
+    /// Argon2 is deliberately slow, so the key for the last salt is kept
+    derived: Mutex<Option<([u8; SALT_LEN], [u8; 32])>>,
+}
+
+impl Cipher {
+    fn new(secret: Secret) -> Self {
+        Self { secret, derived: Mutex::new(None) }
+    }
+
+    fn key_for(&self, salt: &[u8; SALT_LEN]) -> Result<[u8; 32]> {
+        let passphrase = match &self.secret {
+            Secret::Key(key) => return Ok(*key),
+            Secret::Passphrase(p) => p,
+        };
+        let mut derived = self.derived.lock().unwrap();
+        if let Some((cached_salt, key)) = derived.as_ref() {
+            if cached_salt == salt {
+                return Ok(*key);
+            }
+        }
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+        *derived = Some((*salt, key));
+        Ok(key)
+    }
+
+    /// Salt of the last derived key, so re-saving doesn't pay for a new derivation
+    fn salt(&self) -> [u8; SALT_LEN] {
+        if let Some((salt, _)) = self.derived.lock().unwrap().as_ref() {
+            return *salt;
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        salt
+    }
+
+    fn seal(&self, plain: &[u8]) -> Result<Vec<u8>> {
+        let salt = self.salt();
+        let key = self.key_for(&salt)?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let sealed = cipher.encrypt(&nonce, plain).map_err(|_| anyhow::anyhow!("Index encryption failed"))?;
+
+        let mut out = Vec::with_capacity(HEADER_LEN + sealed.len());
+        out.extend_from_slice(ENCRYPTED_MAGIC);
+        out.extend_from_slice(&salt);
+        out.extend_from_slice(&nonce);
+        out.extend(sealed);
+        Ok(out)
+    }
+
+    fn open(&self, file: &[u8]) -> Result<Vec<u8>> {
+        if !is_encrypted(file) || file.len() < HEADER_LEN {
+            anyhow::bail!("not an encrypted index");
+        }
+        let salt: [u8; SALT_LEN] = file[4..4 + SALT_LEN].try_into()?;
+        let nonce = Nonce::from_slice(&file[4 + SALT_LEN..HEADER_LEN]);
+        let key = self.key_for(&salt)?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        cipher
+            .decrypt(nonce, &file[HEADER_LEN..])
+            .map_err(|_| anyhow::anyhow!("Wrong passphrase/key or damaged index"))
+    }
+}
+
+enum State {
+    Off,
+    /// Encryption is configured but no key was unlocked: refuse to read or overwrite the index
+    Locked,
+    Ready(Cipher),
+}
+
+static STATE: OnceLock<State> = OnceLock::new();
+
+pub fn is_encrypted(file: &[u8]) -> bool {
+    file.starts_with(ENCRYPTED_MAGIC)
+}
+
+pub fn is_locked() -> bool {
+    matches!(STATE.get(), Some(State::Locked))
+}
+
+/// Unlock the index key for this session. `prompt` asks for the passphrase (dialog or
+/// console) and is called again after a wrong one. A failure locks RAG instead of letting
+/// ingest overwrite the encrypted index with a new key.
+pub fn init(config: &Config, index_file_path: &Path, prompt: impl Fn(&str) -> Option<String>) -> Result<()> {
+    if !config.rag.enabled || !config.rag.encrypt_index {
+        let _ = STATE.set(State::Off);
+        return Ok(());
+    }
+
+    match unlock(config, index_file_path, prompt) {
+        Ok(cipher) => {
+            let _ = STATE.set(State::Ready(cipher));
+            Ok(())
+        }
+        Err(e) => {
+            let _ = STATE.set(State::Locked);
+            Err(e)
+        }
+    }
+}
+
+fn unlock(config: &Config, index_file_path: &Path, prompt: impl Fn(&str) -> Option<String>) -> Result<Cipher> {
+    if config.rag.index_key_source == "dpapi" {
+        return Ok(Cipher::new(Secret::Key(load_or_create_dpapi_key()?)));
+    }
+
+    // An existing index tells us whether the passphrase is right; a new one just uses it
+    let existing = std::fs::read(index_file_path).ok().filter(|bytes| is_encrypted(bytes));
+
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        let cipher = Cipher::new(Secret::Passphrase(passphrase));
+        if let Some(bytes) = &existing {
+            cipher.open(bytes).with_context(|| format!("{} does not unlock the index", PASSPHRASE_ENV))?;
+        }
+        return Ok(cipher);
+    }
+
+    let mut message = "Passphrase for the encrypted knowledge index:".to_string();
+    for _ in 0..MAX_ATTEMPTS {
+        let passphrase = prompt(&message).filter(|p| !p.is_empty()).context("No passphrase entered; RAG is locked")?;
+        let cipher = Cipher::new(Secret::Passphrase(passphrase));
+        match &existing {
+            Some(bytes) if cipher.open(bytes).is_err() => {
+                message = "Wrong passphrase, try again:".to_string();
+            }
+            _ => return Ok(cipher),
+        }
+    }
+    anyhow::bail!("Wrong passphrase; RAG is locked for this session")
+}
+
+fn load_or_create_dpapi_key() -> Result<[u8; 32]> {
+    let path = get_exe_dir().join("data").join("index.key");
+    if let Ok(protected) = std::fs::read(&path) {
+        let key = crate::dpapi::unprotect(&protected)
+            .context("data/index.key belongs to another Windows account; delete it and reindex")?;
+        return key.as_slice().try_into().context("data/index.key is damaged");
+    }
+
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    std::fs::write(&path, crate::dpapi::protect(&key)?).context("Failed to write data/index.key")?;
+    Ok(key)
+}
+
+/// Encrypt a serialized index when encryption is on
+pub fn seal(plain: Vec<u8>) -> Result<Vec<u8>> {
+    match STATE.get() {
+        None | Some(State::Off) => Ok(plain),
+        Some(State::Locked) => anyhow::bail!("Index is locked (no passphrase); not saving"),
+        Some(State::Ready(cipher)) => cipher.seal(&plain),
+    }
+}
+
+/// Decrypt an index file if it is encrypted; plain files pass through so turning
+/// encryption on migrates the index on the next save
+pub fn open(file: Vec<u8>) -> Result<Vec<u8>> {
+    if !is_encrypted(&file) {
+        return Ok(file);
+    }
+    match STATE.get() {
+        Some(State::Ready(cipher)) => cipher.open(&file),
+        _ => anyhow::bail!("Index is encrypted; set encrypt_index = true under [rag] and unlock it"),
+    }
+}
+
+/// Console prompt for headless commands (the passphrase is echoed)
+pub fn console_prompt(message: &str) -> Option<String> {
+    use std::io::Write;
+    print!("{} ", message);
+    let _ = std::io::stdout().flush();
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line).ok()?;
+    Some(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_round_trip() -> Result<()> {
+        let cipher = Cipher::new(Secret::Passphrase("correct horse".to_string()));
+        let sealed = cipher.seal(b"SPRI notes")?;
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.windows(5).any(|w| w == b"notes"), "plaintext must not leak");
+        assert_eq!(cipher.open(&sealed)?, b"SPRI notes");
+
+        let wrong = Cipher::new(Secret::Passphrase("battery staple".to_string()));
+        assert!(wrong.open(&sealed).is_err());
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(cipher.open(&tampered).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use anyhow::{Context, Result};
+use fastembed::TextEmbedding;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::config::Config;
+
+/// Id stored in indexes written before the backend was configurable
+pub const LEGACY_FASTEMBED_ID: &str = "fastembed:bge-small-en-v1.5";
+
+/// Chunks per embedding call; progress is reported after each batch
+const BATCH_SIZE: usize = 32;
+
+/// Batches in flight at once (blocking threads for FastEmbed, HTTP requests for Ollama)
+const PARALLEL_BATCHES: usize = 4;
+
+type BatchJob = Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>>> + Send>>;
+
+/// Embedding backend for RAG, selected by `[rag] embedding_backend`
+pub enum Embedder {
+    /// Shared so batches can run on the blocking thread pool
+    FastEmbed(Arc<TextEmbedding>),
+    Ollama {
+        client: Client,
+        url: String,
+        model: String,
+    },
+}
+
+impl Embedder {
+    pub fn ollama(config: &Config) -> Result<Self> {
+        let base_url = config.models.ollama.as_ref()
+            .map(|o| o.base_url.clone())
+            .unwrap_or_else(|| "http://localhost:11434".to_string());
+
+        let client = Client::builder()
+            .connect_timeout(Duration::from_secs(config.http.connect_timeout_secs))
+            .timeout(Duration::from_secs(config.http.read_timeout_secs))
+            .build()?;
+
+        Ok(Self::Ollama {
+            client,
+            url: format!("{}/api/embeddings", base_url.trim_end_matches('/')),
+            model: config.rag.ollama_embedding_model.clone(),
+        })
+    }
+
+    /// Identifies which model produced an index so vectors from different models are never compared
+    pub fn id(&self) -> String {
+        match self {
+            Self::FastEmbed(_) => LEGACY_FASTEMBED_ID.to_string(),
+            Self::Ollama { model, .. } => format!("ollama:{}", model),
+        }
+    }
+
+    pub async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        self.batch_job(texts).await
+    }
+
+    /// Embed in batches of `BATCH_SIZE`, up to `PARALLEL_BATCHES` at a time, calling
+    /// `progress(done, total)` as batches finish. Output order matches `texts`.
+    pub async fn embed_batched(&self, texts: Vec<String>, progress: impl Fn(usize, usize) + Send + Sync) -> Result<Vec<Vec<f32>>> {
+        let total = texts.len();
+        let batches: Vec<Vec<String>> = texts.chunks(BATCH_SIZE).map(|batch| batch.to_vec()).collect();
+        let mut results: Vec<Vec<Vec<f32>>> = vec![Vec::new(); batches.len()];
+        let mut pending = batches.into_iter().enumerate();
+        let mut running = tokio::task::JoinSet::new();
+        let mut done = 0;
+
+        loop {
+            while running.len() < PARALLEL_BATCHES {
+                let Some((i, batch)) = pending.next() else { break };
+                let job = self.batch_job(batch);
+                running.spawn(async move { (i, job.await) });
+            }
+            let Some(finished) = running.join_next().await else { break };
+            let (i, embeddings) = finished.context("Embedding task panicked")?;
+            let embeddings = embeddings?;
+            done += embeddings.len();
+            results[i] = embeddings;
+            progress(done, total);
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    fn batch_job(&self, texts: Vec<String>) -> BatchJob {
+        match self {
+            Self::FastEmbed(model) => {
+                // ONNX inference is CPU-bound; keep it off the async workers
+                let model = model.clone();
+                Box::pin(async move {
+                    tokio::task::spawn_blocking(move || model.embed(texts, None))
+                        .await
+                        .context("Embedding task panicked")?
+                })
+            }
+            Self::Ollama { client, url, model } => {
+                let (client, url, model) = (client.clone(), url.clone(), model.clone());
+                Box::pin(async move {
+                    // /api/embeddings takes one prompt per request
+                    let mut embeddings = Vec::with_capacity(texts.len());
+                    for text in texts {
+                        embeddings.push(ollama_embed(&client, &url, &model, &text).await?);
+                    }
+                    Ok(embeddings)
+                })
+            }
+        }
+    }
+}
+
+async fn ollama_embed(client: &Client, url: &str, model: &str, text: &str) -> Result<Vec<f32>> {
+    let res = client.post(url)
+        .json(&json!({ "model": model, "prompt": text }))
+        .send()
+        .await
+        .context("Failed to reach Ollama for embeddings")?;
+
+    if !res.status().is_success() {
+        let status = res.status();
+        let err_text = res.text().await.unwrap_or_default();
+        anyhow::bail!("Ollama Embedding Error ({}): {}", status, err_text);
+    }
+
+    let json: Value = res.json().await?;
+    let embedding: Vec<f32> = json["embedding"].as_array()
+        .context("No embedding field in Ollama response")?
+        .iter()
+        .filter_map(|v| v.as_f64().map(|f| f as f32))
+        .collect();
+
+    if embedding.is_empty() {
+        anyhow::bail!("Ollama returned an empty embedding (is '{}' an embedding model?)", model);
+    }
+    Ok(embedding)
+}
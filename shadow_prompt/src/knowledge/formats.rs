@@ -0,0 +1,284 @@
+//! Document Formats
+//! Turns files in the knowledge folder into plain text for chunking. HTML is reduced to its
+//! readable part, EPUBs are unpacked chapter by chapter in spine order, and Markdown
+//! frontmatter (`title`, `tags`) is split off so tags can be used as retrieval filters.
+//! Spreadsheets become one "Column: value | Column: value" line per row, which
+//! `rag::chunk_text` packs into chunks without splitting rows.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use super::web;
+
+/// File types picked up from the knowledge folder
+pub const EXTENSIONS: &[&str] = &["md", "txt", "html", "htm", "epub", "csv", "xlsx", "xls", "ods"];
+
+/// Files whose extracted text is one self-contained line per table row
+pub fn is_tabular(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    [".csv", ".xlsx", ".xls", ".ods"].iter().any(|ext| lower.ends_with(ext))
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Extracted {
+    pub text: String,
+    pub title: String,
+    pub tags: Vec<String>,
+}
+
+/// Whether `extract` can return a title or tags for this file
+pub fn has_metadata(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".md") || lower.ends_with(".epub")
+}
+
+pub fn extract(path: &Path) -> Result<Extracted> {
+    let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    match ext.as_str() {
+        "epub" => read_epub(path),
+        "csv" => read_csv(path),
+        "xlsx" | "xls" | "ods" => read_workbook(path),
+        "html" | "htm" => Ok(Extracted { text: web::readable_text(&fs::read_to_string(path)?), ..Default::default() }),
+        "md" => {
+            let content = fs::read_to_string(path)?;
+            let (frontmatter, body) = split_frontmatter(&content);
+            Ok(Extracted { text: body.to_string(), ..frontmatter })
+        }
+        _ => Ok(Extracted { text: fs::read_to_string(path)?, ..Default::default() }),
+    }
+}
+
+/// Split a leading `---` YAML block off a Markdown file. Only `title` and `tags` (also
+/// `tag`/`keywords`) are read, as `tags: [a, b]`, `tags: a, b` or a `- a` list.
+pub fn split_frontmatter(content: &str) -> (Extracted, &str) {
+    let unmarked = content.trim_start_matches('\u{feff}');
+    let Some(rest) = unmarked.strip_prefix("---").filter(|r| r.starts_with('\n') || r.starts_with("\r\n")) else {
+        return (Extracted::default(), content);
+    };
+
+    let mut block_end = None;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if offset > 0 && matches!(line.trim_end(), "---" | "...") {
+            block_end = Some((offset, offset + line.len()));
+            break;
+        }
+        offset += line.len();
+    }
+    let Some((end, body_start)) = block_end else {
+        return (Extracted::default(), content);
+    };
+
+    let mut meta = Extracted::default();
+    let mut in_tag_list = false;
+    for line in rest[..end].lines() {
+        let trimmed = line.trim();
+        if in_tag_list {
+            if let Some(item) = trimmed.strip_prefix("- ") {
+                meta.tags.extend(parse_tags(item));
+                continue;
+            }
+            in_tag_list = false;
+        }
+        let Some((key, value)) = trimmed.split_once(':') else { continue };
+        let value = value.trim();
+        match key.trim().to_lowercase().as_str() {
+            "title" => meta.title = unquote(value).to_string(),
+            "tags" | "tag" | "keywords" if value.is_empty() => in_tag_list = true,
+            "tags" | "tag" | "keywords" => meta.tags.extend(parse_tags(value)),
+            _ => {}
+        }
+    }
+    (meta, rest[body_start..].trim_start_matches(['\r', '\n']))
+}
+
+fn parse_tags(value: &str) -> Vec<String> {
+    value
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|tag| unquote(tag.trim()).trim_start_matches('#').trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches(|c| c == '"' || c == '\'')
+}
+
+fn read_csv(path: &Path) -> Result<Extracted> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_path(path)?;
+    let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+    let mut lines = Vec::new();
+    for record in reader.records() {
+        if let Some(line) = row_text(&headers, record?.iter().map(str::to_string)) {
+            lines.push(line);
+        }
+    }
+    Ok(Extracted { text: lines.join("\n"), ..Default::default() })
+}
+
+/// Every sheet, with the first non-empty row of each as its header
+fn read_workbook(path: &Path) -> Result<Extracted> {
+    use calamine::Reader;
+    let mut workbook = calamine::open_workbook_auto(path).context("Not a readable spreadsheet")?;
+    let sheets = workbook.sheet_names();
+    let mut lines = Vec::new();
+    for sheet in &sheets {
+        let range = match workbook.worksheet_range(sheet) {
+            Ok(range) => range,
+            Err(e) => {
+                eprintln!("[RAG] {} / {}: {}", path.display(), sheet, e);
+                continue;
+            }
+        };
+        let mut rows = range.rows().map(|row| row.iter().map(|cell| cell.to_string()).collect::<Vec<_>>());
+        let Some(headers) = rows.find(|row| row.iter().any(|cell| !cell.trim().is_empty())) else { continue };
+        for row in rows {
+            if let Some(line) = row_text(&headers, row.into_iter()) {
+                // Rows from different sheets end up in the same index, so say where they came from
+                lines.push(if sheets.len() > 1 { format!("Sheet: {} | {}", sheet, line) } else { line });
+            }
+        }
+    }
+    Ok(Extracted { text: lines.join("\n"), ..Default::default() })
+}
+
+/// "Formula: F = ma | Unit: N"; empty cells are left out, unnamed columns are numbered
+fn row_text(headers: &[String], cells: impl Iterator<Item = String>) -> Option<String> {
+    let pairs: Vec<String> = cells
+        .enumerate()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(i, value)| {
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            match headers.get(i).map(|h| h.trim()).filter(|h| !h.is_empty()) {
+                Some(header) => format!("{}: {}", header, value),
+                None => format!("Column {}: {}", i + 1, value),
+            }
+        })
+        .collect();
+    (!pairs.is_empty()).then(|| pairs.join(" | "))
+}
+
+/// Chapters of an EPUB in reading order, with the book title from its package file
+fn read_epub(path: &Path) -> Result<Extracted> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(path)?).context("Not a valid EPUB (zip) file")?;
+
+    let container = read_entry(&mut archive, "META-INF/container.xml")?;
+    let opf_path = attribute(&container, "full-path").context("EPUB has no package file")?;
+    let opf = read_entry(&mut archive, &opf_path)?;
+    let base = opf_path.rsplit_once('/').map_or(String::new(), |(dir, _)| format!("{}/", dir));
+
+    let title = Regex::new(r"(?s)<dc:title[^>]*>(.*?)</dc:title>")?
+        .captures(&opf)
+        .map(|c| crate::clipboard::html_to_text(&c[1]).trim().to_string())
+        .unwrap_or_default();
+
+    let items: std::collections::HashMap<String, String> = Regex::new(r"<item\b[^>]*>")?
+        .find_iter(&opf)
+        .filter_map(|tag| Some((attribute(tag.as_str(), "id")?, attribute(tag.as_str(), "href")?)))
+        .collect();
+
+    let mut chapters = Vec::new();
+    for itemref in Regex::new(r"<itemref\b[^>]*>")?.find_iter(&opf) {
+        let Some(href) = attribute(itemref.as_str(), "idref").and_then(|id| items.get(&id)) else { continue };
+        let entry = format!("{}{}", base, href.replace("%20", " "));
+        match read_entry(&mut archive, &entry) {
+            Ok(xhtml) => {
+                let text = web::readable_text(&xhtml);
+                if !text.trim().is_empty() {
+                    chapters.push(text);
+                }
+            }
+            Err(e) => eprintln!("[RAG] {}: {}", path.display(), e),
+        }
+    }
+
+    if chapters.is_empty() {
+        anyhow::bail!("EPUB has no readable chapters");
+    }
+    Ok(Extracted { text: chapters.join("\n\n"), title, tags: Vec::new() })
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<fs::File>, name: &str) -> Result<String> {
+    let mut entry = archive.by_name(name).with_context(|| format!("Missing {} in EPUB", name))?;
+    let mut text = String::new();
+    entry.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Value of `name="..."` (or single-quoted) inside an XML tag
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let pattern = format!(r#"\b{}\s*=\s*["']([^"']*)["']"#, regex::escape(name));
+    Regex::new(&pattern).ok()?.captures(tag).map(|c| c[1].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_split_frontmatter() {
+        let (meta, body) = split_frontmatter("---\ntitle: \"Week 3: Cells\"\ntags: [biology, '#exam']\n---\n\n# Cells\nBody");
+        assert_eq!(meta.title, "Week 3: Cells");
+        assert_eq!(meta.tags, vec!["biology", "exam"]);
+        assert_eq!(body, "# Cells\nBody");
+
+        let (meta, body) = split_frontmatter("---\r\ntags:\r\n  - formulas\r\n  - physics\r\nauthor: me\r\n---\r\nText");
+        assert_eq!(meta.tags, vec!["formulas", "physics"]);
+        assert_eq!(body, "Text");
+
+        let plain = "# Title\n---\nNot frontmatter";
+        assert_eq!(split_frontmatter(plain), (Extracted::default(), plain));
+        let unclosed = "---\ntitle: x\nno end";
+        assert_eq!(split_frontmatter(unclosed).1, unclosed);
+    }
+
+    #[test]
+    fn test_read_csv() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("shadow_prompt_test_{}.csv", uuid::Uuid::new_v4()));
+        fs::write(&path, "Law,Formula,\nNewton's second,\"F = ma\",N\n,,\n\"Ohm's, law\",V = IR\n")?;
+
+        let table = extract(&path)?;
+        assert_eq!(table.text, "Law: Newton's second | Formula: F = ma | Column 3: N\nLaw: Ohm's, law | Formula: V = IR");
+        assert!(is_tabular(&path.display().to_string()));
+
+        let _ = fs::remove_file(path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_epub() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("shadow_prompt_test_{}.epub", uuid::Uuid::new_v4()));
+        {
+            let mut zip = zip::ZipWriter::new(fs::File::create(&path)?);
+            let options = zip::write::SimpleFileOptions::default();
+            let files = [
+                ("META-INF/container.xml", r#"<container><rootfiles><rootfile full-path="OEBPS/content.opf"/></rootfiles></container>"#),
+                ("OEBPS/content.opf", r#"<package><metadata><dc:title>Biology &amp; You</dc:title></metadata>
+                    <manifest><item href="ch2.xhtml" id="ch2"/><item id="ch1" href="ch1.xhtml"/></manifest>
+                    <spine><itemref idref="ch1"/><itemref idref="ch2"/></spine></package>"#),
+                ("OEBPS/ch1.xhtml", "<html><body><p>Cells are small.</p></body></html>"),
+                ("OEBPS/ch2.xhtml", "<html><body><nav>Contents</nav><p>DNA stores genes.</p></body></html>"),
+            ];
+            for (name, content) in files {
+                zip.start_file(name, options)?;
+                zip.write_all(content.as_bytes())?;
+            }
+            zip.finish()?;
+        }
+
+        let book = extract(&path)?;
+        assert_eq!(book.title, "Biology & You");
+        let cells = book.text.find("Cells are small.").expect("chapter 1");
+        let dna = book.text.find("DNA stores genes.").expect("chapter 2");
+        assert!(cells < dna, "spine order");
+        assert!(!book.text.contains("Contents"));
+
+        let _ = fs::remove_file(path);
+        Ok(())
+    }
+}
@@ -1,17 +1,129 @@
 pub mod search;
 pub mod rag;
+pub mod embedder;
+pub mod sanitize;
+pub mod store;
+pub mod crypt;
+pub mod formats;
+pub mod web;
+pub mod wikipedia;
+pub mod academic;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crate::config::Config;
 use crate::capabilities::ModelCapabilities;
 use std::sync::Arc;
 
+#[derive(Debug, Default)]
+pub struct GatheredContext {
+    pub text: String,
+    pub warnings: Vec<String>,
+    /// Citations of the knowledge-base chunks used, e.g. "[notes/week3.md §2]"
+    pub sources: Vec<String>,
+    /// URLs of the search results placed in the context
+    pub links: Vec<String>,
+}
+
+/// Leading "@collection" and "#tag" words narrow RAG retrieval:
+/// "@bio #exam What is ATP?" -> (collection "bio", tags ["exam"]), "What is ATP?".
+/// An "@word"/"#word" that isn't a collection/tag in `names` ends the prefix and stays in
+/// the question ("@Override public void ...", "#include <stdio.h> ...").
+/// Without an @ prefix the `[rag] default_collection` applies (empty = every collection).
+/// "!papers" searches academic papers instead of the web for this question.
+pub fn split_filters<'a>(text: &'a str, names: &rag::FilterNames, config: &Config) -> (rag::QueryFilter, &'a str) {
+    let mut filter = rag::QueryFilter::default();
+    let mut rest = text;
+    loop {
+        let trimmed = rest.trim_start();
+        if let Some(after) = trimmed.strip_prefix("!papers").filter(|a| a.is_empty() || a.starts_with(char::is_whitespace)) {
+            filter.academic = true;
+            rest = after;
+            continue;
+        }
+        let Some(marker) = trimmed.chars().next().filter(|c| *c == '@' || *c == '#') else { break };
+        let word = &trimmed[1..];
+        let name_len = word.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-')).unwrap_or(word.len());
+        // "#1." is a question number, not a tag
+        if name_len == 0 || !word.starts_with(|c: char| c.is_alphabetic()) {
+            break;
+        }
+        let name = word[..name_len].to_string();
+        if marker == '@' {
+            if filter.collection.is_some() || !names.has_collection(&name) {
+                break;
+            }
+            filter.collection = Some(name);
+        } else {
+            if !names.has_tag(&name) {
+                break;
+            }
+            filter.tags.push(name);
+        }
+        rest = &word[name_len..];
+    }
+
+    if filter.collection.is_none() && !config.rag.default_collection.is_empty() {
+        filter.collection = Some(config.rag.default_collection.clone());
+    }
+    if rest.len() == text.len() {
+        return (filter, text);
+    }
+    (filter, rest.trim_start())
+}
+
+/// Headless index management from the command line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndexCommand {
+    /// `--ingest`: embed new/changed files, then print stats
+    Ingest,
+    /// `--reindex`: discard the index and embed everything again
+    Reindex,
+    /// `--index-stats`: print what is indexed without touching it
+    Stats,
+}
+
+impl IndexCommand {
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        args.iter().find_map(|arg| match arg.as_str() {
+            "--ingest" => Some(Self::Ingest),
+            "--reindex" => Some(Self::Reindex),
+            "--index-stats" => Some(Self::Stats),
+            _ => None,
+        })
+    }
+
+    pub async fn run(self, config: &Config) -> Result<()> {
+        let mut config = config.clone();
+        if !config.rag.enabled {
+            println!("[*] RAG is disabled in config.toml; using the index anyway.");
+            config.rag.enabled = true;
+        }
+        crypt::init(&config, &rag::index_file_path(&config), crypt::console_prompt)?;
+
+        if self != Self::Stats {
+            let rag = rag::RagSystem::new(&config).await;
+            if let Some(e) = rag.get_init_error() {
+                anyhow::bail!("Embedding model unavailable: {}", e);
+            }
+            if self == Self::Reindex {
+                rag::clear_index(&config)?;
+                println!("[RAG] Cleared the existing index.");
+            }
+            let count = rag.ingest().await?;
+            println!("[RAG] {} chunk(s) indexed.\n", count);
+        }
+        rag::print_index_stats(&config)
+    }
+}
+
 pub struct KnowledgeProvider {
     rag: Option<Arc<rag::RagSystem>>,
+    progress: Option<rag::IngestProgress>,
 }
 
 impl KnowledgeProvider {
-    pub async fn new(config: &Config) -> Result<Self> {
+    /// `progress` is told how far embedding has got whenever an ingest has new chunks
+    pub async fn new(config: &Config, progress: Option<rag::IngestProgress>) -> Result<Self> {
         let rag = if config.rag.enabled {
             println!("[*] Initializing Local RAG System...");
             let sys = rag::RagSystem::new(config).await;
@@ -20,55 +132,118 @@ impl KnowledgeProvider {
             None
         };
 
-        let provider = Self { rag };
+        let provider = Self { rag, progress };
 
         // Initial Ingestion (Non-blocking if possible, but for MVP we might await or spawn)
         if let Some(rag_sys) = &provider.rag {
              let rag_clone = rag_sys.clone();
+             let refresh_hours = config.rag.url_refresh_hours;
+             let progress = provider.progress.clone();
              tokio::spawn(async move {
-                 if let Err(e) = rag_clone.ingest().await {
+                 if let Err(e) = rag_clone.ingest_with_progress(progress.clone()).await {
                      eprintln!("[!] RAG Ingestion Failed: {}", e);
                  }
+                 // Re-ingest periodically so pages from urls.txt age out; unchanged files are reused.
+                 // Each refresh waits on the ingest lock, so it can't save over a capture in progress.
+                 if refresh_hours == 0 {
+                     return;
+                 }
+                 loop {
+                     tokio::time::sleep(std::time::Duration::from_secs(refresh_hours.saturating_mul(3600))).await;
+                     if let Err(e) = rag_clone.ingest_with_progress(progress.clone()).await {
+                         eprintln!("[!] RAG Refresh Failed: {}", e);
+                     }
+                 }
              });
         }
 
         Ok(provider)
     }
 
-    pub async fn gather_context(&self, query: &str, config: &Config) -> Result<(String, Vec<String>)> {
+    /// Save captured text under knowledge/captured/ and embed it right away, so it is
+    /// retrievable for the next question (after any ingest already running). Returns the new document's path.
+    pub async fn capture(&self, text: &str, config: &Config) -> Result<std::path::PathBuf> {
+        let rag = self.rag.as_ref().context("RAG is disabled in config.toml")?;
+        let path = rag::write_capture(config, text)?;
+        rag.ingest_with_progress(self.progress.clone()).await?;
+        Ok(path)
+    }
+
+    /// The index's collections and tags, for `split_filters`
+    pub async fn filter_names(&self) -> rag::FilterNames {
+        match &self.rag {
+            Some(rag) => rag.filter_names().await,
+            None => rag::FilterNames::default(),
+        }
+    }
+
+    /// `filter` narrows RAG to a knowledge subfolder and/or tags (see `split_filters`)
+    pub async fn gather_context(&self, query: &str, filter: &rag::QueryFilter, config: &Config) -> Result<GatheredContext> {
         let mut context = String::new();
         let mut warnings = Vec::new();
+        let mut sources: Vec<String> = Vec::new();
+        let mut links: Vec<String> = Vec::new();
 
         let model_has_search = ModelCapabilities::supports_search(config);
 
         // 1. Web Search - ONLY if model doesn't have built-in search
-        if config.search.enabled && !model_has_search {
-            match search::perform_search(query, &config.search).await {
-                Ok(results) => {
-                    if !results.is_empty() {
-                        context.push_str("Based on web search results:\n");
-                        context.push_str(&results);
-                        context.push_str("\n\n");
+        // (papers are asked for explicitly, and built-in search doesn't cover them)
+        let searched = if filter.academic || (config.search.enabled && config.search.engine == "academic") {
+            Some(("academic papers", "papers", academic::search(query, &config.search).await))
+        } else if config.search.enabled && !model_has_search {
+            Some(("web search results", "web search", search::perform_search(query, &config.search).await))
+        } else {
+            if model_has_search {
+                info!("[*] Model has built-in search capability, skipping external search");
+            }
+            None
+        };
+        match searched {
+            Some((heading, label, Ok(hits))) => {
+                if !hits.is_empty() {
+                    context.push_str(&format!("Based on {}:\n", heading));
+                    context.push_str(&sanitize::fence(label, &search::format_results(&hits)));
+                    context.push('\n');
+                }
+                links.extend(hits.into_iter().map(|hit| hit.url).filter(|url| !url.is_empty()));
+            }
+            Some((_, label, Err(e))) => {
+                let msg = format!("Search failed ({}): {}", label, e);
+                eprintln!("{}", msg);
+                warnings.push(msg);
+            }
+            None => {}
+        }
+
+        if config.search.wikipedia && !model_has_search {
+            match wikipedia::lookup(query, &config.search).await {
+                Ok(summaries) => {
+                    if !summaries.is_empty() {
+                        context.push_str("Based on Wikipedia:\n");
+                        context.push_str(&sanitize::fence("wikipedia", &summaries));
+                        context.push('\n');
                     }
                 }
                 Err(e) => {
-                    let msg = format!("Search failed: {}", e);
-                    eprintln!("{}", msg); 
+                    let msg = format!("Wikipedia lookup failed: {}", e);
+                    eprintln!("[!] {}", msg);
                     warnings.push(msg);
                 }
             }
-        } else if model_has_search {
-            info!("[*] Model has built-in search capability, skipping external search");
         }
 
         // 2. Local RAG
         if let Some(rag) = &self.rag {
-            match rag.query(query).await {
+            match rag.query(query, filter).await {
                 Ok(results) => {
+                    info!("[Knowledge] RAG returned {} passages", results.len());
                     if !results.is_empty() {
                         context.push_str("Based on your knowledge base:\n");
-                        for (i, doc) in results.iter().enumerate() {
-                            context.push_str(&format!("[Document {}]: {}\n", i + 1, doc));
+                        for passage in &results {
+                            context.push_str(&sanitize::fence(&passage.citation, &passage.content));
+                            if !sources.contains(&passage.citation) {
+                                sources.push(passage.citation.clone());
+                            }
                         }
                         context.push_str("\n\n");
                     }
@@ -76,11 +251,57 @@ impl KnowledgeProvider {
                 Err(e) => {
                     let msg = format!("RAG Query Failed: {}", e);
                     eprintln!("[!] {}", msg);
+                    error!("[Knowledge] {}", msg);
                     warnings.push(msg);
                 }
             }
         }
 
-        Ok((context, warnings))
+        if !context.is_empty() {
+            context = format!("{}\n\n{}", sanitize::CONTEXT_NOTICE, context);
+        }
+
+        Ok(GatheredContext { text: context, warnings, sources, links })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_filters() {
+        let filter = |collection: Option<&str>, tags: &[&str]| rag::QueryFilter {
+            collection: collection.map(str::to_string),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        };
+        let mut config = Config::default();
+        let names = rag::FilterNames::new(&["bio", "bio-2", "biology"], &["exam", "unit-2", "dates"]);
+        assert_eq!(split_filters("@bio What is ATP?", &names, &config), (filter(Some("bio"), &[]), "What is ATP?"));
+        assert_eq!(split_filters("What is ATP?", &names, &config), (filter(None, &[]), "What is ATP?"));
+        assert_eq!(split_filters("@ what", &names, &config), (filter(None, &[]), "@ what"));
+        assert_eq!(split_filters("#exam #unit-2 @bio cells", &names, &config), (filter(Some("bio"), &["exam", "unit-2"]), "cells"));
+        assert_eq!(split_filters("#1. Which is true?", &names, &config), (filter(None, &[]), "#1. Which is true?"));
+        // Not a tag in the index: part of the question
+        assert_eq!(split_filters("#include <stdio.h> why does this fail", &names, &config), (filter(None, &[]), "#include <stdio.h> why does this fail"));
+        assert_eq!(split_filters("#exam #include x", &names, &config), (filter(None, &["exam"]), "#include x"));
+        assert_eq!(split_filters("@Override public void run()", &names, &config), (filter(None, &[]), "@Override public void run()"));
+        let papers = rag::QueryFilter { academic: true, ..filter(Some("bio"), &[]) };
+        assert_eq!(split_filters("!papers @bio CRISPR off-target effects", &names, &config), (papers, "CRISPR off-target effects"));
+        assert_eq!(split_filters("!paperswithcode", &names, &config), (filter(None, &[]), "!paperswithcode"));
+
+        config.rag.default_collection = "history".to_string();
+        assert_eq!(split_filters("Who won WW2?", &names, &config), (filter(Some("history"), &[]), "Who won WW2?"));
+        assert_eq!(split_filters("@bio-2 cells", &names, &config), (filter(Some("bio-2"), &[]), "cells"));
+        assert_eq!(split_filters("#dates Who won WW2?", &names, &config), (filter(Some("history"), &["dates"]), "Who won WW2?"));
+    }
+
+    #[test]
+    fn test_index_command_args() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(IndexCommand::from_args(&args(&["exe", "--debug", "--reindex"])), Some(IndexCommand::Reindex));
+        assert_eq!(IndexCommand::from_args(&args(&["exe", "--index-stats"])), Some(IndexCommand::Stats));
+        assert_eq!(IndexCommand::from_args(&args(&["exe", "--stats"])), None);
     }
 }
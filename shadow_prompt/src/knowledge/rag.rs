@@ -5,26 +5,455 @@ use glob::glob;
 use fastembed::{TextEmbedding, InitOptions, EmbeddingModel};
 use serde::{Deserialize, Serialize};
 use crate::config::{Config, get_exe_dir};
+use super::embedder::{Embedder, LEGACY_FASTEMBED_ID};
+use super::crypt;
+use super::formats;
+use super::store::{self, VectorStore};
+use super::web;
 
 use std::collections::HashMap;
+use text_splitter::{MarkdownSplitter, TextSplitter};
 
-// Simple Document struct for In-Memory/JSON Storage
+/// Target chunk size; BGE-small only sees ~512 tokens, so whole files embed poorly
+const CHUNK_CHARS: usize = 1200;
+
+/// Binary index header: magic + little-endian format version
+const INDEX_MAGIC: &[u8; 4] = b"SPRI";
+const INDEX_VERSION: u32 = 2;
+
+// Simple Document struct for the binary index (one per chunk)
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct Document {
     id: String,
     path: String,
+    /// Path relative to the knowledge folder (or the URL), used in citations
+    source: String,
+    /// 1-based chunk position within the source (0 = whole file, migrated from older versions)
+    chunk: usize,
+    content: String,
+    /// From Markdown frontmatter or EPUB metadata
+    title: String,
+    /// Frontmatter tags, usable as "#tag" retrieval filters
+    tags: Vec<String>,
+    /// Only filled while ingesting; vectors are persisted in the `.vec` file
+    #[serde(skip)]
+    embedding: Vec<f32>,
+    last_modified: u64,
+}
+
+/// Version 1 layout (before titles and tags)
+#[derive(Deserialize)]
+struct IndexV1 {
+    documents: Vec<DocumentV1>,
+    embedding_model: String,
+    dimensions: usize,
+}
+
+#[derive(Deserialize)]
+struct DocumentV1 {
+    id: String,
+    path: String,
+    source: String,
+    chunk: usize,
+    content: String,
+    last_modified: u64,
+}
+
+/// A file or page waiting to be chunked and embedded
+#[derive(Debug)]
+struct PendingDoc {
+    path: String,
+    content: String,
+    modified: u64,
+    title: String,
+    tags: Vec<String>,
+}
+
+impl PendingDoc {
+    fn new(path: String, content: String, modified: u64) -> Self {
+        Self { path, content, modified, title: String::new(), tags: Vec::new() }
+    }
+}
+
+/// index.json as written before the binary format, with embeddings either inline
+/// (oldest) or already in the `.vec` file (`dimensions` > 0)
+#[derive(Deserialize)]
+struct JsonIndex {
+    documents: Vec<JsonDocument>,
+    #[serde(default)]
+    embedding_model: String,
+    #[serde(default)]
+    dimensions: usize,
+}
+
+#[derive(Deserialize)]
+struct JsonDocument {
+    id: String,
+    path: String,
+    #[serde(default)]
+    source: String,
+    #[serde(default)]
+    chunk: usize,
     content: String,
+    #[serde(default)]
     embedding: Vec<f32>,
     last_modified: u64,
 }
 
+impl Document {
+    /// Top-level subfolder of the knowledge folder ("" for root files and URLs)
+    fn collection(&self) -> &str {
+        if self.source.contains("://") {
+            return "";
+        }
+        self.source.split_once('/').map_or("", |(dir, _)| dir)
+    }
+
+    fn source_label(&self) -> String {
+        if self.source.is_empty() {
+            std::path::Path::new(&self.path).file_name().map_or(self.path.clone(), |n| n.to_string_lossy().into_owned())
+        } else {
+            self.source.clone()
+        }
+    }
+
+    fn matches(&self, filter: &ResolvedFilter) -> bool {
+        filter.collection.as_deref().map_or(true, |c| self.collection() == c)
+            && filter.tags.iter().all(|tag| self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+
+    /// "[notes/week3.md §2]"
+    fn citation(&self) -> String {
+        let name = self.source_label();
+        if self.chunk > 0 {
+            format!("[{} §{}]", name, self.chunk)
+        } else {
+            format!("[{}]", name)
+        }
+    }
+}
+
+/// Called with (chunks embedded, chunks to embed) while ingesting, e.g. to update the overlay
+pub type IngestProgress = std::sync::Arc<dyn Fn(usize, usize) + Send + Sync>;
+
+/// Restricts retrieval to one collection and/or documents carrying every listed tag
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryFilter {
+    pub collection: Option<String>,
+    pub tags: Vec<String>,
+    /// `!papers`: search academic papers instead of the web (parsed with the RAG filters)
+    pub academic: bool,
+}
+
+/// The collections and tags in the index, so `split_filters` only takes "@name"/"#name"
+/// words that name one ("@Override" and "#include <stdio.h>" stay part of the question)
+#[derive(Debug, Clone, Default)]
+pub struct FilterNames {
+    collections: Vec<String>,
+    tags: Vec<String>,
+}
+
+impl FilterNames {
+    #[cfg(test)]
+    pub fn new(collections: &[&str], tags: &[&str]) -> Self {
+        let owned = |names: &[&str]| names.iter().map(|n| n.to_string()).collect();
+        Self { collections: owned(collections), tags: owned(tags) }
+    }
+
+    pub fn has_collection(&self, name: &str) -> bool {
+        resolve_collection(&self.collections, name).is_ok()
+    }
+
+    pub fn has_tag(&self, name: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A `QueryFilter` checked against the index (collection prefixes expanded)
+struct ResolvedFilter {
+    collection: Option<String>,
+    tags: Vec<String>,
+}
+
+/// A retrieved chunk and where it came from
+#[derive(Debug, Clone)]
+pub struct Passage {
+    pub citation: String,
+    pub content: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 struct RagIndex {
     documents: Vec<Document>,
+    /// `Embedder::id()` of the backend that produced the vectors (empty = legacy FastEmbed)
+    embedding_model: String,
+    /// Vector length in the `.vec` file
+    dimensions: usize,
+    #[serde(skip)]
+    store: Option<VectorStore>,
+}
+
+impl RagIndex {
+    fn collections(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.documents.iter()
+            .map(|d| d.collection())
+            .filter(|c| !c.is_empty())
+            .map(str::to_string)
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Every tag with the number of sources carrying it, most used first
+    fn tags(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, std::collections::HashSet<&str>> = HashMap::new();
+        for doc in &self.documents {
+            for tag in &doc.tags {
+                counts.entry(tag.to_lowercase()).or_default().insert(&doc.path);
+            }
+        }
+        let mut tags: Vec<(String, usize)> = counts.into_iter().map(|(tag, sources)| (tag, sources.len())).collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tags
+    }
+
+    fn filter_names(&self) -> FilterNames {
+        FilterNames { collections: self.collections(), tags: self.tags().into_iter().map(|(tag, _)| tag).collect() }
+    }
+
+    /// A collection or tag the index doesn't have (a stale `default_collection`, or the index
+    /// changed since `split_filters`) is dropped with a warning, so RAG still runs unfiltered
+    fn resolve(&self, filter: &QueryFilter) -> ResolvedFilter {
+        let collection = filter.collection.as_ref().and_then(|name| match resolve_collection(&self.collections(), name) {
+            Ok(collection) => Some(collection),
+            Err(e) => {
+                warn!("[RAG] Ignoring collection filter: {}", e);
+                None
+            }
+        });
+        let names = self.filter_names();
+        let tags = filter.tags.iter()
+            .filter(|tag| {
+                let known = names.has_tag(tag);
+                if !known {
+                    warn!("[RAG] Ignoring unknown tag '#{}'", tag);
+                }
+                known
+            })
+            .cloned()
+            .collect();
+        ResolvedFilter { collection, tags }
+    }
+
+    fn read(index_file_path: &std::path::Path) -> Result<Self> {
+        let bytes = crypt::open(fs::read(index_file_path)?)?;
+        if bytes.len() < 8 || &bytes[..4] != INDEX_MAGIC {
+            anyhow::bail!("not a ShadowPrompt index");
+        }
+        let version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        match version {
+            INDEX_VERSION => Ok(bincode::deserialize(&bytes[8..])?),
+            1 => {
+                let old: IndexV1 = bincode::deserialize(&bytes[8..])?;
+                Ok(Self {
+                    documents: old.documents.into_iter().map(|d| Document {
+                        // Markdown/EPUB files are re-read once so their frontmatter is picked up
+                        last_modified: if formats::has_metadata(&d.path) { 0 } else { d.last_modified },
+                        id: d.id,
+                        path: d.path,
+                        source: d.source,
+                        chunk: d.chunk,
+                        content: d.content,
+                        title: String::new(),
+                        tags: Vec::new(),
+                        embedding: Vec::new(),
+                    }).collect(),
+                    embedding_model: old.embedding_model,
+                    dimensions: old.dimensions,
+                    store: None,
+                })
+            }
+            _ => anyhow::bail!("unsupported index version {} (expected {})", version, INDEX_VERSION),
+        }
+    }
+
+    fn save(&self, index_file_path: &std::path::Path) -> Result<()> {
+        let mut bytes = INDEX_MAGIC.to_vec();
+        bytes.extend_from_slice(&INDEX_VERSION.to_le_bytes());
+        bytes.extend(bincode::serialize(self)?);
+        fs::write(index_file_path, crypt::seal(bytes)?)?;
+        Ok(())
+    }
+
+    /// Read the document list and map its vectors
+    fn load(index_file_path: &std::path::Path) -> Result<Self> {
+        let mut index = Self::read(index_file_path)?;
+        if index.dimensions > 0 && !index.documents.is_empty() {
+            let vector_path = store::vector_path(index_file_path);
+            index.store = Some(VectorStore::open(&vector_path, index.documents.len(), index.dimensions)?);
+        }
+        Ok(index)
+    }
+}
+
+/// One-time conversion of an index.json next to `index_file_path` into the binary format.
+/// The JSON file is removed afterwards so it isn't migrated again.
+fn migrate_json_index(index_file_path: &std::path::Path) -> Result<()> {
+    let json_path = index_file_path.with_extension("json");
+    if index_file_path.exists() || !json_path.exists() {
+        return Ok(());
+    }
+
+    let legacy: JsonIndex = serde_json::from_str(&fs::read_to_string(&json_path)?)
+        .context("Failed to parse the old JSON index")?;
+    let mut dimensions = legacy.dimensions;
+    if dimensions == 0 {
+        let vector_path = store::vector_path(index_file_path);
+        dimensions = store::write_vectors(&vector_path, legacy.documents.iter().map(|d| d.embedding.as_slice()))?;
+    }
+
+    let index = RagIndex {
+        documents: legacy.documents.into_iter().map(|d| Document {
+            id: d.id,
+            path: d.path,
+            source: d.source,
+            chunk: d.chunk,
+            content: d.content,
+            title: String::new(),
+            tags: Vec::new(),
+            embedding: Vec::new(),
+            last_modified: d.last_modified,
+        }).collect(),
+        embedding_model: legacy.embedding_model,
+        dimensions,
+        store: None,
+    };
+    index.save(index_file_path)?;
+    fs::remove_file(&json_path)?;
+    println!("[RAG] Migrated {} documents from {:?} to the binary index.", index.documents.len(), json_path);
+    Ok(())
+}
+
+/// data/rag_index/index.bin, or `<name>.bin` next to an `index_path` naming a .json file
+pub fn index_file_path(config: &Config) -> std::path::PathBuf {
+    let index_base = get_exe_dir().join(&config.rag.index_path);
+    let path = if config.rag.index_path.ends_with(".json") {
+        index_base.with_extension("bin")
+    } else {
+        index_base.join("index.bin")
+    };
+    if let Err(e) = migrate_json_index(&path) {
+        eprintln!("[RAG] {}. Rebuilding the index.", e);
+    }
+    path
+}
+
+/// Delete the index and its vectors so the next ingest embeds everything again (`--reindex`)
+pub fn clear_index(config: &Config) -> Result<()> {
+    let path = index_file_path(config);
+    for file in [store::vector_path(&path), path] {
+        if file.exists() {
+            fs::remove_file(&file).with_context(|| format!("Failed to delete {:?}", file))?;
+        }
+    }
+    Ok(())
+}
+
+/// Subfolder of knowledge/ that `key_rag_capture` writes to (searchable as "@captured")
+pub const CAPTURE_DIR: &str = "captured";
+
+/// Save text captured mid-session as a new markdown document, e.g.
+/// knowledge/captured/2026-10-14_153012.md; returns its path
+pub fn write_capture(config: &Config, text: &str) -> Result<std::path::PathBuf> {
+    let text = text.trim();
+    if text.is_empty() {
+        anyhow::bail!("Nothing to save (empty text)");
+    }
+    let dir = get_exe_dir().join(&config.rag.knowledge_path).join(CAPTURE_DIR);
+    fs::create_dir_all(&dir).context("Failed to create capture folder")?;
+
+    let now = chrono::Local::now();
+    let stamp = now.format("%Y-%m-%d_%H%M%S").to_string();
+    let mut path = dir.join(format!("{}.md", stamp));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}_{}.md", stamp, n));
+        n += 1;
+    }
+
+    let body = format!("# Captured {}\n\n{}\n", now.format("%Y-%m-%d %H:%M"), text);
+    fs::write(&path, body).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(path)
+}
+
+/// Summary of the on-disk index with per-source status (`--index-stats`)
+pub fn print_index_stats(config: &Config) -> Result<()> {
+    let path = index_file_path(config);
+    if !path.exists() {
+        println!("[RAG] No index at {:?}. Run with --ingest to build one.", path);
+        return Ok(());
+    }
+    let index = RagIndex::read(&path)?;
+    let file_size = |p: &std::path::Path| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    let size = file_size(&path) + file_size(&store::vector_path(&path));
+
+    // (path, label, chunks, last_modified) per source, in index order
+    let mut sources: Vec<(&str, String, usize, u64)> = Vec::new();
+    for doc in &index.documents {
+        match sources.iter_mut().find(|s| s.0 == doc.path) {
+            Some(entry) => entry.2 += 1,
+            None => sources.push((&doc.path, doc.source_label(), 1, doc.last_modified)),
+        }
+    }
+
+    let model = if index.embedding_model.is_empty() { LEGACY_FASTEMBED_ID } else { index.embedding_model.as_str() };
+    println!("Index:       {}", path.display());
+    println!("Model:       {} ({} dimensions)", model, index.dimensions);
+    println!("Sources:     {}", sources.len());
+    println!("Chunks:      {}", index.documents.len());
+    println!("Size:        {:.1} KB", size as f64 / 1024.0);
+    let collections = index.collections();
+    if !collections.is_empty() {
+        println!("Collections: {}", collections.join(", "));
+    }
+    let tags = index.tags();
+    if !tags.is_empty() {
+        let list: Vec<String> = tags.iter().map(|(tag, count)| format!("{} ({})", tag, count)).collect();
+        println!("Tags:        {}", list.join(", "));
+    }
+    println!();
+
+    for (doc_path, label, chunks, last_modified) in sources {
+        let status = if doc_path.contains("://") {
+            let fetched = chrono::DateTime::from_timestamp(last_modified as i64, 0)
+                .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            format!("fetched {}", fetched)
+        } else {
+            match fs::metadata(doc_path).and_then(|m| m.modified()) {
+                Ok(modified) => {
+                    let secs = modified.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+                    if secs == last_modified { "ok".to_string() } else { "modified (re-ingest)".to_string() }
+                }
+                Err(_) => "missing (dropped on next ingest)".to_string(),
+            }
+        };
+        println!("  {:>4} chunk(s)  {:<40} {}", chunks, label, status);
+    }
+    Ok(())
+}
+
+/// Held for a whole ingest (scan, embed, save). Startup, `capture`, the URL refresh and the
+/// wizard each rebuild the document list from disk, so without it the last save would drop
+/// what a concurrent one just added. Process-wide, since the wizard uses its own `RagSystem`.
+fn ingest_lock() -> &'static tokio::sync::Mutex<()> {
+    static INGEST: std::sync::OnceLock<tokio::sync::Mutex<()>> = std::sync::OnceLock::new();
+    INGEST.get_or_init(|| tokio::sync::Mutex::new(()))
 }
 
 pub struct RagSystem {
-    embedding_model: Option<TextEmbedding>,
+    embedding_model: Option<Embedder>,
     config: Config,
     cached_index: tokio::sync::RwLock<Option<RagIndex>>,
     is_operational: bool,
@@ -33,6 +462,25 @@ pub struct RagSystem {
 
 impl RagSystem {
     pub async fn new(config: &Config) -> Self {
+        if config.rag.embedding_backend == "ollama" {
+            // Nothing to download; connection problems surface on the first ingest/query
+            let (model, is_operational, init_error) = match Embedder::ollama(config) {
+                Ok(e) => (Some(e), true, None),
+                Err(e) => {
+                    let err_msg = e.to_string();
+                    eprintln!("[!] Failed to initialize Ollama embeddings: {}", err_msg);
+                    (None, false, Some(err_msg))
+                }
+            };
+            return Self {
+                embedding_model: model,
+                config: config.clone(),
+                cached_index: tokio::sync::RwLock::new(None),
+                is_operational,
+                init_error,
+            };
+        }
+
         // Initialize Embedding Model
         // We use BGE-Small-EN-V1.5 which is small and fast.
         let mut options = InitOptions::default();
@@ -41,7 +489,7 @@ impl RagSystem {
         options.cache_dir = get_exe_dir().join("data").join("models");
 
         let (model, is_operational, init_error) = match TextEmbedding::try_new(options) {
-            Ok(m) => (Some(m), true, None),
+            Ok(m) => (Some(Embedder::FastEmbed(std::sync::Arc::new(m))), true, None),
             Err(e) => {
                 let err_msg = e.to_string();
                 eprintln!("[!] Failed to initialize FastEmbed: {}", err_msg);
@@ -67,36 +515,87 @@ impl RagSystem {
     }
 
 
+    /// Vectors from different embedding models can't be compared, so a mismatched index is rebuilt
+    fn index_matches_model(&self, index: &RagIndex) -> bool {
+        let Some(embedder) = &self.embedding_model else {
+            return true;
+        };
+        let index_model = if index.embedding_model.is_empty() {
+            LEGACY_FASTEMBED_ID
+        } else {
+            index.embedding_model.as_str()
+        };
+        index_model == embedder.id()
+    }
+
+    fn current_model_id(&self) -> String {
+        self.embedding_model.as_ref().map(|e| e.id()).unwrap_or_default()
+    }
+
+    fn index_file_path(&self) -> std::path::PathBuf {
+        index_file_path(&self.config)
+    }
+
+    /// Existing chunks grouped by source path
+    fn load_existing_docs(&self, index_file_path: &std::path::Path) -> HashMap<String, Vec<Document>> {
+        let mut existing_docs = HashMap::new();
+        if let Ok(mut existing_index) = RagIndex::read(index_file_path) {
+            if existing_index.dimensions > 0 {
+                // Copied into memory rather than mapped, since ingest rewrites the file
+                let vector_path = store::vector_path(index_file_path);
+                match store::read_vectors(&vector_path, existing_index.documents.len(), existing_index.dimensions) {
+                    Ok(vectors) => {
+                        for (doc, vector) in existing_index.documents.iter_mut().zip(vectors) {
+                            doc.embedding = vector;
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("[RAG] {}. Re-embedding all documents.", e);
+                        return existing_docs;
+                    }
+                }
+            }
+            // Only called while ingesting (under `ingest_lock`), so a backend switch re-embeds
+            // everything in one run and nothing saves old-model vectors in between
+            if self.index_matches_model(&existing_index) {
+                for doc in existing_index.documents {
+                    existing_docs.entry(doc.path.clone()).or_insert_with(Vec::new).push(doc);
+                }
+            } else {
+                println!("[RAG] Embedding backend changed. Re-embedding all documents.");
+            }
+        }
+        existing_docs
+    }
+
     #[allow(clippy::type_complexity)]
     fn get_files_to_embed(
         &self, 
         root_path: &std::path::Path, 
         index_file_path: &std::path::Path
-    ) -> Result<(Vec<Document>, Vec<(String, String, u64)>)> {
-        // Load existing index if available
-        let mut existing_docs: HashMap<String, Document> = HashMap::new();
-        if index_file_path.exists() {
-             if let Ok(content) = fs::read_to_string(index_file_path) {
-                 if let Ok(existing_index) = serde_json::from_str::<RagIndex>(&content) {
-                     for doc in existing_index.documents {
-                         existing_docs.insert(doc.path.clone(), doc);
-                     }
-                      println!("[RAG] Loaded {} existing documents from index.", existing_docs.len());
-                  }
-              }
+    ) -> Result<(Vec<Document>, Vec<PendingDoc>)> {
+        let existing_docs = self.load_existing_docs(index_file_path);
+        if !existing_docs.is_empty() {
+            println!("[RAG] Loaded {} existing documents from index.", existing_docs.len());
         }
+        // Lets a renamed or touched-but-unchanged file keep its vectors
+        let existing_by_hash: HashMap<u64, &Vec<Document>> = existing_docs
+            .values()
+            .map(|docs| (content_hash(docs.iter().map(|d| d.content.as_str())), docs))
+            .collect();
+        // Content hash -> first file with that content, so copies are indexed once
+        let mut seen_content: HashMap<u64, String> = HashMap::new();
 
         let root_path_str = root_path.display().to_string();
         
         let mut docs_to_embed = Vec::new();
         let mut final_docs = Vec::new();
 
-        let patterns = vec![
-            format!("{}/*.md", root_path_str),
-            format!("{}/*.txt", root_path_str),
-            format!("{}/**/*.md", root_path_str),
-            format!("{}/**/*.txt", root_path_str),
-        ];
+        let patterns: Vec<String> = formats::EXTENSIONS
+            .iter()
+            .flat_map(|ext| [format!("{}/*.{}", root_path_str, ext), format!("{}/**/*.{}", root_path_str, ext)])
+            .collect();
+        let url_list = root_path.join(web::URL_LIST_FILE);
 
         let mut found_paths = std::collections::HashSet::new();
 
@@ -109,23 +608,54 @@ impl RagSystem {
                             continue;
                         }
                         found_paths.insert(path_str.clone());
+                        if path == url_list {
+                            continue; // A list of pages, handled by get_urls_to_embed
+                        }
 
                         let metadata = fs::metadata(&path)?;
                         let modified = metadata.modified()?.duration_since(std::time::UNIX_EPOCH)?.as_secs();
 
-                        let mut reuse = false;
-                        if let Some(existing_doc) = existing_docs.get(&path_str) {
-                            if existing_doc.last_modified == modified {
-                                final_docs.push(existing_doc.clone());
-                                reuse = true;
+                        if let Some(existing) = existing_docs.get(&path_str).filter(|docs| docs[0].last_modified == modified) {
+                            let hash = content_hash(existing.iter().map(|d| d.content.as_str()));
+                            if !is_duplicate(&mut seen_content, hash, root_path, &path_str) {
+                                final_docs.extend(existing.iter().cloned());
                             }
+                            continue;
                         }
 
-                        if !reuse {
-                            let content = fs::read_to_string(&path).unwrap_or_default();
-                            if !content.trim().is_empty() {
-                                docs_to_embed.push((path_str, content, modified));
+                        let extracted = match formats::extract(&path) {
+                            Ok(extracted) if !extracted.text.trim().is_empty() => extracted,
+                            Ok(_) => continue,
+                            Err(e) => {
+                                eprintln!("[RAG] Skipping {}: {}", source_name(root_path, &path_str), e);
+                                continue;
                             }
+                        };
+
+                        let hash = content_hash(chunk_text(&path_str, &extracted.text));
+                        if is_duplicate(&mut seen_content, hash, root_path, &path_str) {
+                            continue;
+                        }
+                        match existing_by_hash.get(&hash) {
+                            Some(docs) => {
+                                let source = source_name(root_path, &path_str);
+                                if docs[0].path != path_str {
+                                    println!("[RAG]   ~ {} (moved from {}, reusing embeddings)", source, docs[0].source_label());
+                                }
+                                final_docs.extend(docs.iter().map(|d| Document {
+                                    path: path_str.clone(),
+                                    source: source.clone(),
+                                    title: extracted.title.clone(),
+                                    tags: extracted.tags.clone(),
+                                    last_modified: modified,
+                                    ..d.clone()
+                                }));
+                            }
+                            None => docs_to_embed.push(PendingDoc {
+                                title: extracted.title,
+                                tags: extracted.tags,
+                                ..PendingDoc::new(path_str, extracted.text, modified)
+                            }),
                         }
                     },
                     Err(e) => eprintln!("[RAG] Error reading file: {:?}", e),
@@ -136,84 +666,236 @@ impl RagSystem {
         Ok((final_docs, docs_to_embed))
     }
 
+    /// Pages from knowledge/urls.txt. `last_modified` holds the fetch time, so a page is
+    /// reused until it is older than `url_refresh_hours`; a failed refresh keeps the old copy.
+    #[allow(clippy::type_complexity)]
+    async fn get_urls_to_embed(
+        &self,
+        root_path: &std::path::Path,
+        index_file_path: &std::path::Path
+    ) -> Result<(Vec<Document>, Vec<PendingDoc>)> {
+        let url_list = root_path.join(web::URL_LIST_FILE);
+        let Ok(list) = fs::read_to_string(&url_list) else {
+            return Ok((vec![], vec![]));
+        };
+        let urls = web::parse_url_list(&list);
+        if urls.is_empty() {
+            return Ok((vec![], vec![]));
+        }
+
+        let existing_docs = self.load_existing_docs(index_file_path);
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+        let ttl_secs = self.config.rag.url_refresh_hours.saturating_mul(3600);
+        let client = web::client(&self.config)?;
+
+        let mut final_docs = Vec::new();
+        let mut docs_to_embed = Vec::new();
+
+        for url in urls {
+            let existing = existing_docs.get(&url);
+            if let Some(docs) = existing {
+                if ttl_secs == 0 || now.saturating_sub(docs[0].last_modified) < ttl_secs {
+                    final_docs.extend(docs.iter().cloned());
+                    continue;
+                }
+            }
+
+            match web::fetch_readable(&client, &url).await {
+                Ok(content) if !content.trim().is_empty() => {
+                    // Unchanged page: keep the vectors, just restart the TTL
+                    let unchanged = existing.filter(|docs| {
+                        docs.iter().map(|d| d.content.as_str()).eq(chunk_text(&url, &content))
+                    });
+                    if let Some(docs) = unchanged {
+                        final_docs.extend(docs.iter().map(|d| Document { last_modified: now, ..d.clone() }));
+                    } else {
+                        docs_to_embed.push(PendingDoc::new(url, content, now));
+                    }
+                }
+                Ok(_) => eprintln!("[RAG] No readable text at {}", url),
+                Err(e) => {
+                    eprintln!("[RAG] {}", e);
+                    if let Some(docs) = existing {
+                        final_docs.extend(docs.iter().cloned());
+                    }
+                }
+            }
+        }
+
+        Ok((final_docs, docs_to_embed))
+    }
+
     pub async fn ingest(&self) -> Result<usize> {
+        self.ingest_with_progress(None).await
+    }
+
+    pub async fn ingest_with_progress(&self, progress: Option<IngestProgress>) -> Result<usize> {
         if !self.config.rag.enabled || !self.is_operational {
              return Ok(0);
         }
 
+        // Re-embedding would only fail at save time, after overwriting the vectors
+        if crypt::is_locked() {
+            eprintln!("[RAG] Index is locked (no passphrase). Skipping ingestion.");
+            return Ok(0);
+        }
+
         let embedding_model = match &self.embedding_model {
             Some(m) => m,
             None => return Ok(0),
         };
 
-        let exe_dir = get_exe_dir();
-        let root_path = exe_dir.join(&self.config.rag.knowledge_path);
-        let index_base = exe_dir.join(&self.config.rag.index_path);
-        
+        // Until the index is saved: a second ingest waits and then sees this one's documents
+        let _ingesting = ingest_lock().lock().await;
+
+        let root_path = get_exe_dir().join(&self.config.rag.knowledge_path);
+
         if !root_path.exists() {
             fs::create_dir_all(&root_path).context("Failed to create knowledge directory")?;
             return Ok(0);
         }
 
-        let index_file_path = if self.config.rag.index_path.ends_with(".json") {
-            index_base.clone()
-        } else {
-            index_base.join("index.json")
-        };
+        let index_file_path = self.index_file_path();
 
         if let Some(parent) = index_file_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
+        // Sources in the index before this run, for the added/updated/removed summary
+        let previous: std::collections::HashSet<String> = RagIndex::read(&index_file_path)
+            .map(|index| index.documents.into_iter().map(|d| d.path).collect())
+            .unwrap_or_default();
+
         println!("[RAG] Scanning knowledge folder: {}", root_path.display());
         
-        let (mut final_docs, docs_to_embed) = self.get_files_to_embed(&root_path, &index_file_path)?;
+        let (mut final_docs, mut docs_to_embed) = self.get_files_to_embed(&root_path, &index_file_path)?;
+        let (url_docs, urls_to_embed) = self.get_urls_to_embed(&root_path, &index_file_path).await?;
+        final_docs.extend(url_docs);
+        docs_to_embed.extend(urls_to_embed);
+
+        let summary = IngestSummary::new(&previous, &final_docs, &docs_to_embed);
+        for path in &summary.removed {
+            println!("[RAG]   - {} (no longer in the knowledge folder)", source_name(&root_path, path));
+        }
 
         if docs_to_embed.is_empty() && final_docs.is_empty() {
-             return Ok(0);
+            if !previous.is_empty() {
+                // Everything was deleted: drop the old index instead of keeping stale chunks
+                *self.cached_index.write().await = None;
+                clear_index(&self.config)?;
+                println!("[RAG] {}", summary);
+            }
+            return Ok(0);
         }
 
         if !docs_to_embed.is_empty() {
             println!("[RAG] Found {} new/modified documents. Generating embeddings...", docs_to_embed.len());
-            let texts: Vec<String> = docs_to_embed.iter().map(|(_, c, _)| c.clone()).collect();
-            let embeddings = embedding_model.embed(texts, None)?;
+            for pending in &docs_to_embed {
+                println!("[RAG]   + {}", source_name(&root_path, &pending.path));
+            }
+            let mut chunks = Vec::new();
+            for pending in &docs_to_embed {
+                let source = source_name(&root_path, &pending.path);
+                for (i, chunk) in chunk_text(&pending.path, &pending.content).into_iter().enumerate() {
+                    chunks.push((pending, source.clone(), i + 1, chunk.to_string()));
+                }
+            }
+            // The title gives chunks from the middle of a book or note some context
+            let texts: Vec<String> = chunks.iter().map(|(pending, _, _, chunk)| {
+                if pending.title.is_empty() { chunk.clone() } else { format!("{}\n\n{}", pending.title, chunk) }
+            }).collect();
+            let embeddings = embedding_model.embed_batched(texts, |done, total| {
+                println!("[RAG]   {}/{} chunks embedded", done, total);
+                if let Some(progress) = &progress {
+                    progress(done, total);
+                }
+            }).await?;
 
-            for (i, embedding) in embeddings.into_iter().enumerate() {
-                let (path, content, modified) = &docs_to_embed[i];
+            for ((pending, source, chunk, content), embedding) in chunks.into_iter().zip(embeddings) {
                 final_docs.push(Document {
                     id: uuid::Uuid::new_v4().to_string(), // Generate unique ID
-                    path: path.clone(),
-                    content: content.clone(),
+                    path: pending.path.clone(),
+                    source,
+                    chunk,
+                    content,
+                    title: pending.title.clone(),
+                    tags: pending.tags.clone(),
                     embedding,
-                    last_modified: *modified,
+                    last_modified: pending.modified,
                 });
             }
         } else {
             println!("[RAG] No new documents to embed. Using cached index.");
         }
 
-        let index = RagIndex {
+        // Unmap the old vectors before overwriting them (Windows refuses to replace a mapped file)
+        let mut cache = self.cached_index.write().await;
+        *cache = None;
+
+        let vector_path = store::vector_path(&index_file_path);
+        let dimensions = store::write_vectors(&vector_path, final_docs.iter().map(|d| d.embedding.as_slice()))?;
+
+        let mut index = RagIndex {
             documents: final_docs,
+            embedding_model: self.current_model_id(),
+            dimensions,
+            store: None,
         };
 
-        // Save to Disk (JSON)
-        let json = serde_json::to_string_pretty(&index)?;
-        fs::write(&index_file_path, json)?;
+        // Save document list; embeddings are skipped and live in the .vec file
+        index.save(&index_file_path)?;
 
         let count = index.documents.len();
 
         // Update Cache
-        {
-            let mut cache = self.cached_index.write().await;
-            *cache = Some(index);
+        for doc in &mut index.documents {
+            doc.embedding = Vec::new();
         }
+        index.store = Some(VectorStore::open(&vector_path, count, dimensions)?);
+        *cache = Some(index);
+        drop(cache);
 
         println!("[RAG] Saved index to {:?}", index_file_path);
+        println!("[RAG] {}", summary);
 
         Ok(count)
     }
 
-    pub async fn query(&self, text: &str) -> Result<Vec<String>> {
+    /// Load the index from disk unless it is already cached
+    async fn load_cached(&self) {
+        // We use a block to drop the read lock before potentially acquiring a write lock or doing IO
+        let needs_load = {
+            let cache = self.cached_index.read().await;
+            cache.is_none()
+        };
+
+        if needs_load {
+            // Read under the write lock, which an ingest holds while it rewrites the files: reading
+            // outside it could pair the old document list with vectors from a re-embed in progress
+            let mut cache = self.cached_index.write().await;
+            let index_file_path = self.index_file_path();
+
+            if cache.is_none() && index_file_path.exists() {
+                match RagIndex::load(&index_file_path) {
+                    Ok(index) => *cache = Some(index),
+                    Err(e) => eprintln!("[RAG] Failed to load index {:?}: {}", index_file_path, e),
+                }
+            }
+        }
+    }
+
+    /// Collections and tags that `split_filters` may take from a question
+    pub async fn filter_names(&self) -> FilterNames {
+        if !self.config.rag.enabled || !self.is_operational {
+            return FilterNames::default();
+        }
+        self.load_cached().await;
+        self.cached_index.read().await.as_ref().map(RagIndex::filter_names).unwrap_or_default()
+    }
+
+    /// `filter.collection` (a subfolder name or unique prefix of one, see `[rag] default_collection`)
+    /// restricts retrieval to that folder's documents, `filter.tags` to documents tagged with all of them
+    pub async fn query(&self, text: &str, filter: &QueryFilter) -> Result<Vec<Passage>> {
          if !self.config.rag.enabled {
              return Ok(vec![]);
         }
@@ -228,38 +910,7 @@ impl RagSystem {
             None => return Ok(vec![]),
         };
 
-        // Check if cache is empty
-        // We use a block to drop the read lock before potentially acquiring a write lock or doing IO
-        let needs_load = {
-            let cache = self.cached_index.read().await;
-            cache.is_none()
-        };
-
-        if needs_load {
-            let exe_dir = get_exe_dir();
-            let index_base = exe_dir.join(&self.config.rag.index_path);
-            let index_file_path = if self.config.rag.index_path.ends_with(".json") {
-                index_base
-            } else {
-                index_base.join("index.json")
-            };
-
-            if index_file_path.exists() {
-                // Load Index
-                // Note: We might be doing double work if multiple threads race here, 
-                // but for this use case it's acceptable simplicity vs complexity of double-checked locking with async.
-                if let Ok(content) = fs::read_to_string(&index_file_path) {
-                     if let Ok(index) = serde_json::from_str::<RagIndex>(&content) {
-                        let mut cache = self.cached_index.write().await;
-                        *cache = Some(index);
-                     } else {
-                         eprintln!("[RAG] Failed to parse index file: {:?}", index_file_path);
-                     }
-                } else {
-                    eprintln!("[RAG] Failed to read index file: {:?}", index_file_path);
-                }
-            }
-        }
+        self.load_cached().await;
 
         // Now query from cache
         let cache = self.cached_index.read().await;
@@ -272,40 +923,146 @@ impl RagSystem {
             return Ok(vec![]);
         }
 
+        if !self.index_matches_model(index) {
+            eprintln!("[RAG] Index was built with a different embedding backend. Waiting for re-ingest.");
+            return Ok(vec![]);
+        }
+
+        let filter = index.resolve(filter);
+        let is_filtered = filter.collection.is_some() || !filter.tags.is_empty();
+
         // Embed Query
-        let query_embeddings = embedding_model.embed(vec![text.to_string()], None)?;
+        let query_embeddings = embedding_model.embed(vec![text.to_string()]).await?;
         let query_vec = &query_embeddings[0];
 
-        // Calculate Cosine Similarity
-        let mut scores: Vec<(f32, &Document)> = index.documents.iter().map(|doc| {
-            let score = cosine_similarity(query_vec, &doc.embedding);
-            (score, doc)
-        }).collect();
-
-        // Sort by score descending
-        scores.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        let max_results = self.config.rag.max_results;
+        let scores: Vec<(f32, &Document)> = match &index.store {
+            Some(vector_store) => {
+                let allowed = |i: usize| index.documents.get(i).is_some_and(|doc| doc.matches(&filter));
+                let allowed = is_filtered.then_some(&allowed as &dyn Fn(usize) -> bool);
+                vector_store.search(query_vec, max_results, allowed)
+                    .into_iter()
+                    .filter_map(|(score, i)| index.documents.get(i).map(|doc| (score, doc)))
+                    .collect()
+            }
+            None => vec![],
+        };
 
         // Filter and Collect
-        let results: Vec<String> = scores.into_iter()
+        let results: Vec<Passage> = scores.into_iter()
             .filter(|(score, _)| *score >= self.config.rag.min_score)
-            .take(self.config.rag.max_results)
-            .map(|(_, doc)| doc.content.clone())
+            .take(max_results)
+            .map(|(_, doc)| Passage { citation: doc.citation(), content: doc.content.clone() })
             .collect();
 
         Ok(results)
     }
 }
 
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    let dot_product: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
-    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
-    
-    if norm_a == 0.0 || norm_b == 0.0 {
-        return 0.0;
+/// Exact (case-insensitive) folder name, else a unique prefix: "bio" -> "biology"
+fn resolve_collection(collections: &[String], name: &str) -> Result<String> {
+    if let Some(exact) = collections.iter().find(|c| c.eq_ignore_ascii_case(name)) {
+        return Ok(exact.clone());
     }
-    
-    dot_product / (norm_a * norm_b)
+    let lower = name.to_lowercase();
+    let matches: Vec<&String> = collections.iter().filter(|c| c.to_lowercase().starts_with(&lower)).collect();
+    match matches.as_slice() {
+        [only] => Ok((*only).clone()),
+        [] => anyhow::bail!("Unknown collection '@{}' (available: {})", name, collections.join(", ")),
+        _ => anyhow::bail!("Collection '@{}' is ambiguous ({})", name, matches.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", ")),
+    }
+}
+
+/// Split a document into embedding-sized chunks, keeping markdown sections and table rows together
+fn chunk_text<'a>(path: &str, content: &'a str) -> Vec<&'a str> {
+    if formats::is_tabular(path) {
+        chunk_rows(content)
+    } else if path.ends_with(".md") {
+        MarkdownSplitter::new(CHUNK_CHARS).chunks(content).collect()
+    } else {
+        TextSplitter::new(CHUNK_CHARS).chunks(content).collect()
+    }
+}
+
+/// Identifies a document by its chunk texts, independent of path and timestamp
+fn content_hash<'a>(chunks: impl IntoIterator<Item = &'a str>) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for chunk in chunks {
+        chunk.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Records the first file with a given content; later copies are reported and skipped
+fn is_duplicate(seen: &mut HashMap<u64, String>, hash: u64, root_path: &std::path::Path, path: &str) -> bool {
+    match seen.get(&hash) {
+        Some(first) => {
+            println!("[RAG]   = {} (same content as {}, skipped)", source_name(root_path, path), source_name(root_path, first));
+            true
+        }
+        None => {
+            seen.insert(hash, path.to_string());
+            false
+        }
+    }
+}
+
+/// Per-source changes of one ingest run
+#[derive(Debug, Default, PartialEq)]
+struct IngestSummary {
+    added: usize,
+    updated: usize,
+    removed: Vec<String>,
+}
+
+impl IngestSummary {
+    fn new(previous: &std::collections::HashSet<String>, kept: &[Document], to_embed: &[PendingDoc]) -> Self {
+        let current: std::collections::HashSet<&str> = kept.iter().map(|d| d.path.as_str())
+            .chain(to_embed.iter().map(|pending| pending.path.as_str()))
+            .collect();
+        let mut removed: Vec<String> = previous.iter().filter(|p| !current.contains(p.as_str())).cloned().collect();
+        removed.sort();
+        Self {
+            added: current.iter().filter(|p| !previous.contains(**p)).count(),
+            updated: to_embed.iter().filter(|pending| previous.contains(&pending.path)).count(),
+            removed,
+        }
+    }
+}
+
+impl std::fmt::Display for IngestSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} added, {} updated, {} removed", self.added, self.updated, self.removed.len())
+    }
+}
+
+/// Pack whole lines (one per table row) into chunks of up to `CHUNK_CHARS`; a row is
+/// never split, so an overlong one becomes a chunk of its own
+fn chunk_rows(content: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    for line in content.split_inclusive('\n') {
+        let line_end = end + line.len();
+        if end > start && content[start..line_end].trim_end().chars().count() > CHUNK_CHARS {
+            chunks.push(content[start..end].trim_end());
+            start = end;
+        }
+        end = line_end;
+    }
+    if !content[start..].trim().is_empty() {
+        chunks.push(content[start..].trim_end());
+    }
+    chunks
+}
+
+/// "notes/week3.md" for files under the knowledge folder; URLs are kept as-is
+fn source_name(root_path: &std::path::Path, path: &str) -> String {
+    std::path::Path::new(path)
+        .strip_prefix(root_path)
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| path.to_string())
 }
 
 #[cfg(test)]
@@ -339,9 +1096,13 @@ mod tests {
         index.documents.push(Document {
             id: "test".to_string(),
             path: "test.txt".to_string(),
+            source: String::new(),
+            chunk: 0,
             content: "hello world".to_string(),
             embedding: vec![0.1, 0.2, 0.3],
             last_modified: 0,
+            title: String::new(),
+            tags: vec![],
         });
 
         {
@@ -368,7 +1129,7 @@ mod tests {
         let temp_dir = std::env::temp_dir().join(format!("shadow_prompt_test_{}", uuid::Uuid::new_v4()));
         let knowledge_dir = temp_dir.join("knowledge");
         let data_dir = temp_dir.join("data");
-        let index_path = data_dir.join("index.json");
+        let index_path = data_dir.join("index.bin");
 
         fs::create_dir_all(&knowledge_dir)?;
         fs::create_dir_all(&data_dir)?;
@@ -392,22 +1153,30 @@ mod tests {
                 Document {
                     id: "1".to_string(),
                     path: doc1_path.display().to_string(),
+                    source: String::new(),
+                    chunk: 0,
                     content: "content 1".to_string(),
                     embedding: vec![],
                     last_modified: modified,
+                    title: String::new(),
+                    tags: vec![],
                 },
                 Document {
                     id: "2".to_string(),
                     path: knowledge_dir.join("doc2_missing.txt").display().to_string(),
+                    source: String::new(),
+                    chunk: 0,
                     content: "content 2".to_string(),
                     embedding: vec![],
                     last_modified: 12345,
+                    title: String::new(),
+                    tags: vec![],
                 }
-            ]
+            ],
+            ..Default::default()
         };
         
-        let json = serde_json::to_string(&index)?;
-        fs::write(&index_path, json)?;
+        index.save(&index_path)?;
 
         // Setup RagSystem
         let mut config = Config::default();
@@ -447,8 +1216,8 @@ mod tests {
         assert_eq!(reused_2.len(), 0, "Should reuse 0 documents (doc1 changed)");
         // doc1 should be in to_embed
         assert_eq!(to_embed_2.len(), 1, "Should have 1 doc to embed");
-        assert_eq!(to_embed_2[0].0, doc1_path.display().to_string());
-        assert_eq!(to_embed_2[0].1, "content 1 modified");
+        assert_eq!(to_embed_2[0].path, doc1_path.display().to_string());
+        assert_eq!(to_embed_2[0].content, "content 1 modified");
 
 
         // TEST 3: Add new file
@@ -476,6 +1245,250 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_rag_dedup_and_move() -> Result<()> {
+        let temp_dir = std::env::temp_dir().join(format!("shadow_prompt_test_dedup_{}", uuid::Uuid::new_v4()));
+        let knowledge_dir = temp_dir.join("knowledge");
+        let index_path = temp_dir.join("index.bin");
+        fs::create_dir_all(&knowledge_dir)?;
+
+        fs::write(knowledge_dir.join("a.txt"), "same text")?;
+        fs::write(knowledge_dir.join("b.txt"), "same text")?;
+        fs::write(knowledge_dir.join("moved.txt"), "old content")?;
+        let old_path = knowledge_dir.join("old.txt").display().to_string();
+
+        let index = RagIndex {
+            documents: vec![Document {
+                id: "1".to_string(),
+                path: old_path.clone(),
+                source: "old.txt".to_string(),
+                chunk: 1,
+                content: "old content".to_string(),
+                embedding: vec![],
+                last_modified: 12345,
+                title: String::new(),
+                tags: vec![],
+            }],
+            ..Default::default()
+        };
+        index.save(&index_path)?;
+
+        let mut config = Config::default();
+        config.rag.enabled = true;
+        let rag = RagSystem {
+            embedding_model: None,
+            config,
+            cached_index: tokio::sync::RwLock::new(None),
+            is_operational: true,
+            init_error: None,
+        };
+
+        let (reused, to_embed) = rag.get_files_to_embed(&knowledge_dir, &index_path)?;
+        assert_eq!(to_embed.len(), 1, "identical copies are embedded once");
+        assert_eq!(reused.len(), 1, "the renamed file keeps its chunk");
+        assert_eq!(reused[0].source, "moved.txt");
+
+        let previous = std::collections::HashSet::from([old_path.clone()]);
+        let summary = IngestSummary::new(&previous, &reused, &to_embed);
+        assert_eq!(summary, IngestSummary { added: 2, updated: 0, removed: vec![old_path] });
+        assert_eq!(summary.to_string(), "2 added, 0 updated, 1 removed");
+
+        let _ = fs::remove_dir_all(temp_dir);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rag_html_and_url_list() -> Result<()> {
+        let temp_dir = std::env::temp_dir().join(format!("shadow_prompt_test_web_{}", uuid::Uuid::new_v4()));
+        let knowledge_dir = temp_dir.join("knowledge");
+        let index_path = temp_dir.join("index.bin");
+        fs::create_dir_all(&knowledge_dir)?;
+
+        fs::write(knowledge_dir.join("page.html"), "<html><body><nav>Menu</nav><p>Photosynthesis</p></body></html>")?;
+        fs::write(knowledge_dir.join(web::URL_LIST_FILE), "https://example.com/a\n")?;
+
+        let mut config = Config::default();
+        config.rag.enabled = true;
+        let rag = RagSystem {
+            embedding_model: None,
+            config,
+            cached_index: tokio::sync::RwLock::new(None),
+            is_operational: true,
+            init_error: None,
+        };
+
+        // urls.txt is a page list, not a document; .html files are embedded as plain text
+        let (_, to_embed) = rag.get_files_to_embed(&knowledge_dir, &index_path)?;
+        assert_eq!(to_embed.len(), 1);
+        assert_eq!(to_embed[0].content, "Photosynthesis");
+
+        // A page fetched within the TTL is reused without touching the network
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+        let index = RagIndex {
+            documents: vec![Document {
+                id: "u".to_string(),
+                path: "https://example.com/a".to_string(),
+                source: "https://example.com/a".to_string(),
+                chunk: 1,
+                content: "cached page".to_string(),
+                embedding: vec![],
+                last_modified: now - 60,
+                title: String::new(),
+                tags: vec![],
+            }],
+            ..Default::default()
+        };
+        index.save(&index_path)?;
+
+        let (reused, to_embed) = rag.get_urls_to_embed(&knowledge_dir, &index_path).await?;
+        assert_eq!(reused.len(), 1);
+        assert!(to_embed.is_empty());
+
+        let _ = fs::remove_dir_all(temp_dir);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunk_citations() {
+        let root = std::path::Path::new("knowledge");
+        let path = root.join("notes").join("week3.md").display().to_string();
+        assert_eq!(source_name(root, &path), "notes/week3.md");
+        assert_eq!(source_name(root, "https://example.com/a"), "https://example.com/a");
+
+        let long = "Paragraph about cells.\n\n".repeat(200);
+        let chunks = chunk_text(&path, &long);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= CHUNK_CHARS));
+
+        let doc = Document {
+            id: "1".to_string(),
+            path: path.clone(),
+            source: "notes/week3.md".to_string(),
+            chunk: 2,
+            content: String::new(),
+            embedding: vec![],
+            last_modified: 0,
+            title: String::new(),
+            tags: vec![],
+        };
+        assert_eq!(doc.citation(), "[notes/week3.md §2]");
+        // Indexes from before chunking have neither field
+        let legacy = Document { source: String::new(), chunk: 0, ..doc };
+        assert_eq!(legacy.citation(), "[week3.md]");
+    }
+
+    #[test]
+    fn test_chunk_rows() {
+        let row = |i: usize| format!("Element: E{} | Symbol: {}", i, "x".repeat(40));
+        let table: Vec<String> = (0..100).map(row).collect();
+        let content = table.join("\n");
+
+        let chunks = chunk_text("data/elements.csv", &content);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= CHUNK_CHARS));
+        // Every row survives intact, in order
+        let rows: Vec<&str> = chunks.iter().flat_map(|c| c.lines()).collect();
+        assert_eq!(rows, table.iter().map(String::as_str).collect::<Vec<_>>());
+
+        let long = "Notes: ".to_string() + &"y".repeat(CHUNK_CHARS + 10);
+        assert_eq!(chunk_rows(&format!("{}\n", long)), vec![long.as_str()]);
+    }
+
+    #[test]
+    fn test_collections() {
+        let doc = |source: &str| Document {
+            id: String::new(),
+            path: String::new(),
+            source: source.to_string(),
+            chunk: 1,
+            content: String::new(),
+            embedding: vec![],
+            last_modified: 0,
+            title: String::new(),
+            tags: vec![],
+        };
+        let index = RagIndex {
+            documents: vec![doc("biology/cells.md"), doc("biology/dna/replication.md"), doc("history/ww2.md"), doc("misc.md"), doc("https://example.com/a/b")],
+            ..Default::default()
+        };
+        assert_eq!(index.collections(), vec!["biology", "history"]);
+
+        let collections = index.collections();
+        assert_eq!(resolve_collection(&collections, "bio").unwrap(), "biology");
+        assert_eq!(resolve_collection(&collections, "History").unwrap(), "history");
+        assert!(resolve_collection(&collections, "chem").is_err());
+    }
+
+    #[test]
+    fn test_tag_filters() {
+        let doc = |path: &str, tags: &[&str]| Document {
+            id: String::new(),
+            path: path.to_string(),
+            source: path.to_string(),
+            chunk: 1,
+            content: String::new(),
+            title: String::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            embedding: vec![],
+            last_modified: 0,
+        };
+        let index = RagIndex {
+            documents: vec![doc("bio/cells.md", &["Exam", "cells"]), doc("bio/cells.md", &["Exam", "cells"]), doc("bio/dna.md", &["exam"]), doc("notes.md", &[])],
+            ..Default::default()
+        };
+        assert_eq!(index.tags(), vec![("exam".to_string(), 2), ("cells".to_string(), 1)]);
+
+        let filter = |collection: Option<&str>, tags: &[&str]| QueryFilter {
+            collection: collection.map(str::to_string),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        };
+        let matching = |f: &QueryFilter| {
+            let resolved = index.resolve(f);
+            index.documents.iter().filter(|d| d.matches(&resolved)).count()
+        };
+        assert_eq!(matching(&filter(None, &[])), 4);
+        assert_eq!(matching(&filter(None, &["EXAM"])), 3);
+        assert_eq!(matching(&filter(Some("bi"), &["exam", "cells"])), 2);
+        // Unknown tags are dropped rather than failing the query
+        assert_eq!(matching(&filter(None, &["chemistry"])), 4);
+        assert_eq!(matching(&filter(Some("chem"), &[])), 4);
+    }
+
+    #[test]
+    fn test_json_index_migration() -> Result<()> {
+        let temp_dir = std::env::temp_dir().join(format!("shadow_prompt_test_migrate_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&temp_dir)?;
+        let index_path = temp_dir.join("index.bin");
+        let json_path = temp_dir.join("index.json");
+
+        // Oldest layout: pretty JSON with inline embeddings and no chunk fields
+        fs::write(&json_path, r#"{
+            "documents": [
+                {"id": "1", "path": "notes.md", "content": "cells", "embedding": [1.0, 0.0], "last_modified": 7},
+                {"id": "2", "path": "dna.md", "content": "dna", "embedding": [0.0, 1.0], "last_modified": 8}
+            ]
+        }"#)?;
+
+        migrate_json_index(&index_path)?;
+        assert!(!json_path.exists(), "JSON index should be removed after migration");
+
+        let index = RagIndex::load(&index_path)?;
+        assert_eq!(index.documents.len(), 2);
+        assert_eq!(index.dimensions, 2);
+        assert_eq!(index.documents[1].content, "dna");
+        let store = index.store.as_ref().expect("vectors should be mapped");
+        assert_eq!(store.search(&[0.0, 1.0], 1, None)[0].1, 1);
+
+        // Anything that isn't our header is rejected rather than misparsed
+        fs::write(&index_path, b"{}")?;
+        assert!(RagIndex::read(&index_path).is_err());
+
+        drop(index);
+        let _ = fs::remove_dir_all(temp_dir);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_rag_operational_flag() {
         // Create a dummy config
@@ -491,7 +1504,7 @@ mod tests {
         };
 
         // Test Query
-        let result = rag.query("test").await;
+        let result = rag.query("test", &QueryFilter::default()).await;
         assert!(result.is_ok());
         let results = result.unwrap();
         assert!(results.is_empty());
@@ -509,7 +1522,7 @@ mod tests {
         let temp_dir = std::env::temp_dir().join(format!("shadow_prompt_test_corrupt_{}", uuid::Uuid::new_v4()));
         let knowledge_dir = temp_dir.join("knowledge");
         let data_dir = temp_dir.join("data");
-        let index_path = data_dir.join("index.json");
+        let index_path = data_dir.join("index.bin");
 
         fs::create_dir_all(&knowledge_dir)?;
         fs::create_dir_all(&data_dir)?;
@@ -521,10 +1534,12 @@ mod tests {
             f.write_all(b"content 1")?;
         }
 
-        // 2. Write a corrupted index.json
+        // 2. Write a corrupted index.bin (valid header, truncated body)
         {
             let mut f = fs::File::create(&index_path)?;
-            f.write_all(b"{ \"documents\": [ ")?; // Invalid JSON
+            f.write_all(INDEX_MAGIC)?;
+            f.write_all(&INDEX_VERSION.to_le_bytes())?;
+            f.write_all(b"\x05\x00")?;
         }
 
         // Setup RagSystem
@@ -552,7 +1567,7 @@ mod tests {
         // Should ignore corrupt index (treat as empty), see file on disk, and mark it for embedding
         assert_eq!(reused.len(), 0, "Should have 0 reused docs (index corrupted)");
         assert_eq!(to_embed.len(), 1, "Should have 1 doc to embed (fresh scan)");
-        assert_eq!(to_embed[0].0, doc1_path.display().to_string());
+        assert_eq!(to_embed[0].path, doc1_path.display().to_string());
 
 
         // TEST 2: Call ingest (graceful handling verification)
@@ -562,7 +1577,7 @@ mod tests {
 
 
         // TEST 3: Call query (graceful handling verification)
-        let query_result = rag.query("test").await;
+        let query_result = rag.query("test", &QueryFilter::default()).await;
         assert!(query_result.is_ok());
         assert!(query_result.unwrap().is_empty());
 
@@ -571,4 +1586,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_write_capture() -> Result<()> {
+        let knowledge_dir = std::env::temp_dir().join(format!("shadow_prompt_test_capture_{}", uuid::Uuid::new_v4()));
+        let mut config = Config::default();
+        config.rag.knowledge_path = knowledge_dir.to_string_lossy().to_string();
+
+        assert!(write_capture(&config, "  \n ").is_err());
+        let first = write_capture(&config, "  Mitochondria make ATP.\n")?;
+        let second = write_capture(&config, "Ribosomes make proteins.")?;
+        assert_ne!(first, second, "captures in the same second must not overwrite each other");
+
+        let content = fs::read_to_string(&first)?;
+        assert!(content.starts_with("# Captured "));
+        assert!(content.ends_with("\n\nMitochondria make ATP.\n"));
+        let source = source_name(&knowledge_dir, &first.display().to_string());
+        assert!(source.starts_with("captured/") && source.ends_with(".md"), "{}", source);
+
+        let _ = fs::remove_dir_all(knowledge_dir);
+        Ok(())
+    }
 }
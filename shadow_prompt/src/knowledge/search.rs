@@ -1,35 +1,135 @@
 use anyhow::{Result, Context};
 use reqwest::{Client, header};
 use regex::Regex;
+use std::time::Duration;
+use tokio::task::JoinSet;
 use crate::config::SearchConfig;
+use super::web;
 
-pub async fn perform_search(query: &str, config: &SearchConfig) -> Result<String> {
-    log::info!("[Search] Query: '{}', Engine: {}, Max Results: {}", query, config.engine, config.max_results);
-    
-    // Try Serper first if configured
-    if config.engine == "serper" {
-        log::info!("[Search] Attempting Serper.dev...");
-        match perform_serper_search(query, config.max_results, &config.serper_api_key).await {
-            Ok(results) if !results.is_empty() => {
-                log::info!("[Search] Serper returned {} results", results.lines().count() / 2);
-                return Ok(results);
-            }
-            Ok(_) => {
-                log::warn!("[Search] Serper returned empty results, falling back to DuckDuckGo...");
+/// `fetch_pages` is capped here; every page adds a round trip before the answer
+const MAX_FETCH_PAGES: usize = 2;
+const PAGE_TIMEOUT_SECS: u64 = 8;
+
+/// Paragraphs shorter than this are usually menus, bylines or captions
+const MIN_PARAGRAPH_CHARS: usize = 40;
+
+/// One result from any engine (or paper, see `academic`)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchHit {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+    /// Relevant part of the page itself, with `fetch_pages`
+    pub page: Option<String>,
+}
+
+/// `[search] recency`: only return results from the last day/week/month/year
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Recency {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Recency {
+    fn from_config(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "any" => None,
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            "year" => Some(Self::Year),
+            other => {
+                log::warn!("[Search] Unknown recency '{}' (use day, week, month or year); not filtering", other);
+                None
             }
-            Err(e) => {
-                log::error!("[Search] Serper failed with error: {}. Falling back to DuckDuckGo...", e);
+        }
+    }
+
+    /// Google's `tbs` value, also used by DuckDuckGo's `df` (d/w/m/y)
+    fn letter(self) -> &'static str {
+        match self {
+            Self::Day => "d",
+            Self::Week => "w",
+            Self::Month => "m",
+            Self::Year => "y",
+        }
+    }
+
+    /// Bing has Day/Week/Month; a year is given as a date range
+    fn bing_freshness(self) -> String {
+        match self {
+            Self::Day => "Day".to_string(),
+            Self::Week => "Week".to_string(),
+            Self::Month => "Month".to_string(),
+            Self::Year => {
+                let today = chrono::Local::now().date_naive();
+                format!("{}..{}", today - chrono::Duration::days(365), today)
             }
         }
-    } else {
-        log::info!("[Search] Using DuckDuckGo (engine config: {})", config.engine);
+    }
+
+    fn searxng_time_range(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+        }
+    }
+}
+
+pub async fn perform_search(query: &str, config: &SearchConfig) -> Result<Vec<SearchHit>> {
+    let mut hits = search_hits(query, config).await?;
+    if config.fetch_pages > 0 && !hits.is_empty() {
+        fetch_pages(query, &mut hits, config).await;
+    }
+    Ok(hits)
+}
+
+async fn search_hits(query: &str, config: &SearchConfig) -> Result<Vec<SearchHit>> {
+    log::info!("[Search] Query: '{}', Engine: {}, Max Results: {}", crate::logger::content(query), config.engine, config.max_results);
+    let recency = Recency::from_config(&config.recency);
+    
+    // Try the configured API engine first
+    let primary = match config.engine.as_str() {
+        "serper" => {
+            log::info!("[Search] Attempting Serper.dev...");
+            Some(("Serper", perform_serper_search(query, config.max_results, &config.serper_api_key, recency).await))
+        }
+        "bing" => {
+            log::info!("[Search] Attempting Bing Web Search...");
+            Some(("Bing", perform_bing_search(query, config.max_results, &config.bing_api_key, recency).await))
+        }
+        "searxng" => {
+            log::info!("[Search] Attempting SearXNG at {}...", config.searxng_url);
+            Some(("SearXNG", perform_searxng_search(query, config.max_results, &config.searxng_url, recency).await))
+        }
+        _ => None,
+    };
+
+    match primary {
+        Some((name, Ok(results))) if !results.is_empty() => {
+            log::info!("[Search] {} returned {} results", name, results.len());
+            return Ok(results);
+        }
+        Some((name, Ok(_))) => {
+            log::warn!("[Search] {} returned empty results, falling back to DuckDuckGo...", name);
+        }
+        Some((name, Err(e))) => {
+            log::error!("[Search] {} failed with error: {}. Falling back to DuckDuckGo...", name, e);
+        }
+        None => {
+            log::info!("[Search] Using DuckDuckGo (engine config: {})", config.engine);
+        }
     }
     
     // Fallback to DuckDuckGo
     log::info!("[Search] Attempting DuckDuckGo...");
-    match perform_duckduckgo_search(query, config.max_results).await {
+    match perform_duckduckgo_search(query, config.max_results, recency).await {
         Ok(results) => {
-            log::info!("[Search] DuckDuckGo returned {} results", results.lines().count() / 2);
+            log::info!("[Search] DuckDuckGo returned {} results", results.len());
             Ok(results)
         }
         Err(e) => {
@@ -39,18 +139,23 @@ pub async fn perform_search(query: &str, config: &SearchConfig) -> Result<String
     }
 }
 
-async fn perform_serper_search(query: &str, max_results: usize, api_key: &Option<String>) -> Result<String> {
+async fn perform_serper_search(query: &str, max_results: usize, api_key: &Option<String>, recency: Option<Recency>) -> Result<Vec<SearchHit>> {
     let api_key = api_key.as_ref().context("Serper API key not configured. Please add serper_api_key in config.toml")?;
     
     log::debug!("[Search] Serper API key present, making request...");
     
+    let mut body = serde_json::json!({
+        "q": query,
+        "num": max_results
+    });
+    if let Some(recency) = recency {
+        body["tbs"] = format!("qdr:{}", recency.letter()).into();
+    }
+
     let client = Client::new();
     let res = client.post("https://google.serper.dev/search")
         .header("X-API-KEY", api_key)
-        .json(&serde_json::json!({
-            "q": query,
-            "num": max_results
-        }))
+        .json(&body)
         .send()
         .await
         .map_err(|e| anyhow::anyhow!("Serper network error: {}. Check your internet connection.", e))?;
@@ -66,38 +171,227 @@ async fn perform_serper_search(query: &str, max_results: usize, api_key: &Option
         } else {
             "Serper API error"
         };
-        return Err(anyhow::anyhow!("{} (status: {}): {}", err_msg, status, &body[..body.len().min(200)]));
+        return Err(anyhow::anyhow!("{} (status: {}): {}", err_msg, status, error_excerpt(&body, 200)));
     }
     
     let json: serde_json::Value = serde_json::from_str(&body)
-        .map_err(|e| anyhow::anyhow!("Serper returned invalid JSON: {}. Response: {}", e, &body[..body.len().min(500)]))?;
+        .map_err(|e| anyhow::anyhow!("Serper returned invalid JSON: {}. Response: {}", e, error_excerpt(&body, 500)))?;
     
     let organic = json["organic"].as_array()
         .context("Serper response missing 'organic' results array")?;
     
+    Ok(organic.iter().take(max_results).map(|item| hit(item, "title", "link", "snippet")).collect())
+}
+
+async fn perform_bing_search(query: &str, max_results: usize, api_key: &Option<String>, recency: Option<Recency>) -> Result<Vec<SearchHit>> {
+    let api_key = api_key.as_ref().context("Bing API key not configured. Please add bing_api_key in config.toml")?;
+
+    let count = max_results.to_string();
+    let mut params = vec![("q", query.to_string()), ("count", count), ("textDecorations", "false".to_string())];
+    if let Some(recency) = recency {
+        params.push(("freshness", recency.bing_freshness()));
+    }
+
+    let client = Client::new();
+    let res = client.get("https://api.bing.microsoft.com/v7.0/search")
+        .header("Ocp-Apim-Subscription-Key", api_key)
+        .query(&params)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Bing network error: {}. Check your internet connection.", e))?;
+
+    let status = res.status();
+    let body = res.text().await?;
+
+    if !status.is_success() {
+        let err_msg = match status.as_u16() {
+            401 | 403 => "Invalid Bing API key. Check your bing_api_key in config.toml",
+            429 => "Bing API rate limit or quota exceeded",
+            _ => "Bing API error",
+        };
+        return Err(anyhow::anyhow!("{} (status: {}): {}", err_msg, status, error_excerpt(&body, 200)));
+    }
+
+    let json: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|e| anyhow::anyhow!("Bing returned invalid JSON: {}. Response: {}", e, error_excerpt(&body, 500)))?;
+    parse_bing_results(&json, max_results)
+}
+
+fn parse_bing_results(json: &serde_json::Value, max_results: usize) -> Result<Vec<SearchHit>> {
+    // A query with no web hits has no webPages section at all
+    let Some(pages) = json.get("webPages") else {
+        return Ok(Vec::new());
+    };
+    let values = pages["value"].as_array()
+        .context("Bing response missing 'webPages.value' results array")?;
+
+    Ok(values.iter().take(max_results).map(|item| hit(item, "name", "url", "snippet")).collect())
+}
+
+async fn perform_searxng_search(query: &str, max_results: usize, instance_url: &str, recency: Option<Recency>) -> Result<Vec<SearchHit>> {
+    if instance_url.trim().is_empty() {
+        anyhow::bail!("SearXNG instance URL not configured. Please add searxng_url in config.toml");
+    }
+    let url = format!("{}/search", instance_url.trim().trim_end_matches('/'));
+
+    let mut params = vec![("q", query), ("format", "json")];
+    if let Some(recency) = recency {
+        params.push(("time_range", recency.searxng_time_range()));
+    }
+
+    let client = Client::new();
+    let res = client.get(&url)
+        .query(&params)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("SearXNG network error: {}. Is the instance at {} reachable?", e, instance_url))?;
+
+    let status = res.status();
+    let body = res.text().await?;
+
+    if !status.is_success() {
+        // Instances reject format=json with 403 unless it is listed under search.formats in settings.yml
+        let err_msg = if status.as_u16() == 403 {
+            "SearXNG refused the JSON API; enable the json format in the instance's settings.yml"
+        } else {
+            "SearXNG error"
+        };
+        return Err(anyhow::anyhow!("{} (status: {}): {}", err_msg, status, error_excerpt(&body, 200)));
+    }
+
+    let json: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|e| anyhow::anyhow!("SearXNG returned invalid JSON: {}. Response: {}", e, error_excerpt(&body, 500)))?;
+    parse_searxng_results(&json, max_results)
+}
+
+fn parse_searxng_results(json: &serde_json::Value, max_results: usize) -> Result<Vec<SearchHit>> {
+    let results = json["results"].as_array()
+        .context("SearXNG response missing 'results' array")?;
+
+    Ok(results.iter().take(max_results).map(|item| hit(item, "title", "url", "content")).collect())
+}
+
+/// The start of an error response for a message; cut by characters, since a byte cut can
+/// land inside a non-ASCII character and panic
+fn error_excerpt(body: &str, max_chars: usize) -> String {
+    body.chars().take(max_chars).collect()
+}
+
+/// A result object from a JSON API, given each engine's field names
+fn hit(item: &serde_json::Value, title: &str, url: &str, snippet: &str) -> SearchHit {
+    let field = |name: &str| item[name].as_str().unwrap_or("").to_string();
+    SearchHit { title: field(title), url: field(url), snippet: field(snippet), page: None }
+}
+
+/// "- title\n  url\n  snippet" per result, followed by an excerpt of the page when one was
+/// fetched. The URL is kept so answers grounded in search can be checked.
+pub fn format_results(hits: &[SearchHit]) -> String {
     let mut results = String::new();
-    for item in organic.iter().take(max_results) {
-        let title = item["title"].as_str().unwrap_or("");
-        let snippet = item["snippet"].as_str().unwrap_or("");
-        results.push_str(&format!("- {}\n  {}\n", title, snippet));
+    for hit in hits {
+        if !hit.title.is_empty() {
+            results.push_str(&format!("- {}\n", hit.title));
+        }
+        if !hit.url.is_empty() {
+            results.push_str(&format!("  {}\n", hit.url));
+        }
+        let marker = if hit.title.is_empty() && hit.url.is_empty() { "- " } else { "  " };
+        results.push_str(&format!("{}{}\n", marker, hit.snippet));
+        if let Some(page) = &hit.page {
+            results.push_str(&format!("  From the page: {}\n", page));
+        }
     }
-    
-    if results.is_empty() {
-        return Ok("No search results found.".to_string());
+    results
+}
+
+/// Readable text of the top `fetch_pages` results, cut down with `excerpt`. A page that
+/// fails to load just leaves its snippet on its own.
+async fn fetch_pages(query: &str, hits: &mut [SearchHit], config: &SearchConfig) {
+    let client = match Client::builder().timeout(Duration::from_secs(PAGE_TIMEOUT_SECS)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("[Search] Page fetching unavailable: {}", e);
+            return;
+        }
+    };
+
+    let mut tasks = JoinSet::new();
+    for (i, hit) in hits.iter().enumerate().take(config.fetch_pages.min(MAX_FETCH_PAGES)) {
+        if !hit.url.starts_with("http") {
+            continue;
+        }
+        let client = client.clone();
+        let url = hit.url.clone();
+        tasks.spawn(async move { (i, web::fetch_readable(&client, &url).await) });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        let Ok((i, fetched)) = joined else { continue };
+        match fetched {
+            Ok(text) => {
+                let excerpt = excerpt(&text, query, config.page_chars);
+                log::debug!("[Search] Fetched {} ({} chars kept)", hits[i].url, excerpt.chars().count());
+                hits[i].page = Some(excerpt).filter(|e| !e.is_empty());
+            }
+            Err(e) => log::warn!("[Search] Could not fetch {}: {}", hits[i].url, e),
+        }
     }
-    
-    Ok(results)
 }
 
-async fn perform_duckduckgo_search(query: &str, max_results: usize) -> Result<String> {
-    log::debug!("[Search] DuckDuckGo: making request for query: '{}'", query);
+/// The paragraphs sharing the most words with the query, back in page order, within `max_chars`
+fn excerpt(text: &str, query: &str, max_chars: usize) -> String {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 3)
+        .map(str::to_lowercase)
+        .collect();
+    let paragraphs: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|p| p.chars().count() >= MIN_PARAGRAPH_CHARS)
+        .collect();
+
+    let mut ranked: Vec<(usize, usize)> = paragraphs
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let lower = p.to_lowercase();
+            (i, terms.iter().filter(|t| lower.contains(t.as_str())).count())
+        })
+        .collect();
+    // Ties go to the earlier paragraph; the lead of a page usually sums it up
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut chosen = Vec::new();
+    let mut used = 0;
+    for (i, _) in &ranked {
+        let len = paragraphs[*i].chars().count() + 1;
+        if used + len <= max_chars {
+            chosen.push(*i);
+            used += len;
+        }
+    }
+    if chosen.is_empty() {
+        return ranked.first().map_or(String::new(), |(i, _)| {
+            format!("{}...", paragraphs[*i].chars().take(max_chars).collect::<String>())
+        });
+    }
+    chosen.sort_unstable();
+    chosen.iter().map(|&i| paragraphs[i]).collect::<Vec<_>>().join(" ")
+}
+
+async fn perform_duckduckgo_search(query: &str, max_results: usize, recency: Option<Recency>) -> Result<Vec<SearchHit>> {
+    log::debug!("[Search] DuckDuckGo: making request for query: '{}'", crate::logger::content(query));
     
     let client = Client::new();
     let url = "https://html.duckduckgo.com/html/";
+    let mut form = vec![("q", query)];
+    if let Some(recency) = recency {
+        form.push(("df", recency.letter()));
+    }
     
     // DDG requires User-Agent
     let res = client.post(url)
-        .form(&[("q", query)])
+        .form(&form)
         .header(header::USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
         .send()
         .await
@@ -111,37 +405,140 @@ async fn perform_duckduckgo_search(query: &str, max_results: usize) -> Result<St
     let body = res.text().await
         .map_err(|e| anyhow::anyhow!("DuckDuckGo failed to read response: {}", e))?;
 
-    // Regex to extract snippets
+    let results = parse_duckduckgo_results(&body, max_results)?;
+    if results.is_empty() {
+        log::warn!("[Search] DuckDuckGo: No results found for query: '{}'. HTML structure may have changed.", crate::logger::content(query));
+        return Ok(results);
+    }
+
+    log::debug!("[Search] DuckDuckGo: successfully extracted {} results", results.len());
+    Ok(results)
+}
+
+fn parse_duckduckgo_results(body: &str, max_results: usize) -> Result<Vec<SearchHit>> {
+    // Regexes to extract the result link and snippet
+    let re_link = Regex::new(r#"href="([^"]*)"[^>]*>(.*?)</a>"#)
+        .map_err(|e| anyhow::anyhow!("DuckDuckGo: Failed to compile regex: {}", e))?;
     let re_snippet = Regex::new(r#"<a[^>]*class="[^"]*result__snippet[^"]*"[^>]*>(.*?)</a>"#)
         .map_err(|e| anyhow::anyhow!("DuckDuckGo: Failed to compile regex: {}", e))?;
     let re_tags = Regex::new(r"<[^>]*>")
         .map_err(|e| anyhow::anyhow!("DuckDuckGo: Failed to compile regex: {}", e))?;
+    let clean = |html: &str| decode_entities(&re_tags.replace_all(html, "")).trim().to_string();
 
-    let mut results = String::new();
+    let mut results = Vec::new();
 
-    for (count, cap) in re_snippet.captures_iter(&body).enumerate() {
-        if count >= max_results { break; }
-        
-        let raw_snippet = &cap[1];
-        let clean_snippet = re_tags.replace_all(raw_snippet, "").to_string();
-        
-        // Manual decode for basic entities
-        let final_text = clean_snippet
-            .replace("&nbsp;", " ")
-            .replace("&amp;", "&")
-            .replace("&lt;", "<")
-            .replace("&gt;", ">")
-            .replace("&quot;", "\"")
-            .replace("&#39;", "'");
+    // Each result starts at its title link and runs until the next one
+    for block in body.split(r#"class="result__a""#).skip(1) {
+        if results.len() >= max_results { break; }
 
-        results.push_str(&format!("- {}\n", final_text));
+        let Some(snippet) = re_snippet.captures(block) else { continue };
+        let (url, title) = re_link
+            .captures(block)
+            .map_or((String::new(), String::new()), |cap| (duckduckgo_target(&cap[1]), clean(&cap[2])));
+        results.push(SearchHit { title, url, snippet: clean(&snippet[1]), page: None });
     }
+    Ok(results)
+}
 
-    if results.is_empty() {
-        log::warn!("[Search] DuckDuckGo: No results found for query: '{}'. HTML structure may have changed.", query);
-        return Ok("No search results found.".to_string());
+/// DDG links go through a redirect (`//duckduckgo.com/l/?uddg=<url>`); return the real page
+fn duckduckgo_target(href: &str) -> String {
+    let href = decode_entities(href);
+    let absolute = if href.starts_with("//") { format!("https:{}", href) } else { href };
+    url::Url::parse(&absolute)
+        .ok()
+        .and_then(|u| u.query_pairs().find(|(k, _)| k == "uddg").map(|(_, v)| v.into_owned()))
+        .unwrap_or(absolute)
+}
+
+/// Manual decode for basic entities
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bing_results() -> Result<()> {
+        let json = serde_json::json!({
+            "webPages": { "value": [
+                { "name": "Mitochondrion - Wikipedia", "url": "https://en.wikipedia.org/wiki/Mitochondrion", "snippet": "Organelle that makes ATP." },
+                { "name": "Second", "snippet": "Dropped by max_results." }
+            ]}
+        });
+        let hits = parse_bing_results(&json, 1)?;
+        assert_eq!(hits[0].url, "https://en.wikipedia.org/wiki/Mitochondrion");
+        assert_eq!(format_results(&hits), "- Mitochondrion - Wikipedia\n  https://en.wikipedia.org/wiki/Mitochondrion\n  Organelle that makes ATP.\n");
+        assert!(parse_bing_results(&serde_json::json!({ "_type": "SearchResponse" }), 3)?.is_empty());
+        Ok(())
     }
 
-    log::debug!("[Search] DuckDuckGo: successfully extracted {} results", results.lines().count());
-    Ok(results)
+    #[test]
+    fn test_error_excerpt_cuts_by_chars() {
+        let body = "é".repeat(300);
+        assert_eq!(error_excerpt(&body, 200).chars().count(), 200);
+        assert_eq!(error_excerpt("short", 200), "short");
+    }
+
+    #[test]
+    fn test_parse_searxng_results() -> Result<()> {
+        let json = serde_json::json!({
+            "query": "atp",
+            "results": [{ "title": "ATP", "content": "Energy currency of the cell.", "url": "https://example.org/atp", "engine": "wikipedia" }]
+        });
+        assert_eq!(format_results(&parse_searxng_results(&json, 3)?), "- ATP\n  https://example.org/atp\n  Energy currency of the cell.\n");
+        assert!(parse_searxng_results(&serde_json::json!({ "error": "x" }), 3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_recency_from_config() {
+        assert_eq!(Recency::from_config(""), None);
+        assert_eq!(Recency::from_config("Week"), Some(Recency::Week));
+        assert_eq!(Recency::from_config("fortnight"), None);
+        assert_eq!(Recency::Month.letter(), "m");
+        assert_eq!(Recency::Day.bing_freshness(), "Day");
+        assert!(Recency::Year.bing_freshness().contains(".."));
+    }
+
+    #[test]
+    fn test_parse_duckduckgo_results() -> Result<()> {
+        let html = r#"<div class="result"><h2><a rel="nofollow" class="result__a" href="//duckduckgo.com/l/?uddg=https%3A%2F%2Fexample.org%2Fatp&amp;rut=abc">What is <b>ATP</b>?</a></h2>
+            <a class="result__snippet" href="x">ATP stores energy &amp; releases it.</a></div>
+            <div class="result"><a rel="nofollow" class="result__a" href="https://example.com/2">Second</a><a class="result__snippet">Dropped.</a></div>"#;
+        let hits = parse_duckduckgo_results(html, 1)?;
+        assert_eq!(hits, vec![SearchHit {
+            title: "What is ATP?".to_string(),
+            url: "https://example.org/atp".to_string(),
+            snippet: "ATP stores energy & releases it.".to_string(),
+            page: None,
+        }]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_excerpt_keeps_relevant_paragraphs() {
+        let page = "Home | News | Sign in\n\
+                    The Krebs cycle is a series of reactions in the mitochondrial matrix.\n\
+                    Our newsletter brings you science stories every single week, subscribe now.\n\
+                    Each turn of the Krebs cycle produces NADH, FADH2 and one ATP molecule.";
+        assert_eq!(
+            excerpt(page, "What does the Krebs cycle produce?", 160),
+            "The Krebs cycle is a series of reactions in the mitochondrial matrix. Each turn of the Krebs cycle produces NADH, FADH2 and one ATP molecule."
+        );
+        assert_eq!(excerpt(page, "Krebs cycle", 20), "The Krebs cycle is a...");
+
+        let hits = [
+            SearchHit { title: "Krebs".to_string(), snippet: "Citric acid cycle".to_string(), page: Some("Makes NADH.".to_string()), ..Default::default() },
+            SearchHit { snippet: "Untitled snippet".to_string(), ..Default::default() },
+        ];
+        assert_eq!(format_results(&hits), "- Krebs\n  Citric acid cycle\n  From the page: Makes NADH.\n- Untitled snippet\n");
+    }
 }
@@ -0,0 +1,196 @@
+//! Vector Store
+//! Embeddings live next to the index as raw little-endian f32s (`index.vec`), memory-mapped
+//! instead of being parsed out of JSON. Small indexes are scanned directly; from
+//! `HNSW_MIN_DOCS` documents up an HNSW graph is built on load so queries stay sub-linear.
+
+use anyhow::{Context, Result};
+use instant_distance::{Builder, HnswMap, Point, Search};
+use memmap2::Mmap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Below this a linear scan is faster than building the graph
+const HNSW_MIN_DOCS: usize = 512;
+
+/// Approximate search returns this many times `k` candidates before the exact re-rank
+const HNSW_OVERSAMPLE: usize = 4;
+
+pub fn vector_path(index_file_path: &Path) -> PathBuf {
+    index_file_path.with_extension("vec")
+}
+
+/// Write the vectors in document order; returns the dimension
+pub fn write_vectors<'a>(path: &Path, vectors: impl Iterator<Item = &'a [f32]>) -> Result<usize> {
+    let mut writer = BufWriter::new(File::create(path).context("Failed to create vector file")?);
+    let mut dimensions = 0;
+    for vector in vectors {
+        if dimensions == 0 {
+            dimensions = vector.len();
+        } else if vector.len() != dimensions {
+            anyhow::bail!("Embedding dimension changed mid-index ({} vs {})", vector.len(), dimensions);
+        }
+        for value in vector {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+    }
+    writer.flush()?;
+    Ok(dimensions)
+}
+
+/// Read vectors into memory (used when re-ingesting, so the file isn't left mapped)
+pub fn read_vectors(path: &Path, count: usize, dimensions: usize) -> Result<Vec<Vec<f32>>> {
+    let bytes = fs::read(path).context("Failed to read vector file")?;
+    if dimensions == 0 || bytes.len() != count * dimensions * 4 {
+        anyhow::bail!("Vector file does not match the index");
+    }
+    Ok(bytes
+        .chunks_exact(dimensions * 4)
+        .map(|row| row.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+        .collect())
+}
+
+struct Vectors {
+    mmap: Mmap,
+    dimensions: usize,
+}
+
+impl Vectors {
+    fn get(&self, idx: usize) -> &[f32] {
+        let all = self.as_slice();
+        &all[idx * self.dimensions..(idx + 1) * self.dimensions]
+    }
+
+    fn as_slice(&self) -> &[f32] {
+        // SAFETY: the mapping is page-aligned, its length was checked to be a multiple of 4,
+        // and the file is only rewritten after the store has been dropped (see RagSystem::ingest)
+        unsafe { std::slice::from_raw_parts(self.mmap.as_ptr() as *const f32, self.mmap.len() / 4) }
+    }
+}
+
+#[derive(Clone)]
+enum StorePoint {
+    Stored(Arc<Vectors>, usize),
+    Query(Arc<Vec<f32>>),
+}
+
+impl StorePoint {
+    fn as_slice(&self) -> &[f32] {
+        match self {
+            Self::Stored(vectors, idx) => vectors.get(*idx),
+            Self::Query(vector) => vector,
+        }
+    }
+}
+
+impl Point for StorePoint {
+    fn distance(&self, other: &Self) -> f32 {
+        1.0 - cosine_similarity(self.as_slice(), other.as_slice())
+    }
+}
+
+pub struct VectorStore {
+    vectors: Arc<Vectors>,
+    len: usize,
+    hnsw: Option<HnswMap<StorePoint, usize>>,
+}
+
+impl std::fmt::Debug for VectorStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VectorStore")
+            .field("len", &self.len)
+            .field("dimensions", &self.vectors.dimensions)
+            .field("hnsw", &self.hnsw.is_some())
+            .finish()
+    }
+}
+
+impl VectorStore {
+    pub fn open(path: &Path, count: usize, dimensions: usize) -> Result<Self> {
+        let file = File::open(path).context("Failed to open vector file")?;
+        // SAFETY: see Vectors::as_slice
+        let mmap = unsafe { Mmap::map(&file) }.context("Failed to map vector file")?;
+        if dimensions == 0 || mmap.len() != count * dimensions * 4 {
+            anyhow::bail!("Vector file does not match the index");
+        }
+        let vectors = Arc::new(Vectors { mmap, dimensions });
+
+        let hnsw = (count >= HNSW_MIN_DOCS).then(|| {
+            let points = (0..count).map(|i| StorePoint::Stored(vectors.clone(), i)).collect();
+            Builder::default().build(points, (0..count).collect())
+        });
+
+        Ok(Self { vectors, len: count, hnsw })
+    }
+
+    /// Top `k` documents as (cosine score, document index), best first. With a `filter`
+    /// (e.g. one collection) the allowed documents are scanned exactly instead of via HNSW,
+    /// since the graph's nearest neighbours may all be filtered out.
+    pub fn search(&self, query: &[f32], k: usize, filter: Option<&dyn Fn(usize) -> bool>) -> Vec<(f32, usize)> {
+        if query.len() != self.vectors.dimensions {
+            return vec![];
+        }
+
+        let candidates: Vec<usize> = match (&self.hnsw, filter) {
+            (_, Some(filter)) => (0..self.len).filter(|&i| filter(i)).collect(),
+            (Some(hnsw), None) => {
+                let point = StorePoint::Query(Arc::new(query.to_vec()));
+                let mut search = Search::default();
+                hnsw.search(&point, &mut search)
+                    .take(k * HNSW_OVERSAMPLE)
+                    .map(|item| *item.value)
+                    .collect()
+            }
+            (None, None) => (0..self.len).collect(),
+        };
+
+        let mut scores: Vec<(f32, usize)> = candidates
+            .into_iter()
+            .map(|i| (cosine_similarity(query, self.vectors.get(i)), i))
+            .collect();
+        scores.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scores.truncate(k);
+        scores
+    }
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot_product: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    dot_product / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_round_trip_and_search() -> Result<()> {
+        let path = std::env::temp_dir().join(format!("shadow_prompt_vec_{}.vec", uuid::Uuid::new_v4()));
+        let vectors = [vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0], vec![0.7, 0.7, 0.0]];
+
+        let dimensions = write_vectors(&path, vectors.iter().map(|v| v.as_slice()))?;
+        assert_eq!(dimensions, 3);
+        assert_eq!(read_vectors(&path, 3, 3)?, vectors.to_vec());
+        assert!(VectorStore::open(&path, 4, 3).is_err(), "count mismatch must be rejected");
+
+        {
+            let store = VectorStore::open(&path, 3, 3)?;
+            let results = store.search(&[1.0, 0.1, 0.0], 2, None);
+            assert_eq!(results.iter().map(|r| r.1).collect::<Vec<_>>(), vec![0, 2]);
+            let only_odd = |i: usize| i % 2 == 1;
+            assert_eq!(store.search(&[1.0, 0.1, 0.0], 2, Some(&only_odd))[0].1, 1);
+            assert!(store.search(&[1.0, 0.0], 2, None).is_empty(), "wrong dimension");
+        }
+
+        let _ = fs::remove_file(path);
+        Ok(())
+    }
+}
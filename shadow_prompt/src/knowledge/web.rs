@@ -0,0 +1,110 @@
+//! Web Pages for RAG
+//! `knowledge/urls.txt` lists pages (one URL per line, `#` comments) that are fetched,
+//! reduced to readable text, and embedded like local files. They are re-fetched once
+//! older than `[rag] url_refresh_hours`.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use reqwest::{header, Client};
+use std::sync::OnceLock;
+use std::time::Duration;
+use crate::clipboard::html_to_text;
+use crate::config::Config;
+
+pub const URL_LIST_FILE: &str = "urls.txt";
+
+const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0 Safari/537.36";
+
+pub fn parse_url_list(content: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !(line.starts_with("http://") || line.starts_with("https://")) {
+            eprintln!("[RAG] Skipping '{}' in {} (not an http(s) URL)", line, URL_LIST_FILE);
+            continue;
+        }
+        if !urls.iter().any(|u| u == line) {
+            urls.push(line.to_string());
+        }
+    }
+    urls
+}
+
+pub fn client(config: &Config) -> Result<Client> {
+    Ok(Client::builder()
+        .connect_timeout(Duration::from_secs(config.http.connect_timeout_secs))
+        .timeout(Duration::from_secs(config.http.read_timeout_secs))
+        .build()?)
+}
+
+pub async fn fetch_readable(client: &Client, url: &str) -> Result<String> {
+    let res = client.get(url)
+        .header(header::USER_AGENT, USER_AGENT)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch {}", url))?;
+
+    let status = res.status();
+    if !status.is_success() {
+        anyhow::bail!("{} returned {}", url, status);
+    }
+
+    let is_html = res.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map_or(true, |ct| ct.contains("html"));
+    let body = res.text().await?;
+
+    Ok(if is_html { readable_text(&body) } else { body.trim().to_string() })
+}
+
+/// Page text without navigation chrome: the <article>/<main> element when there is one, else <body>
+pub fn readable_text(html: &str) -> String {
+    static CHROME: OnceLock<Regex> = OnceLock::new();
+    let chrome = CHROME.get_or_init(|| {
+        Regex::new(r"(?is)<(nav|header|footer|aside|form|noscript)\b.*?</(nav|header|footer|aside|form|noscript)\s*>").unwrap()
+    });
+
+    let main = ["article", "main", "body"]
+        .iter()
+        .find_map(|tag| element_inner(html, tag))
+        .unwrap_or(html);
+    html_to_text(&chrome.replace_all(main, ""))
+}
+
+fn element_inner<'a>(html: &'a str, tag: &str) -> Option<&'a str> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find(&format!("<{}", tag))?;
+    // Make sure we matched the tag itself and not e.g. <mainframe>
+    let after = lower[open + tag.len() + 1..].chars().next()?;
+    if !(after == '>' || after.is_whitespace()) {
+        return None;
+    }
+    let start = open + lower[open..].find('>')? + 1;
+    let end = lower.rfind(&format!("</{}", tag)).filter(|&e| e >= start).unwrap_or(html.len());
+    Some(&html[start..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url_list() {
+        let list = "# Biology notes\nhttps://example.com/cells\n\n  https://example.com/dna  \nexample.com/no-scheme\nhttps://example.com/cells\n";
+        assert_eq!(parse_url_list(list), vec!["https://example.com/cells", "https://example.com/dna"]);
+    }
+
+    #[test]
+    fn test_readable_text_prefers_main_content() {
+        let html = "<html><head><title>T</title></head><body><nav><a>Home</a> <a>About</a></nav>\
+                    <main><h1>Mitosis</h1><p>Cells divide &amp; copy DNA.</p></main>\
+                    <footer>Copyright</footer></body></html>";
+        assert_eq!(readable_text(html), "Mitosis\nCells divide & copy DNA.");
+
+        let plain = "<body><header>Site</header><p>Only body</p></body>";
+        assert_eq!(readable_text(plain), "Only body");
+    }
+}
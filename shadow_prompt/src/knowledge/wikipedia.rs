@@ -0,0 +1,149 @@
+//! Wikipedia Lookup
+//! With `[search] wikipedia = true`, names and quoted terms in the question are resolved
+//! to articles (opensearch) and their lead sections (TextExtracts) are added as context.
+//! Questions without an obvious entity fall back to one full-text search hit.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use reqwest::Client;
+use std::sync::OnceLock;
+use crate::config::SearchConfig;
+
+/// Entities looked up per question
+const MAX_ARTICLES: usize = 2;
+
+/// Lead sections are cut to roughly this many characters
+const MAX_EXTRACT_CHARS: usize = 1200;
+
+/// Capitalised words that start questions or label options rather than name things
+const NOT_ENTITIES: &[&str] = &[
+    "A", "An", "The", "What", "Who", "Whom", "Whose", "Which", "When", "Where", "Why", "How",
+    "Is", "Are", "Was", "Were", "Do", "Does", "Did", "Can", "In", "On", "Of", "For", "Name",
+    "Explain", "Describe", "Define", "True", "False", "None", "All", "Both", "I", "B", "C", "D",
+];
+
+pub async fn lookup(question: &str, config: &SearchConfig) -> Result<String> {
+    let client = Client::builder()
+        // Wikimedia asks API clients to identify themselves
+        .user_agent(concat!("ShadowPrompt/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let api = format!("https://{}.wikipedia.org/w/api.php", config.wikipedia_lang);
+
+    let mut titles = Vec::new();
+    for entity in detect_entities(question).into_iter().take(MAX_ARTICLES) {
+        if let Some(title) = opensearch(&client, &api, &entity).await? {
+            if !titles.contains(&title) {
+                titles.push(title);
+            }
+        }
+    }
+    if titles.is_empty() {
+        titles.extend(full_text_search(&client, &api, question).await?);
+    }
+    if titles.is_empty() {
+        return Ok(String::new());
+    }
+
+    let res = client.get(&api)
+        .query(&[
+            ("action", "query"), ("prop", "extracts"), ("exintro", "1"), ("explaintext", "1"),
+            ("redirects", "1"), ("format", "json"), ("titles", titles.join("|").as_str()),
+        ])
+        .send()
+        .await
+        .context("Wikipedia network error")?;
+    let json: serde_json::Value = res.error_for_status()?.json().await?;
+    Ok(format_extracts(&json))
+}
+
+async fn opensearch(client: &Client, api: &str, term: &str) -> Result<Option<String>> {
+    let res = client.get(api)
+        .query(&[("action", "opensearch"), ("search", term), ("limit", "1"), ("namespace", "0"), ("format", "json")])
+        .send()
+        .await
+        .context("Wikipedia network error")?;
+    // [term, [titles], [descriptions], [urls]]
+    let json: serde_json::Value = res.error_for_status()?.json().await?;
+    Ok(json[1][0].as_str().map(str::to_string))
+}
+
+async fn full_text_search(client: &Client, api: &str, question: &str) -> Result<Option<String>> {
+    let res = client.get(api)
+        .query(&[("action", "query"), ("list", "search"), ("srsearch", question), ("srlimit", "1"), ("format", "json")])
+        .send()
+        .await
+        .context("Wikipedia network error")?;
+    let json: serde_json::Value = res.error_for_status()?.json().await?;
+    Ok(json["query"]["search"][0]["title"].as_str().map(str::to_string))
+}
+
+/// Quoted terms first, then runs of capitalised words ("Marie Curie", "Treaty of Versailles")
+fn detect_entities(question: &str) -> Vec<String> {
+    static QUOTED: OnceLock<Regex> = OnceLock::new();
+    static NAME: OnceLock<Regex> = OnceLock::new();
+    let quoted = QUOTED.get_or_init(|| Regex::new(r#""([^"]{3,60})"|“([^”]{3,60})”"#).unwrap());
+    let name = NAME.get_or_init(|| {
+        Regex::new(r"\b\p{Lu}[\p{L}\p{N}'-]*(?:\s+(?:of|the|de|von|and)?\s*\p{Lu}[\p{L}\p{N}'-]*)*").unwrap()
+    });
+
+    let mut entities: Vec<String> = quoted
+        .captures_iter(question)
+        .filter_map(|c| c.get(1).or_else(|| c.get(2)).map(|m| m.as_str().trim().to_string()))
+        .collect();
+
+    for found in name.find_iter(question) {
+        // Drop question words and option letters around the name: "When Napoleon" -> "Napoleon"
+        let mut words: Vec<&str> = found.as_str().split_whitespace().skip_while(|w| NOT_ENTITIES.contains(w)).collect();
+        while words.last().is_some_and(|w| NOT_ENTITIES.contains(w)) {
+            words.pop();
+        }
+        let entity = words.join(" ");
+        let lower = entity.to_lowercase();
+        if entity.chars().count() >= 3 && !entities.iter().any(|e| e.to_lowercase().contains(&lower)) {
+            entities.push(entity);
+        }
+    }
+    entities
+}
+
+/// "- Title: lead section" per article, in the order the API returned them
+fn format_extracts(json: &serde_json::Value) -> String {
+    let Some(pages) = json["query"]["pages"].as_object() else {
+        return String::new();
+    };
+    let mut results = String::new();
+    for page in pages.values() {
+        let title = page["title"].as_str().unwrap_or("");
+        let extract = page["extract"].as_str().unwrap_or("").trim();
+        if extract.is_empty() {
+            continue;
+        }
+        let trimmed: String = extract.chars().take(MAX_EXTRACT_CHARS).collect();
+        let ellipsis = if trimmed.len() < extract.len() { "..." } else { "" };
+        results.push_str(&format!("- {}: {}{}\n", title, trimmed.replace('\n', " "), ellipsis));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_entities() {
+        assert_eq!(detect_entities("Who discovered Penicillin?"), vec!["Penicillin"]);
+        assert_eq!(detect_entities("When was the Treaty of Versailles signed?"), vec!["Treaty of Versailles"]);
+        assert_eq!(detect_entities("What does \"mitochondrial DNA\" encode?"), vec!["mitochondrial DNA"]);
+        assert_eq!(detect_entities("Which element did Marie Curie discover? A. Radium B. Helium"), vec!["Marie Curie", "Radium", "Helium"]);
+        assert!(detect_entities("what is the powerhouse of the cell?").is_empty());
+    }
+
+    #[test]
+    fn test_format_extracts() {
+        let json = serde_json::json!({ "query": { "pages": {
+            "123": { "pageid": 123, "title": "Mitochondrion", "extract": "A mitochondrion is an organelle.\nIt makes ATP." },
+            "-1": { "title": "Missing", "missing": "" }
+        }}});
+        assert_eq!(format_extracts(&json), "- Mitochondrion: A mitochondrion is an organelle. It makes ATP.\n");
+    }
+}
@@ -2,7 +2,9 @@ use anyhow::{Result, Context};
 use reqwest::Client;
 use serde_json::{json, Value};
 use crate::config::Config;
-use std::time::Duration;
+use crate::provider_health::{self, Provider};
+use crate::spend;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
 pub struct LlmClient;
@@ -119,49 +121,36 @@ impl LlmClient {
             || error_str.contains("service unavailable")
     }
 
-    /// Auto-LLM Selection with fallback chain: Groq -> OpenRouter -> Ollama
+    /// Auto-LLM Selection with fallback chain: Groq -> OpenRouter -> Ollama by default,
+    /// reordered by session health so a struggling provider is tried later.
     /// Each provider is tried with retry logic before falling back
     async fn query_with_fallback(client: &Client, prompt: &str, config: &Config) -> Result<String> {
-        // Priority 1: Groq (fastest, free tier)
-        if let Some(groq) = &config.models.groq {
-            if !groq.api_key.is_empty() && groq.api_key != "your_groq_api_key_here" {
-                match Self::query_with_retry_groq(client, prompt, config).await {
-                    Ok(res) => return Ok(res),
-                    Err(e) => {
-                        let error_str = e.to_string().to_lowercase();
-                        if Self::is_retryable_error(&error_str) {
-                            log::warn!("Groq failed (retryable): {}. Falling back...", e);
-                        } else {
-                            log::error!("Groq failed: {}. Trying next provider...", e);
-                        }
-                    }
-                }
+        for provider in provider_health::fallback_order() {
+            if !Self::is_configured(provider, config) {
+                continue;
             }
-        }
-        
-        // Priority 2: OpenRouter (wider model selection)
-        if let Some(or) = &config.models.openrouter {
-            if !or.api_key.is_empty() && or.api_key != "your_openrouter_api_key_here" {
-                match Self::query_with_retry_openrouter(client, prompt, config).await {
-                    Ok(res) => return Ok(res),
-                    Err(e) => {
-                        let error_str = e.to_string().to_lowercase();
-                        if Self::is_retryable_error(&error_str) {
-                            log::warn!("OpenRouter failed (retryable): {}. Falling back...", e);
-                        } else {
-                            log::error!("OpenRouter failed: {}. Trying next provider...", e);
-                        }
-                    }
+
+            let started = Instant::now();
+            let result = match provider {
+                Provider::Groq => Self::query_with_retry_groq(client, prompt, config).await,
+                Provider::OpenRouter => Self::query_with_retry_openrouter(client, prompt, config).await,
+                Provider::Ollama => Self::query_with_retry_ollama(client, prompt, config).await,
+            };
+            provider_health::record(provider, result.is_ok(), started.elapsed());
+
+            match result {
+                Ok(res) => {
+                    log::info!("{} answered in {} ms", provider.name(), started.elapsed().as_millis());
+                    return Ok(res);
                 }
-            }
-        }
-        
-        // Priority 3: Ollama (local, no rate limits)
-        if config.models.ollama.is_some() {
-            match Self::query_with_retry_ollama(client, prompt, config).await {
-                Ok(res) => return Ok(res),
                 Err(e) => {
-                    log::error!("Ollama failed: {}", e);
+                    crate::analytics::record(crate::analytics::Event::ProviderFallback);
+                    let error_str = e.to_string().to_lowercase();
+                    if Self::is_retryable_error(&error_str) {
+                        log::warn!("{} failed (retryable): {}. Falling back...", provider.name(), e);
+                    } else {
+                        log::error!("{} failed: {}. Trying next provider...", provider.name(), e);
+                    }
                 }
             }
         }
@@ -169,9 +158,20 @@ impl LlmClient {
         anyhow::bail!("All providers failed. Please check your API keys and network connection.")
     }
 
+    fn is_configured(provider: Provider, config: &Config) -> bool {
+        match provider {
+            Provider::Groq => config.models.groq.as_ref()
+                .is_some_and(|groq| !groq.api_key.is_empty() && groq.api_key != "your_groq_api_key_here"),
+            Provider::OpenRouter => config.models.openrouter.as_ref()
+                .is_some_and(|or| !or.api_key.is_empty() && or.api_key != "your_openrouter_api_key_here"),
+            Provider::Ollama => config.models.ollama.is_some(),
+        }
+    }
+
     async fn query_groq(client: &Client, prompt: &str, config: &Config) -> Result<String> {
         let groq_config = config.models.groq.as_ref()
             .context("Groq config missing")?;
+        spend::check_paid(Provider::Groq, config)?;
 
         let system_prompt = Self::load_system_prompt();
 
@@ -199,6 +199,7 @@ impl LlmClient {
             .as_str()
             .context("Failed to parse Groq response")?
             .to_string();
+        spend::record(Provider::Groq, &json["usage"], config);
 
         Ok(content)
     }
@@ -212,6 +213,7 @@ impl LlmClient {
     async fn query_openrouter(client: &Client, prompt: &str, config: &Config) -> Result<String> {
         let openrouter_config = config.models.openrouter.as_ref()
             .context("OpenRouter config missing")?;
+        spend::check_paid(Provider::OpenRouter, config)?;
 
         let system_prompt = Self::load_system_prompt();
 
@@ -220,7 +222,8 @@ impl LlmClient {
             "messages": [
                 {"role": "system", "content": system_prompt},
                 {"role": "user", "content": prompt}
-            ]
+            ],
+            "usage": {"include": true}
         });
 
         let res = client.post("https://openrouter.ai/api/v1/chat/completions")
@@ -240,6 +243,7 @@ impl LlmClient {
             .as_str()
             .context("Failed to parse LLM response")?
             .to_string();
+        spend::record(Provider::OpenRouter, &json["usage"], config);
 
         Ok(content)
     }
@@ -267,6 +271,7 @@ impl LlmClient {
 
          let json: Value = res.json().await?;
         let response = json["response"].as_str().context("No response field")?.to_string();
+        spend::record(Provider::Ollama, &json, config);
         Ok(response)
     }
 
@@ -290,8 +295,33 @@ impl LlmClient {
         }
     }
 
+    /// Model IDs the provider offers, sorted; `credential` is the API key, or the base URL
+    /// for Ollama
+    pub async fn list_models(provider: &str, credential: &str) -> Result<Vec<String>> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_secs(15))
+            .build()?;
+        let request = match provider {
+            "groq" => client.get("https://api.groq.com/openai/v1/models").bearer_auth(credential.trim()),
+            "openrouter" => client.get("https://openrouter.ai/api/v1/models").bearer_auth(credential.trim()),
+            "ollama" => client.get(format!("{}/api/tags", credential.trim().trim_end_matches('/'))),
+            _ => anyhow::bail!("Unknown provider: {}", provider),
+        };
+        let res = request.send().await?;
+        if !res.status().is_success() {
+            anyhow::bail!("{} model list error ({}): {}", provider, res.status(), res.text().await?);
+        }
+        Ok(parse_model_ids(&res.json().await?))
+    }
+
     /// Query LLM with an image (for vision-capable models)
     pub async fn query_with_image(prompt: &str, image_base64: &str, config: &Config) -> Result<String> {
+        Self::query_with_images(prompt, &[format!("data:image/png;base64,{}", image_base64)], config).await
+    }
+
+    /// Query LLM with several images, each a `data:<mime>;base64,...` URL
+    pub async fn query_with_images(prompt: &str, images: &[String], config: &Config) -> Result<String> {
         let connect_timeout = Duration::from_secs(config.http.connect_timeout_secs);
         let read_timeout = Duration::from_secs(config.http.read_timeout_secs);
         
@@ -301,20 +331,20 @@ impl LlmClient {
             .build()?;
 
         match config.models.provider.as_str() {
-            "groq" => Self::query_groq_with_image(&client, prompt, image_base64, config).await,
-            "openrouter" => Self::query_openrouter_with_image(&client, prompt, image_base64, config).await,
-            "ollama" => Self::query_ollama_with_image(&client, prompt, image_base64, config).await,
+            "groq" => Self::query_groq_with_image(&client, prompt, images, config).await,
+            "openrouter" => Self::query_openrouter_with_image(&client, prompt, images, config).await,
+            "ollama" => Self::query_ollama_with_image(&client, prompt, images, config).await,
             "auto" => {
                 if let Some(groq) = &config.models.groq {
                     if !groq.api_key.is_empty() && groq.api_key != "your_groq_api_key_here" {
-                        if let Ok(res) = Self::query_groq_with_image(&client, prompt, image_base64, config).await {
+                        if let Ok(res) = Self::query_groq_with_image(&client, prompt, images, config).await {
                             return Ok(res);
                         }
                     }
                 }
                 if let Some(or) = &config.models.openrouter {
                     if !or.api_key.is_empty() && or.api_key != "your_openrouter_api_key_here" {
-                        if let Ok(res) = Self::query_openrouter_with_image(&client, prompt, image_base64, config).await {
+                        if let Ok(res) = Self::query_openrouter_with_image(&client, prompt, images, config).await {
                             return Ok(res);
                         }
                     }
@@ -325,9 +355,10 @@ impl LlmClient {
         }
     }
 
-    async fn query_groq_with_image(client: &Client, prompt: &str, image_base64: &str, config: &Config) -> Result<String> {
+    async fn query_groq_with_image(client: &Client, prompt: &str, images: &[String], config: &Config) -> Result<String> {
         let groq_config = config.models.groq.as_ref()
             .context("Groq config missing")?;
+        spend::check_paid(Provider::Groq, config)?;
 
         let system_prompt = "You are a helpful assistant that analyzes images and answers questions about them. Be concise and accurate.";
 
@@ -335,10 +366,7 @@ impl LlmClient {
             "model": groq_config.model_id,
             "messages": [
                 {"role": "system", "content": system_prompt},
-                {"role": "user", "content": [
-                    {"type": "text", "text": prompt},
-                    {"type": "image_url", "image_url": {"url": format!("data:image/png;base64,{}", image_base64)}}
-                ]}
+                {"role": "user", "content": vision_content(prompt, images)}
             ]
         });
 
@@ -358,13 +386,15 @@ impl LlmClient {
             .as_str()
             .context("Failed to parse Groq vision response")?
             .to_string();
+        spend::record(Provider::Groq, &json["usage"], config);
 
         Ok(content)
     }
 
-    async fn query_openrouter_with_image(client: &Client, prompt: &str, image_base64: &str, config: &Config) -> Result<String> {
+    async fn query_openrouter_with_image(client: &Client, prompt: &str, images: &[String], config: &Config) -> Result<String> {
         let openrouter_config = config.models.openrouter.as_ref()
             .context("OpenRouter config missing")?;
+        spend::check_paid(Provider::OpenRouter, config)?;
 
         let system_prompt = "You are a helpful assistant that analyzes images and answers questions about them. Be concise and accurate.";
 
@@ -372,11 +402,9 @@ impl LlmClient {
             "model": openrouter_config.model_id,
             "messages": [
                 {"role": "system", "content": system_prompt},
-                {"role": "user", "content": [
-                    {"type": "text", "text": prompt},
-                    {"type": "image_url", "image_url": {"url": format!("data:image/png;base64,{}", image_base64)}}
-                ]}
-            ]
+                {"role": "user", "content": vision_content(prompt, images)}
+            ],
+            "usage": {"include": true}
         });
 
         let res = client.post("https://openrouter.ai/api/v1/chat/completions")
@@ -395,18 +423,20 @@ impl LlmClient {
             .as_str()
             .context("Failed to parse OpenRouter vision response")?
             .to_string();
+        spend::record(Provider::OpenRouter, &json["usage"], config);
 
         Ok(content)
     }
 
-    async fn query_ollama_with_image(client: &Client, prompt: &str, image_base64: &str, config: &Config) -> Result<String> {
+    async fn query_ollama_with_image(client: &Client, prompt: &str, images: &[String], config: &Config) -> Result<String> {
         let ollama_config = config.models.ollama.as_ref()
             .context("Ollama config missing")?;
 
         let body = json!({
             "model": ollama_config.model_id,
             "prompt": prompt,
-            "images": [image_base64]
+            // Ollama takes bare base64 rather than data URLs
+            "images": images.iter().map(|url| url.split_once(',').map_or(url.as_str(), |(_, data)| data)).collect::<Vec<_>>()
         });
 
         let url = format!("{}/api/generate", ollama_config.base_url);
@@ -421,8 +451,39 @@ impl LlmClient {
 
         let json: Value = res.json().await?;
         let response = json["response"].as_str().context("No response field")?.to_string();
+        spend::record(Provider::Ollama, &json, config);
         Ok(response)
     }
 
+}
+
+/// IDs from an OpenAI-style `{"data": [{"id"}]}` list or Ollama's `{"models": [{"name"}]}`
+fn parse_model_ids(json: &Value) -> Vec<String> {
+    let openai = json["data"].as_array().into_iter().flatten().filter_map(|m| m["id"].as_str());
+    let ollama = json["models"].as_array().into_iter().flatten().filter_map(|m| m["name"].as_str());
+    let mut ids: Vec<String> = openai.chain(ollama).map(str::to_string).collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// OpenAI-style user content: the prompt followed by every image
+fn vision_content(prompt: &str, images: &[String]) -> Value {
+    let mut content = vec![json!({"type": "text", "text": prompt})];
+    content.extend(images.iter().map(|url| json!({"type": "image_url", "image_url": {"url": url}})));
+    Value::Array(content)
+}
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_model_ids() {
+        let groq = json!({"object": "list", "data": [{"id": "llama-3.3-70b-versatile"}, {"id": "gemma2-9b-it"}]});
+        assert_eq!(parse_model_ids(&groq), vec!["gemma2-9b-it", "llama-3.3-70b-versatile"]);
+        let ollama = json!({"models": [{"name": "llama3:latest", "size": 1}]});
+        assert_eq!(parse_model_ids(&ollama), vec!["llama3:latest"]);
+        assert!(parse_model_ids(&json!({"error": "invalid key"})).is_empty());
+    }
 }
@@ -0,0 +1,109 @@
+//! Log Viewer
+//! Debug-build window that tails `data/logs/error.log` live.
+//! Runs as a separate `--log-viewer` process because the egui event loop
+//! needs the main thread, which the app already uses for its event loop.
+
+use eframe::egui;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const MAX_LINES: usize = 500;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct LogViewer {
+    path: PathBuf,
+    last_len: u64,
+    lines: Vec<String>,
+    filter: String,
+    follow: bool,
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogViewer {
+    pub fn new() -> Self {
+        Self {
+            path: PathBuf::from("data/logs/error.log"),
+            last_len: 0,
+            lines: Vec::new(),
+            filter: String::new(),
+            follow: true,
+        }
+    }
+
+    pub fn show(self) {
+        let options = eframe::NativeOptions {
+            viewport: egui::ViewportBuilder::default()
+                .with_inner_size([720.0, 420.0])
+                .with_title("ShadowPrompt Log")
+                .with_always_on_top(),
+            ..Default::default()
+        };
+
+        let _ = eframe::run_native(
+            "ShadowPrompt Log",
+            options,
+            Box::new(|cc| {
+                cc.egui_ctx.set_visuals(egui::Visuals::dark());
+                Ok(Box::new(self))
+            }),
+        );
+    }
+
+    /// Re-read the file only when its size changed; it is truncated on every app start
+    fn refresh(&mut self) {
+        let len = std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if len == self.last_len {
+            return;
+        }
+        self.last_len = len;
+
+        let content = std::fs::read_to_string(&self.path).unwrap_or_default();
+        let all: Vec<&str> = content.lines().collect();
+        let start = all.len().saturating_sub(MAX_LINES);
+        self.lines = all[start..].iter().map(|l| l.to_string()).collect();
+    }
+}
+
+impl eframe::App for LogViewer {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.refresh();
+
+        egui::TopBottomPanel::top("log_toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Filter:");
+                ui.text_edit_singleline(&mut self.filter);
+                ui.checkbox(&mut self.follow, "Follow");
+                ui.label(self.path.display().to_string());
+            });
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let filter = self.filter.to_lowercase();
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .stick_to_bottom(self.follow)
+                .show(ui, |ui| {
+                    for line in &self.lines {
+                        if !filter.is_empty() && !line.to_lowercase().contains(&filter) {
+                            continue;
+                        }
+                        let color = if line.contains("[ERROR]") {
+                            egui::Color32::from_rgb(255, 110, 110)
+                        } else if line.contains("[WARN]") {
+                            egui::Color32::YELLOW
+                        } else {
+                            egui::Color32::LIGHT_GRAY
+                        };
+                        ui.label(egui::RichText::new(line).monospace().color(color));
+                    }
+                });
+        });
+
+        ctx.request_repaint_after(POLL_INTERVAL);
+    }
+}
@@ -1,6 +1,62 @@
+use regex::Regex;
 use simplelog::*;
 use std::fs::File;
+use std::future::Future;
+use std::io::Write;
+use std::collections::VecDeque;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+
+/// Question text, search queries and answers are written to the log only with `general.log_content`
+static LOG_CONTENT: AtomicBool = AtomicBool::new(false);
+
+/// Configured API keys, masked wherever they appear (provider errors can echo them)
+fn secrets() -> &'static RwLock<Vec<String>> {
+    static SECRETS: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+    SECRETS.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Shorter values are too likely to match ordinary text
+const MIN_SECRET_LEN: usize = 8;
+
+/// Lines kept in memory for `key_log_dump`
+pub const RECENT_LINES: usize = 500;
+
+/// The newest `capacity` lines, oldest first
+pub struct RecentRing {
+    capacity: usize,
+    lines: Mutex<VecDeque<String>>,
+}
+
+impl RecentRing {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, lines: Mutex::new(VecDeque::with_capacity(capacity)) }
+    }
+
+    pub fn push(&self, line: &str) {
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line.to_string());
+    }
+
+    pub fn contents(&self) -> String {
+        self.lines.lock().unwrap().iter().map(String::as_str).collect()
+    }
+}
+
+/// What every log line is kept in for `recent`
+fn recent_lines() -> &'static RecentRing {
+    static RECENT: OnceLock<RecentRing> = OnceLock::new();
+    RECENT.get_or_init(|| RecentRing::new(RECENT_LINES))
+}
+
+tokio::task_local! {
+    /// Short ID of the Wake/Model event being answered; prefixed to every log line it causes
+    static QUERY_ID: String;
+}
 
 pub fn init() -> anyhow::Result<()> {
     // Ensure data/logs directory exists
@@ -14,8 +70,188 @@ pub fn init() -> anyhow::Result<()> {
     WriteLogger::init(
         LevelFilter::Info,
         Config::default(),
-        log_file,
+        RedactingWriter { inner: log_file, line: Vec::new() },
     )?;
 
     Ok(())
 }
+
+/// Pick up the loaded config's keys and `log_content`; call again after the config changes
+pub fn configure(config: &crate::config::Config) {
+    LOG_CONTENT.store(config.general.log_content, Ordering::Relaxed);
+    let mut values = Vec::new();
+    if let Ok(table) = toml::Value::try_from(config) {
+        collect_secrets(&table, "", &mut values);
+    }
+    values.retain(|v| v.len() >= MIN_SECRET_LEN);
+    // Longest first, so a key containing another is masked whole
+    values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+    values.dedup();
+    *secrets().write().unwrap() = values;
+}
+
+/// `text` for the log: as is with `general.log_content`, else only its length
+pub fn content(text: &str) -> String {
+    if LOG_CONTENT.load(Ordering::Relaxed) {
+        text.to_string()
+    } else {
+        format!("[{} chars hidden, set log_content = true to log]", text.chars().count())
+    }
+}
+
+/// The last `RECENT_LINES` log lines (already redacted), oldest first
+pub fn recent() -> String {
+    recent_lines().contents()
+}
+
+/// A fresh 6-character ID for one Wake/Model event
+pub fn new_query_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..6].to_string()
+}
+
+/// The ID of the query the current task is answering, if any
+pub fn query_id() -> Option<String> {
+    QUERY_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Run `future` (usually a spawned query task) with its log lines tagged `[q:<id>]`
+pub fn with_query_id<F: Future>(id: String, future: F) -> impl Future<Output = F::Output> {
+    QUERY_ID.scope(id, future)
+}
+
+/// `f` with its log lines tagged `[q:<id>]`, for the synchronous main loop
+pub fn in_query<R>(id: &str, f: impl FnOnce() -> R) -> R {
+    QUERY_ID.sync_scope(id.to_string(), f)
+}
+
+/// The finished line as written to the file: tagged with the query, then redacted.
+/// It is also kept for `recent`.
+fn format_line(line: &[u8]) -> String {
+    let line = String::from_utf8_lossy(line);
+    let line = match query_id() {
+        Some(id) => redact(&format!("[q:{}] {}", id, line)),
+        None => redact(&line),
+    };
+    recent_lines().push(&line);
+    line
+}
+
+fn collect_secrets(value: &toml::Value, key: &str, out: &mut Vec<String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (k, v) in table {
+                collect_secrets(v, k, out);
+            }
+        }
+        toml::Value::String(s) if is_secret_key(key) && !s.is_empty() => out.push(s.clone()),
+        _ => {}
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    key.ends_with("api_key") || key.ends_with("token") || key.ends_with("secret") || key == "password"
+}
+
+fn patterns() -> &'static [(Regex, &'static str)] {
+    static PATTERNS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // Authorization headers and anything shaped like a provider key
+            (r"(?i)(bearer\s+)[A-Za-z0-9._~+/=-]{8,}", "${1}***"),
+            (r"\b(gsk_|sk-or-v1-|sk-|AIza|ghp_|gho_)[A-Za-z0-9_-]{8,}", "${1}***"),
+            // key=value / "key": "value" pairs in URLs, JSON and config dumps
+            (r#"(?i)((?:api[_-]?key|access[_-]?token|token|secret|password|passwd)["']?\s*[:=]\s*["']?)[^"'\s&,;}]+"#, "${1}***"),
+            // Cookie headers and cookie JSON from the browser flow
+            (r#"(?i)((?:set-)?cookie["']?\s*[:=]\s*)[^\r\n]+"#, "${1}***"),
+        ]
+        .into_iter()
+        .map(|(pattern, replacement)| (Regex::new(pattern).unwrap(), replacement))
+        .collect()
+    })
+}
+
+/// The line with configured secrets and key/cookie-shaped values masked
+pub fn redact(line: &str) -> String {
+    let mut line = line.to_string();
+    for secret in secrets().read().unwrap().iter() {
+        if line.contains(secret.as_str()) {
+            line = line.replace(secret.as_str(), "***");
+        }
+    }
+    for (pattern, replacement) in patterns() {
+        line = pattern.replace_all(&line, *replacement).into_owned();
+    }
+    line
+}
+
+/// Buffers each record up to its newline so the whole line is redacted at once
+struct RedactingWriter<W: Write> {
+    inner: W,
+    line: Vec<u8>,
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.line.extend_from_slice(buf);
+        while let Some(end) = self.line.iter().position(|&b| b == b'\n') {
+            let rest = self.line.split_off(end + 1);
+            let line = std::mem::replace(&mut self.line, rest);
+            self.inner.write_all(format_line(&line).as_bytes())?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.line.is_empty() {
+            let line = std::mem::take(&mut self.line);
+            self.inner.write_all(format_line(&line).as_bytes())?;
+        }
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_patterns() {
+        assert_eq!(redact("Groq API Error: invalid key gsk_abcdefgh12345678"), "Groq API Error: invalid key gsk_***");
+        assert_eq!(redact("Authorization: Bearer abc.def-ghi_12345"), "Authorization: Bearer ***");
+        assert_eq!(redact("GET https://x.test/search?api_key=s3cr3tvalue&q=cells"), "GET https://x.test/search?api_key=***&q=cells");
+        assert_eq!(redact(r#"{"token": "abcd1234efgh"}"#), r#"{"token": "***"}"#);
+        assert_eq!(redact("Cookie: SID=123; HSID=456"), "Cookie: ***");
+        assert_eq!(redact("nothing secret here"), "nothing secret here");
+    }
+
+    #[test]
+    fn test_writer_redacts_split_lines() {
+        let mut writer = RedactingWriter { inner: Vec::new(), line: Vec::new() };
+        writer.write_all(b"token=abcd").unwrap();
+        writer.write_all(b"1234efgh\nnext").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(String::from_utf8(writer.inner).unwrap(), "token=***\nnext");
+    }
+
+    #[test]
+    fn test_query_id_prefix() {
+        let mut writer = RedactingWriter { inner: Vec::new(), line: Vec::new() };
+        in_query("ab12cd", || writer.write_all(b"[INFO] Query from Clipboard\n").unwrap());
+        writer.write_all(b"[INFO] idle\n").unwrap();
+        assert_eq!(String::from_utf8(writer.inner).unwrap(), "[q:ab12cd] [INFO] Query from Clipboard\n[INFO] idle\n");
+        assert_eq!(new_query_id().len(), 6);
+    }
+
+    #[test]
+    fn test_recent_keeps_the_last_lines() {
+        let ring = RecentRing::new(RECENT_LINES);
+        for i in 0..RECENT_LINES + 20 {
+            ring.push(&format!("line {}\n", i));
+        }
+        let recent = ring.contents();
+        assert_eq!(recent.lines().count(), RECENT_LINES);
+        assert!(recent.starts_with("line 20\n"));
+        assert!(recent.ends_with(&format!("line {}\n", RECENT_LINES + 19)));
+    }
+}
@@ -1,7 +1,11 @@
 #![cfg_attr(not(feature = "debug"), windows_subsystem = "windows")]
 
 mod config;
+mod config_template;
+mod bundle;
 mod input;
+mod input_win32;
+mod capture_wgc;
 mod clipboard;
 mod ui;
 mod ocr;
@@ -9,24 +13,45 @@ mod llm;
 mod knowledge;
 mod utils;
 mod setup;
+mod setup_headless;
 mod logger;
+mod crash;
 mod tos_text;
 mod hotkey_recorder;
 mod color_picker;
 mod capabilities;
+mod provider_health;
+mod source;
+mod pipeline;
+mod app_state;
+mod output_format;
+mod selftest;
+mod analytics;
+mod spend;
+mod remote_config;
+mod answer_cache;
+mod i18n;
+mod dpapi;
+mod passphrase_dialog;
+#[cfg(feature = "debug")]
+mod log_viewer;
 pub mod browser;
 
 #[macro_use]
 extern crate log;
 
 use crate::config::Config;
-use crate::input::{InputManager, InputEvent};
+use crate::i18n::{t, tf};
+use crate::input::{HotkeyBindings, InputBackend, InputManager, InputEvent};
 use crate::clipboard::ClipboardManager;
 use crate::ui::{UIManager, UICommand};
-use crate::llm::LlmClient;
 use crate::knowledge::KnowledgeProvider;
 use crate::capabilities::ModelCapabilities;
-use crate::utils::{parse_hex_color, parse_keys};
+use crate::analytics::Event;
+use crate::ocr::CaptureBackend;
+use crate::pipeline::QueryPipeline;
+use crate::app_state::{AppState, JobKind};
+use crate::source::{ClipboardSource, OcrSource, QuestionSource, StdinSource};
 use std::sync::mpsc;
 
 fn main() -> anyhow::Result<()> {
@@ -37,23 +62,116 @@ fn main() -> anyhow::Result<()> {
     if let Err(e) = crate::logger::init() {
         eprintln!("Failed to initialize logger: {}", e);
     }
+    crate::crash::install();
 
     // Check for --debug flag or config setting
     let args: Vec<String> = std::env::args().collect();
     let debug_flag = args.contains(&"--debug".to_string());
+    let self_test = args.contains(&"--self-test".to_string());
+    let stats = args.contains(&"--stats".to_string());
+    let spend_report = args.contains(&"--spend-report".to_string());
+    let encrypt_keys = args.contains(&"--encrypt-keys".to_string());
+    let init_config = args.contains(&"--init-config".to_string());
+    let bundle_command = crate::bundle::Command::from_args(&args);
+    let setup_headless = args.contains(&"--setup-headless".to_string());
+    let index_command = crate::knowledge::IndexCommand::from_args(&args);
     
     // If debug flag is present, attach console
-    if debug_flag {
+    if debug_flag || self_test || stats || spend_report || encrypt_keys || init_config || setup_headless || bundle_command.is_some() || index_command.is_some() {
         unsafe {
             use windows::Win32::System::Console::AllocConsole;
             let _ = AllocConsole();
         }
     }
     
+    #[cfg(feature = "debug")]
+    if args.contains(&"--log-viewer".to_string()) {
+        crate::log_viewer::LogViewer::new().show();
+        return Ok(());
+    }
+
+    if stats {
+        crate::analytics::print_report();
+        return Ok(());
+    }
+
+    if spend_report {
+        crate::spend::print_report(&Config::load().unwrap_or_default());
+        return Ok(());
+    }
+
+    if init_config {
+        match crate::config_template::run(&args) {
+            Ok(path) => println!("[+] Wrote a documented default config to {}", path.display()),
+            Err(e) => {
+                eprintln!("[!] {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if setup_headless {
+        if let Err(e) = crate::setup_headless::run(&args) {
+            eprintln!("[!] {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(command) = bundle_command {
+        if let Err(e) = command.and_then(|command| command.run()) {
+            eprintln!("[!] {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if encrypt_keys {
+        match crate::config::encrypt_keys() {
+            Ok(0) => println!("[*] No plaintext API keys found in config.toml."),
+            Ok(count) => println!("[+] Encrypted {} API key(s) in config.toml for this Windows account.", count),
+            Err(e) => {
+                eprintln!("[!] {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // Index management works before setup too, so notes can be prepared on another machine
+    if let Some(command) = index_command {
+        let config = Config::load().unwrap_or_default().with_cli_overrides();
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        if let Err(e) = rt.block_on(command.run(&config)) {
+            eprintln!("[!] {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Self-test doesn't require a finished setup; it reports what is missing instead
+    if self_test {
+        let config = Config::load().unwrap_or_default().with_cli_overrides();
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let all_passed = rt.block_on(crate::selftest::run(&config));
+        std::process::exit(if all_passed { 0 } else { 1 });
+    }
+
     // 1. Setup Wizard (First Run or --setup)
     let args: Vec<String> = std::env::args().collect();
     let force_setup = args.contains(&"--setup".to_string()) || args.contains(&"--reset-setup".to_string());
 
+    if args.contains(&"--settings".to_string()) && Config::is_setup_complete() {
+        println!("[*] Opening ShadowPrompt Settings...");
+        crate::setup::SetupWizard::settings().show();
+        return Ok(());
+    }
+
     if !Config::is_setup_complete() || force_setup {
         println!("[*] Starting ShadowPrompt Setup Wizard...");
         let wizard = crate::setup::SetupWizard::new();
@@ -71,9 +189,28 @@ fn main() -> anyhow::Result<()> {
         .enable_all()
         .build()?;
 
+    if args.contains(&"--stdin".to_string()) {
+        return rt.block_on(run_stdin());
+    }
+
     rt.block_on(run_app())
 }
 
+/// One-shot mode: read a question from stdin, print the answer and exit.
+async fn run_stdin() -> anyhow::Result<()> {
+    let config = Config::load().unwrap_or_default().with_cli_overrides();
+    let index_path = crate::knowledge::rag::index_file_path(&config);
+    if let Err(e) = crate::knowledge::crypt::init(&config, &index_path, crate::knowledge::crypt::console_prompt) {
+        eprintln!("[!] Knowledge index: {}", e);
+    }
+    let knowledge_provider = KnowledgeProvider::new(&config, None).await?;
+    let mut request = StdinSource.read().await?;
+    QueryPipeline::guard_input(&mut request, &config);
+    let response = QueryPipeline::answer(&request, &config, &knowledge_provider).await;
+    println!("{}", response);
+    Ok(())
+}
+
 async fn run_app() -> anyhow::Result<()> {
     // 2. Load Configuration
     println!("[*] Loading ShadowPrompt...");
@@ -85,35 +222,56 @@ async fn run_app() -> anyhow::Result<()> {
             Config::default()
         }
     };
+    // Command-line overrides win over the remote snippet
+    let config = crate::remote_config::apply(config).await.with_cli_overrides();
+    crate::i18n::set_language(&config.general.language);
+    crate::logger::configure(&config);
+    crate::spend::configure(&config);
 
     println!("[*] Loaded Configuration. Mode: {}", config.general.mode);
     println!("[*] Active Provider: {}", config.models.provider);
+    crate::analytics::init(config.general.local_analytics);
+    ClipboardManager::init_history(config.general.clipboard_history_size);
 
     // KEYBIND CONFLICT CHECK
-    let wake_str = config.general.wake_key.to_lowercase();
-    let model_str = config.general.model_key.to_lowercase();
-    let panic_str = config.general.panic_key.to_lowercase();
-
-    if wake_str == model_str || wake_str == panic_str || model_str == panic_str {
+    if let Err(conflict) = crate::hotkey_recorder::validate_hotkeys(&config) {
         eprintln!("\n/!\\ WARNING: DUPLICATE KEYBINDS DETECTED /!\\");
-        eprintln!("    Wake:  {}", config.general.wake_key);
-        eprintln!("    Model: {}", config.general.model_key);
-        eprintln!("    Panic: {}", config.general.panic_key);
+        eprintln!("    {}", conflict);
         eprintln!("    Behavior is undefined for overlapping keys.\n");
+        error!("Hotkey conflict: {}", conflict);
     }
 
     // 2. Initialize Knowledge Provider (Search & RAG)
     // This might take a moment if downloading embedding models.
     println!("[*] Initializing Knowledge Provider...");
-    let knowledge_provider = std::sync::Arc::new(KnowledgeProvider::new(&config).await?);
+    let index_path = crate::knowledge::rag::index_file_path(&config);
+    if let Err(e) = crate::knowledge::crypt::init(&config, &index_path, crate::passphrase_dialog::prompt) {
+        eprintln!("[!] Knowledge index: {}", e);
+        error!("Knowledge index: {}", e);
+    }
 
-    // 2. Start Visual Feedback Thread
+    // 2. Start Visual Feedback Thread (before RAG, so ingestion progress can be shown)
     let (ui_tx, ui_rx) = mpsc::channel();
     UIManager::start(ui_rx, config.visuals.clone());
+    crate::crash::attach_ui(ui_tx.clone(), config.general.restart_on_crash);
+
+    let ingest_progress: Option<crate::knowledge::rag::IngestProgress> = config.visuals.text_overlay_enabled.then(|| {
+        let progress_tx = ui_tx.clone();
+        std::sync::Arc::new(move |done: usize, total: usize| {
+            let text = if done < total {
+                tf("overlay.knowledge_indexing", &[("done", &done.to_string()), ("total", &total.to_string())])
+            } else {
+                tf("overlay.knowledge_updated", &[("count", &total.to_string())])
+            };
+            let _ = progress_tx.send(UICommand::SetOverlayText(text));
+        }) as crate::knowledge::rag::IngestProgress
+    });
+    let knowledge_provider = std::sync::Arc::new(KnowledgeProvider::new(&config, ingest_progress).await?);
     
-    // Set initial Green "Ready" state
-    let ready_color = parse_hex_color(&config.visuals.ready_color);
-    let _ = ui_tx.send(UICommand::SetColor(ready_color)); 
+    // Pause/skip state of the form flow, shared with the input hook (digits) and AppState
+    let flow_control = std::sync::Arc::new(crate::browser::FlowControl::default());
+    // The query or form flow in flight and the indicator; starts on the green "Ready" state
+    let mut app_state = AppState::new(ui_tx.clone(), flow_control.clone());
     
     // Send overlay config
     let _ = ui_tx.send(UICommand::UpdateOverlayConfig(
@@ -125,19 +283,25 @@ async fn run_app() -> anyhow::Result<()> {
     // 3. Start Input Listener
     let (tx, rx) = mpsc::channel();
     
-    let wake_keys = parse_keys(&config.general.wake_key);
-    let model_keys = parse_keys(&config.general.model_key);
-    let panic_keys = parse_keys(&config.general.panic_key);
-    let hide_keys = parse_keys(&config.visuals.hide_key);
+    // Unknown key names would otherwise just make a hotkey silently dead
+    let invalid_bindings = HotkeyBindings::invalid(&config);
+    if !invalid_bindings.is_empty() {
+        for problem in &invalid_bindings {
+            eprintln!("[!] Hotkey Error: {}", problem);
+            error!("Hotkey error: {}", problem);
+        }
+        let _ = ui_tx.send(UICommand::SetOverlayText(tf(
+            "overlay.invalid_hotkeys",
+            &[("problems", &invalid_bindings.join("\n"))],
+        )));
+    }
 
-    let b_pass_keys = parse_keys(&config.general.key_browser_pass);
-    let b_exec_keys = parse_keys(&config.general.key_browser_exec);
-    let b_exec_single_keys = parse_keys(&config.general.key_browser_exec_single);
-    let b_abort_keys = parse_keys(&config.general.key_browser_abort);
-    let b_incognito_keys = parse_keys(&config.general.key_browser_incognito);
+    let mut bindings = HotkeyBindings::from_config(&config);
+    bindings.skip_digits = flow_control.paused_flag();
+    let backend = InputBackend::from_config(&config.general.input_backend);
 
-    println!("[*] Listening for Hotkeys...");
-    InputManager::start(wake_keys, model_keys, panic_keys, hide_keys, b_pass_keys, b_exec_keys, b_exec_single_keys, b_abort_keys, b_incognito_keys, tx);
+    println!("[*] Listening for Hotkeys ({:?} backend)...", backend);
+    InputManager::start(bindings, backend, tx);
 
     // 4. Main Event Loop
     println!("[*] ShadowPrompt is running. Press Panic Key to exit.");
@@ -146,212 +310,122 @@ async fn run_app() -> anyhow::Result<()> {
     // For simplicity, we parse on fly or clone config.
     // Ideally we put these in a strut but cloning config is fine for this app scale.
     
-    let mut active_browser_task: Option<tokio::task::JoinHandle<()>> = None;
     let mut stored_password: Option<String> = None;
+    // Text of the last OCR capture, consumed by the save-to-knowledge key
+    let last_ocr_text: std::sync::Arc<std::sync::Mutex<Option<String>>> = Default::default();
+    let mut selection_drawn = false;
+    // Set on Wake and carried to the OCR capture it starts, so both log under one ID
+    let mut wake_query_id: Option<String> = None;
+    #[cfg(feature = "debug")]
+    let mut log_viewer: Option<std::process::Child> = None;
+
+    // No hotkey for this long exits the app (general.idle_exit_minutes)
+    let idle_limit = (config.general.idle_exit_minutes > 0)
+        .then(|| std::time::Duration::from_secs(config.general.idle_exit_minutes.saturating_mul(60)));
+    let mut last_action = std::time::Instant::now();
 
     loop {
         // Check for Input Events (Non-blocking or blocking depending on design)
         // Here we use recv() which blocks, effectively putting the main thread to sleep until an event.
-        if let Ok(event) = rx.recv() {
+        let received = match idle_limit {
+            Some(limit) => rx.recv_timeout(limit.saturating_sub(last_action.elapsed())),
+            None => rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+        };
+        if let Err(mpsc::RecvTimeoutError::Timeout) = received {
+            // A query or form flow still running counts as activity
+            if app_state.is_busy() {
+                last_action = std::time::Instant::now();
+                continue;
+            }
+            println!("[*] No hotkey pressed for {} minutes. Exiting.", config.general.idle_exit_minutes);
+            info!("Idle for {} minutes, exiting", config.general.idle_exit_minutes);
+            shut_down(&ui_tx, #[cfg(feature = "debug")] &mut log_viewer);
+        }
+        if let Ok(event) = received {
+            // Only hotkeys and finished selections are the user acting; drags, digits and
+            // every Ctrl+V also reach this loop
+            if event.binding_name().is_some() || matches!(event, InputEvent::OCRRect(..)) {
+                last_action = std::time::Instant::now();
+            }
+
+            // Any event other than the selection itself ends the rubber-band preview
+            if selection_drawn && !matches!(event, InputEvent::OCRDrag(..) | InputEvent::OCRClick1 | InputEvent::SkipQuestion(_) | InputEvent::Pasted) {
+                let _ = ui_tx.send(UICommand::ClearDebugRect);
+                selection_drawn = false;
+            }
+
             match event {
                 InputEvent::Wake => {
                     println!("[!] EVENT: Wake Key Pressed (Enter OCR Selection Mode)");
-                    // Use Processing Color (Red by default) or maybe a specific "Wake" color?
-                    // Currently hardcoded to Red. Let's use processing color.
-                    let color = parse_hex_color(&config.visuals.color_processing);
-                    let _ = ui_tx.send(UICommand::SetColor(color)); 
+                    let id = crate::logger::new_query_id();
+                    crate::logger::in_query(&id, || info!("[Input] Wake key pressed"));
+                    wake_query_id = Some(id);
+                    app_state.begin_selection();
                 },
                 InputEvent::OCRClick1 => {
                     println!("[!] EVENT: OCR Point 1 Captured");
-                    let _ = ui_tx.send(UICommand::SetColor(0x0000A5FF)); // Orange (BGR: FF A5 00) - Keeping hardcoded or add to config? 
-                    // Keeping hardcoded for now as it wasn't explicitly asked to be configurable, 
-                    // but the user said "Customize any of the indicator colors".
-                    // I'll leave Orange hardcoded for obscure states unless I add more fields.
+                    app_state.point_captured();
+                },
+                InputEvent::OCRDrag(x, y, w, h) => {
+                    if w > 0 && h > 0 {
+                        let _ = ui_tx.send(UICommand::DrawDebugRect(x, y, w, h));
+                        selection_drawn = true;
+                    }
                 },
                 InputEvent::OCRRect(x, y, w, h) => {
                     println!("[*] OCR Region Captured: x={}, y={}, w={}, h={}", x, y, w, h);
-                    let _ = ui_tx.send(UICommand::SetColor(0x0000FFFF));
+                    crate::analytics::record(Event::OcrCapture);
 
                     let config_clone = config.clone();
-                    let ui_tx_clone = ui_tx.clone();
-                    let ready_color = parse_hex_color(&config.visuals.ready_color);
-                    
-                    tokio::spawn(async move {
-                        let supports_vision = ModelCapabilities::supports_vision(&config_clone);
-                        
-                        if supports_vision {
-                            println!("[*] Model supports vision, capturing screenshot for LLM...");
-                            
-                            match crate::ocr::OcrManager::capture_as_base64(x, y, w, h).await {
-                                Ok(image_b64) => {
-                                    let prompt = "Analyze the image. If there are questions, answer them directly and concisely. Provide all correct options if it is a multiple-choice question. If it is a matching or matrix question, clearly provide all pairings and answers.";
-                                    
-                                    match LlmClient::query_with_image(prompt, &image_b64, &config_clone).await {
-                                        Ok(response) => {
-                                            println!("[+] Vision query success");
-                                            if let Err(e) = ClipboardManager::write(&response) {
-                                                eprintln!("Clipboard Write Error: {}", e);
-                                            }
-                                            // Show text overlay if enabled
-                                            if config_clone.visuals.text_overlay_enabled && !response.is_empty() {
-                                                let _ = ui_tx_clone.send(UICommand::SetOverlayText(response.clone()));
-                                            }
-                                        },
-                                        Err(e) => {
-                                            eprintln!("[-] Vision query failed: {}, falling back to OCR...", e);
-                                            if let Ok(text) = crate::ocr::OcrManager::extract_from_screen(x, y, w, h).await {
-                                                match LlmClient::query(&text, &config_clone).await {
-                                                    Ok(response) => {
-                                                        println!("[+] OCR fallback success");
-                                                        if let Err(e) = ClipboardManager::write(&response) {
-                                                            eprintln!("Clipboard Write Error: {}", e);
-                                                        }
-                                                        // Show text overlay if enabled
-                                                        if config_clone.visuals.text_overlay_enabled && !response.is_empty() {
-                                                            let _ = ui_tx_clone.send(UICommand::SetOverlayText(response.clone()));
-                                                        }
-                                                    },
-                                                    Err(e) => {
-                                                        eprintln!("[-] LLM query failed: {}", e);
-                                                        if let Err(e) = ClipboardManager::write(&text) {
-                                                            eprintln!("Clipboard Write Error: {}", e);
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                },
-                                Err(e) => {
-                                    eprintln!("[-] Screenshot capture failed: {}", e);
+                    let kp_arc = knowledge_provider.clone();
+                    let last_ocr = last_ocr_text.clone();
+                    let id = wake_query_id.take().unwrap_or_else(crate::logger::new_query_id);
+                    crate::logger::in_query(&id, || info!("[Input] OCR region {}x{} at ({}, {})", w, h, x, y));
+
+                    app_state.start_job(JobKind::Ocr, move |ui| crate::logger::with_query_id(id, async move {
+                        let source = OcrSource {
+                            x, y, w, h,
+                            capture_image: ModelCapabilities::supports_vision(&config_clone),
+                            capture_backend: CaptureBackend::from_config(&config_clone.general.capture_backend),
+                        };
+                        match source.read().await {
+                            Ok(request) => {
+                                if !request.text.trim().is_empty() {
+                                    *last_ocr.lock().unwrap() = Some(request.text.clone());
                                 }
+                                QueryPipeline::run(request, &config_clone, &kp_arc, &ui).await
                             }
-                        } else {
-                            println!("[*] Model doesn't support vision, using OCR fallback...");
-                            
-                            match crate::ocr::OcrManager::extract_from_screen(x, y, w, h).await {
-                                Ok(text) => {
-                                    println!("[+] OCR Success: \"{}\"", text.trim());
-                                    
-                                    match LlmClient::query(&text, &config_clone).await {
-                                        Ok(response) => {
-                                            println!("[+] LLM query success");
-                                            if let Err(e) = ClipboardManager::write(&response) {
-                                                eprintln!("Clipboard Write Error: {}", e);
-                                            }
-                                            // Show text overlay if enabled
-                                            if config_clone.visuals.text_overlay_enabled && !response.is_empty() {
-                                                let _ = ui_tx_clone.send(UICommand::SetOverlayText(response.clone()));
-                                            }
-                                        },
-                                        Err(e) => {
-                                            eprintln!("[-] LLM query failed: {}, writing OCR text only", e);
-                                            if let Err(e) = ClipboardManager::write(&text) {
-                                                eprintln!("Clipboard Write Error: {}", e);
-                                            }
-                                        }
-                                    }
-                                },
-                                Err(e) => {
-                                    eprintln!("[-] OCR Failed: {}", e);
-                                }
+                            Err(e) => {
+                                eprintln!("[-] OCR Failed: {}", e);
+                                error!("[Input] OCR failed: {}", e);
+                                crate::analytics::record(Event::Error("ocr"));
+                                ui.finish(None);
                             }
                         }
-                        
-                        let _ = ui_tx_clone.send(UICommand::SetColor(ready_color));
-                    });
+                    }));
                 },
                 InputEvent::Model => {
                     println!("[!] EVENT: Model Key Pressed (Clipboard Trigger)");
-                    let processing_color = parse_hex_color(&config.visuals.color_processing);
-                    let _ = ui_tx.send(UICommand::SetColor(processing_color)); 
-                    let _ = ui_tx.send(UICommand::ClearOverlayText);
-                    
+
                     let config_clone = config.clone();
-                    let ui_tx_clone = ui_tx.clone();
-                    let ready_color = parse_hex_color(&config.visuals.ready_color);
                     let kp_arc = knowledge_provider.clone();
+                    let id = crate::logger::new_query_id();
+                    crate::logger::in_query(&id, || info!("[Input] Model key pressed"));
 
-                    tokio::spawn(async move {
-
-                        // 1. Read Clipboard
-                        let prompt = match ClipboardManager::read() {
-                            Ok(text) => text,
+                    app_state.start_job(JobKind::Clipboard, move |ui| crate::logger::with_query_id(id, async move {
+                        match ClipboardSource.read().await {
+                            Ok(request) => QueryPipeline::run(request, &config_clone, &kp_arc, &ui).await,
                             Err(e) => {
                                 eprintln!("Clipboard Read Error: {}", e);
-                                let _ = ui_tx_clone.send(UICommand::SetColor(ready_color));
-                                return;
+                                error!("[Clipboard] Read failed: {}", e);
+                                ui.finish(None);
                             }
-                        };
-
-                        println!("[*] Processing Query: {:.50}...", prompt);
-
-
-                        // 2. Gather Context (Search/RAG)
-                        let (context, warnings) = match kp_arc.gather_context(&prompt, &config_clone).await {
-                             Ok((ctx, warns)) => (ctx, warns),
-                             Err(e) => {
-                                 let err_msg = format!("Knowledge System Error: {}", e);
-                                 error!("{}", err_msg);
-                                 (String::new(), vec![err_msg])
-                             }
-                        };
-                        
-                        let augmented_prompt = if !context.is_empty() {
-                            info!("[*] Context found. Augmenting prompt.");
-                            format!("Context:\n{}\nQuestion:\n{}", context, prompt)
-                        } else {
-                            prompt.clone()
-                        };
-
-                        // 3. Query LLM
-                        let mut final_output = String::new();
-
-                        // Add warnings to output if any
-                        for warning in warnings {
-                            final_output.push_str(&format!("[System Warning: {}]\n\n", warning));
-                        }
-
-                        match LlmClient::query(&augmented_prompt, &config_clone).await {
-                             Ok(res) => {
-                                 final_output.push_str(&res);
-                             },
-                             Err(e) => {
-                                 let err_msg = format!("AI Error: {}", e);
-                                 error!("{}", err_msg);
-                                 // If we have warnings, they are already in final_output. 
-                                 // We append the fatal error.
-                                 final_output.push_str(&format!("[FATAL ERROR]\n{}", err_msg));
-                             }
-                        };
-                        
-                        // Treat the final_output as the response for MCQ/Clipboard
-                        let response = final_output;
-
-                        // 5. Write Clipboard (Always)
-                        if let Err(e) = ClipboardManager::write(&response) {
-                            eprintln!("Clipboard Write Error: {}", e);
-                        }
-
-                        // 6. Show text overlay if enabled
-                        if config_clone.visuals.text_overlay_enabled && !response.is_empty() {
-                            let _ = ui_tx_clone.send(UICommand::SetOverlayText(response.clone()));
                         }
-
-                        println!("[*] Response written to clipboard.");
-                        let _ = ui_tx_clone.send(UICommand::SetColor(0x0000FF00)); // Reset Green
-                        
-                        // We do NOT reset the secondary color immediately here, so the user can see it.
-                        // However, we should probably reset it on the NEXT trigger or after a timeout?
-                        // The user request didn't specify reset behavior, but usually indicators stay until next action.
-                    });
+                    }));
                 },
                 InputEvent::Panic => {
                     println!("[!!!] PANIC KEY PRESSED. EXITING.");
-                    if let Err(e) = ClipboardManager::clear() {
-                        eprintln!("Failed to clear clipboard: {}", e);
-                    }
-                    std::process::exit(0);
+                    shut_down(&ui_tx, #[cfg(feature = "debug")] &mut log_viewer);
                 },
                 InputEvent::HideToggle => {
                     println!("[!] EVENT: Hide Toggle Key Pressed");
@@ -361,18 +435,17 @@ async fn run_app() -> anyhow::Result<()> {
                     println!("[!] EVENT: Browser Pass Key Pressed");
                     if let Ok(text) = ClipboardManager::read() {
                         stored_password = Some(text);
-                        if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText("🔑 Password locked.".to_string())); }
+                        if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(t("overlay.password_locked"))); }
                     } else {
-                        if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText("❌ No password on clipboard.".to_string())); }
+                        if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(t("overlay.no_password"))); }
                     }
                 },
                 InputEvent::BrowserAbort => {
                     println!("[!] EVENT: Browser Abort Key Pressed");
-                    if let Some(handle) = active_browser_task.take() {
-                        handle.abort();
-                        if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText("🛑 Headless Browser Aborted.".to_string())); }
+                    if app_state.abort_browser() {
+                        if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(t("overlay.browser_aborted"))); }
                     } else {
-                        if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText("ℹ️ No active browser task to abort.".to_string())); }
+                        if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(t("overlay.nothing_to_abort"))); }
                     }
                 },
                 InputEvent::BrowserExec | InputEvent::BrowserExecSingle => {
@@ -382,42 +455,180 @@ async fn run_app() -> anyhow::Result<()> {
                     };
                     
                     println!("[!] EVENT: Browser Exec Key Pressed (Auto={})", is_auto);
+                    // One flow at a time; a second one would drive the same tab
+                    if app_state.browser_running() {
+                        if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(t("overlay.form_flow_running"))); }
+                        continue;
+                    }
                     // Read clipboard, but don't hard fail if it's empty or invalid yet.
                     let url = match ClipboardManager::read() {
                         Ok(t) if t.contains("forms.gle") || t.contains("docs.google.com/forms") => Some(t),
                         _ => None,
                     };
 
-                    if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText("🧠 Initializing browser...".to_string())); }
+                    if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(t("overlay.initializing_browser"))); }
                     
                     let p_clone = stored_password.clone();
                     let c_clone = std::sync::Arc::new(config.clone());
                     let tx_clone = ui_tx.clone();
                     let debug_mode = config.general.debug;
+                    // A dry run leaves its preview on the overlay
+                    let dry_run = config.browser.dry_run;
+                    let control = flow_control.clone();
                     
-                    active_browser_task = Some(tokio::spawn(async move {
-                        if let Err(e) = crate::browser::execute_form_flow(url.as_deref(), p_clone.as_deref(), c_clone, tx_clone.clone(), is_auto).await {
-                            if debug_mode { let _ = tx_clone.send(UICommand::SetOverlayText(format!("❌ Browser Error: {}", e))); }
-                        } else {
-                            if debug_mode { let _ = tx_clone.send(UICommand::SetOverlayText("✅ Answers Auto-saved.".to_string())); }
+                    app_state.start_browser(async move {
+                        if let Err(e) = crate::browser::execute_form_flow(url.as_deref(), p_clone.as_deref(), c_clone, tx_clone.clone(), is_auto, control).await {
+                            crate::analytics::record(Event::Error("browser"));
+                            if debug_mode { let _ = tx_clone.send(UICommand::SetOverlayText(tf("overlay.browser_error", &[("error", &e.to_string())]))); }
+                        } else if debug_mode && !dry_run {
+                            let _ = tx_clone.send(UICommand::SetOverlayText(t("overlay.answers_saved")));
                         }
-                    }));
+                    });
                 },
                 InputEvent::BrowserIncognito => {
                     println!("[!] EVENT: Browser Incognito Key Pressed");
-                    if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText("🌐 Launching Debug Incognito...".to_string())); }
+                    if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(t("overlay.launching_incognito"))); }
                     
                     let tx_clone = ui_tx.clone();
                     let debug_mode = config.general.debug;
+                    let binary_path = config.browser.binary_path.clone();
                     tokio::spawn(async move {
-                        if let Err(e) = crate::browser::launch_incognito_debugger() {
-                            if debug_mode { let _ = tx_clone.send(UICommand::SetOverlayText(format!("❌ Failed to launch Chrome: {}", e))); }
+                        if let Err(e) = crate::browser::launch_incognito_debugger(binary_path.as_deref()) {
+                            if debug_mode { let _ = tx_clone.send(UICommand::SetOverlayText(tf("overlay.launch_failed", &[("error", &e.to_string())]))); }
                         } else {
-                            if debug_mode { let _ = tx_clone.send(UICommand::SetOverlayText("✅ Incognito Debugger Ready.".to_string())); }
+                            if debug_mode { let _ = tx_clone.send(UICommand::SetOverlayText(t("overlay.incognito_ready"))); }
+                        }
+                    });
+                }
+                InputEvent::BrowserPause => {
+                    if app_state.browser_running() {
+                        let paused = flow_control.toggle_pause();
+                        println!("[!] EVENT: Form flow {}", if paused { "paused" } else { "resumed" });
+                        if paused {
+                            let _ = ui_tx.send(UICommand::SetOverlayText(t("overlay.pausing")));
+                        }
+                    } else {
+                        println!("[*] No active form flow to pause.");
+                    }
+                }
+                InputEvent::Pasted => {
+                    if config.visuals.clipboard_restore == "after_paste" {
+                        // Give the target app time to read the clipboard before swapping it back
+                        tokio::spawn(async {
+                            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                            match ClipboardManager::restore_pending(None) {
+                                Ok(true) => println!("[*] Original clipboard restored after paste."),
+                                Ok(false) => {}
+                                Err(e) => eprintln!("[-] Clipboard restore failed: {}", e),
+                            }
+                        });
+                    }
+                }
+                InputEvent::ClipboardCycle => {
+                    match ClipboardManager::cycle_history() {
+                        Ok(Some((position, count))) => {
+                            println!("[*] Clipboard restored from history ({}/{})", position, count);
+                            if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(tf("overlay.history", &[("position", &position.to_string()), ("count", &count.to_string())]))); }
+                        }
+                        Ok(None) => println!("[*] Clipboard history is empty."),
+                        Err(e) => eprintln!("[-] Clipboard history restore failed: {}", e),
+                    }
+                }
+                InputEvent::RagCapture => {
+                    println!("[!] EVENT: Save to Knowledge Key Pressed");
+                    // The clipboard usually holds the answer after an OCR query, so an unsaved OCR result wins
+                    let text = match last_ocr_text.lock().unwrap().take() {
+                        Some(text) => Ok(text),
+                        None => ClipboardManager::read(),
+                    };
+                    let config_clone = config.clone();
+                    let ui_tx_clone = ui_tx.clone();
+                    let kp_arc = knowledge_provider.clone();
+
+                    tokio::spawn(async move {
+                        let result = match text {
+                            Ok(text) => kp_arc.capture(&text, &config_clone).await,
+                            Err(e) => Err(e),
+                        };
+                        match result {
+                            Ok(path) => {
+                                println!("[+] Saved to knowledge base: {}", path.display());
+                                if config_clone.general.debug { let _ = ui_tx_clone.send(UICommand::SetOverlayText(t("overlay.saved_to_knowledge"))); }
+                            }
+                            Err(e) => {
+                                eprintln!("[-] Save to knowledge failed: {}", e);
+                                if config_clone.general.debug { let _ = ui_tx_clone.send(UICommand::SetOverlayText(tf("overlay.save_to_knowledge_failed", &[("error", &e.to_string())]))); }
+                            }
                         }
                     });
                 }
+                InputEvent::SkipQuestion(number) => {
+                    if flow_control.is_paused() {
+                        let skipped = flow_control.toggle_skip(number);
+                        println!("[*] Question {} {}", number, if skipped { "will be skipped" } else { "restored" });
+                    }
+                }
+                InputEvent::LogViewerToggle => {
+                    #[cfg(feature = "debug")]
+                    {
+                        // Close if it is still open, otherwise launch a new viewer process
+                        let running = log_viewer.as_mut().is_some_and(|child| matches!(child.try_wait(), Ok(None)));
+                        if running {
+                            if let Some(mut child) = log_viewer.take() {
+                                let _ = child.kill();
+                                let _ = child.wait();
+                            }
+                        } else {
+                            match std::env::current_exe().and_then(|exe| std::process::Command::new(exe).arg("--log-viewer").spawn()) {
+                                Ok(child) => log_viewer = Some(child),
+                                Err(e) => eprintln!("[-] Failed to open log viewer: {}", e),
+                            }
+                        }
+                    }
+                    #[cfg(not(feature = "debug"))]
+                    println!("[*] Log viewer is only available in debug builds.");
+                }
+                InputEvent::LogDump => {
+                    println!("[!] EVENT: Log Dump Key Pressed");
+                    let recent = crate::logger::recent();
+                    let lines = recent.lines().count().to_string();
+                    let result = if config.general.log_dump_target == "file" {
+                        let path = crate::config::get_exe_dir()
+                            .join("data")
+                            .join("logs")
+                            .join(format!("dump-{}.log", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+                        std::fs::write(&path, &recent)
+                            .map(|_| tf("overlay.log_dumped_file", &[("lines", &lines), ("path", &path.display().to_string())]))
+                            .map_err(anyhow::Error::from)
+                    } else {
+                        ClipboardManager::write(&recent).map(|_| tf("overlay.log_dumped", &[("lines", &lines)]))
+                    };
+                    match result {
+                        Ok(message) => {
+                            println!("[+] {}", message);
+                            if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(message)); }
+                        }
+                        Err(e) => {
+                            eprintln!("[-] Log dump failed: {}", e);
+                            if config.general.debug { let _ = ui_tx.send(UICommand::SetOverlayText(tf("overlay.log_dump_failed", &[("error", &e.to_string())]))); }
+                        }
+                    }
+                }
             }
         }
     }
 }
+
+/// Clear the clipboard and overlays, close the log viewer, and exit (panic key, idle timeout)
+fn shut_down(ui_tx: &mpsc::Sender<UICommand>, #[cfg(feature = "debug")] log_viewer: &mut Option<std::process::Child>) -> ! {
+    if let Err(e) = ClipboardManager::clear() {
+        eprintln!("Failed to clear clipboard: {}", e);
+    }
+    let _ = ui_tx.send(UICommand::ClearOverlayText);
+    let _ = ui_tx.send(UICommand::ClearDebugRect);
+    #[cfg(feature = "debug")]
+    if let Some(mut child) = log_viewer.take() {
+        let _ = child.kill();
+    }
+    std::process::exit(0);
+}
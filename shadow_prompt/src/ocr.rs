@@ -6,8 +6,10 @@ use windows::Storage::Streams::DataWriter;
 use windows::Win32::Graphics::Gdi::{
     GetDC, CreateCompatibleDC, CreateCompatibleBitmap, SelectObject, BitBlt, 
     DeleteObject, DeleteDC, ReleaseDC, SRCCOPY, BITMAPINFO, BITMAPINFOHEADER, 
-    DIB_RGB_COLORS, BI_RGB, GetDIBits,
+    DIB_RGB_COLORS, BI_RGB, GetDIBits, PatBlt, WHITENESS, CreateFontW, TextOutW,
+    SetBkMode, SetTextColor, TRANSPARENT, HDC, HBITMAP,
 };
+use windows::Win32::Foundation::COLORREF;
 use windows::Win32::UI::WindowsAndMessaging::GetDesktopWindow;
 use windows::Win32::Foundation::HWND;
 use windows::Foundation::AsyncStatus;
@@ -15,16 +17,53 @@ use anyhow::{Result, Context};
 
 // Blocking helper removed. We rely on async/await support in windows-rs.
 
+/// PowerShell (admin) command that installs an OCR language pack, shown when none matches the profile
+pub const OCR_INSTALL_HINT: &str = "Add-WindowsCapability -Online -Name \"Language.OCR~~~en-US~0.0.1.0\"";
+/// Windows Settings deep link for adding display languages (and their OCR packs)
+pub const LANGUAGE_SETTINGS_URI: &str = "ms-settings:regionlanguage";
+
+pub struct OcrLanguageStatus {
+    /// Language the profile-based engine picked; None when no profile language has an OCR pack
+    pub active: Option<String>,
+    /// BCP-47 tags of every installed OCR language
+    pub installed: Vec<String>,
+}
+
+/// Screen capture implementation (`general.capture_backend`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CaptureBackend {
+    BitBlt,
+    /// Windows Graphics Capture, falling back to BitBlt when it fails
+    Wgc,
+}
+
+impl CaptureBackend {
+    pub fn from_config(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "wgc" | "auto" => CaptureBackend::Wgc,
+            "bitblt" | "gdi" => CaptureBackend::BitBlt,
+            other => {
+                eprintln!("Warning: Unknown capture backend '{}', using bitblt", other);
+                CaptureBackend::BitBlt
+            }
+        }
+    }
+}
+
 pub struct OcrManager;
 
 impl OcrManager {
-    pub async fn extract_from_screen(x: i32, y: i32, width: i32, height: i32) -> Result<String> {
+    pub async fn extract_from_screen(backend: CaptureBackend, x: i32, y: i32, width: i32, height: i32) -> Result<String> {
         // 1. Capture Pixels
-        let pixels = capture_pixels(x, y, width, height)?;
-        
+        let pixels = capture_region(backend, x, y, width, height)?;
+        Self::recognize_pixels(&pixels, width, height).await
+    }
+
+    /// Run OCR on a top-down BGRA buffer
+    pub async fn recognize_pixels(pixels: &[u8], width: i32, height: i32) -> Result<String> {
         // 2. Create IBuffer via DataWriter
         let writer = DataWriter::new()?;
-        writer.WriteBytes(&pixels)?;
+        writer.WriteBytes(pixels)?;
         let buffer = writer.DetachBuffer()?;
 
         // 3. Create SoftwareBitmap
@@ -36,9 +75,12 @@ impl OcrManager {
         )?;
 
         // 4. Init Engine
-        let engine = OcrEngine::TryCreateFromUserProfileLanguages().unwrap_or_else(|_| {
-             panic!("Failed to create OCR engine from profile languages.");
-        });
+        let engine = OcrEngine::TryCreateFromUserProfileLanguages().with_context(|| {
+            format!(
+                "No Windows OCR language pack matches your profile languages. Install one via {} or run: {}",
+                LANGUAGE_SETTINGS_URI, OCR_INSTALL_HINT
+            )
+        })?;
 
         // 5. Recognize
         let operation = engine.RecognizeAsync(&bitmap)?;
@@ -54,13 +96,75 @@ impl OcrManager {
         Ok(text)
     }
 
-    pub async fn capture_as_base64(x: i32, y: i32, width: i32, height: i32) -> Result<String> {
-        let pixels = capture_pixels(x, y, width, height)?;
+    /// Which OCR languages are installed and whether one matches the user profile
+    pub fn language_status() -> Result<OcrLanguageStatus> {
+        let languages = OcrEngine::AvailableRecognizerLanguages()?;
+        let mut installed = Vec::new();
+        for i in 0..languages.Size()? {
+            installed.push(languages.GetAt(i)?.LanguageTag()?.to_string());
+        }
+
+        let active = OcrEngine::TryCreateFromUserProfileLanguages()
+            .and_then(|engine| engine.RecognizerLanguage())
+            .and_then(|lang| lang.LanguageTag())
+            .map(|tag| tag.to_string())
+            .ok();
+
+        Ok(OcrLanguageStatus { active, installed })
+    }
+
+    pub async fn capture_as_base64(backend: CaptureBackend, x: i32, y: i32, width: i32, height: i32) -> Result<String> {
+        let pixels = capture_region(backend, x, y, width, height)?;
         let png_bytes = encode_bgra_to_png(&pixels, width, height)?;
         Ok(base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &png_bytes))
     }
 }
 
+/// Render black text on a white bitmap and return its BGRA pixels (used by `--self-test`)
+pub fn render_text_pixels(text: &str, width: i32, height: i32) -> Result<Vec<u8>> {
+    unsafe {
+        let hwnd_desktop = GetDesktopWindow();
+        let hdc_screen = GetDC(hwnd_desktop);
+        let hdc_mem = CreateCompatibleDC(hdc_screen);
+        let hbitmap = CreateCompatibleBitmap(hdc_screen, width, height);
+        let h_old = SelectObject(hdc_mem, hbitmap);
+
+        let font = CreateFontW(
+            height / 2, 0, 0, 0, 700, 0, 0, 0, 0, 0, 0, 0, 0,
+            windows::core::w!("Arial"),
+        );
+        let old_font = SelectObject(hdc_mem, font);
+
+        let _ = PatBlt(hdc_mem, 0, 0, width, height, WHITENESS);
+        SetBkMode(hdc_mem, TRANSPARENT);
+        SetTextColor(hdc_mem, COLORREF(0));
+        let wide: Vec<u16> = text.encode_utf16().collect();
+        let _ = TextOutW(hdc_mem, 10, height / 4, &wide);
+
+        SelectObject(hdc_mem, old_font);
+        DeleteObject(font);
+
+        let pixels = read_bitmap_pixels(hdc_mem, hbitmap, width, height);
+
+        SelectObject(hdc_mem, h_old); DeleteObject(hbitmap); DeleteDC(hdc_mem); ReleaseDC(hwnd_desktop, hdc_screen);
+
+        Ok(pixels)
+    }
+}
+
+fn capture_region(backend: CaptureBackend, x: i32, y: i32, width: i32, height: i32) -> Result<Vec<u8>> {
+    if backend == CaptureBackend::Wgc {
+        match crate::capture_wgc::capture_pixels(x, y, width, height) {
+            Ok(pixels) => return Ok(pixels),
+            Err(e) => {
+                eprintln!("[-] WGC capture failed ({}), falling back to BitBlt", e);
+                error!("WGC capture failed: {}", e);
+            }
+        }
+    }
+    capture_pixels(x, y, width, height)
+}
+
 fn capture_pixels(x: i32, y: i32, width: i32, height: i32) -> Result<Vec<u8>> {
     unsafe {
         let hwnd_desktop = GetDesktopWindow();
@@ -75,21 +179,7 @@ fn capture_pixels(x: i32, y: i32, width: i32, height: i32) -> Result<Vec<u8>> {
             anyhow::bail!("BitBlt failed");
         }
 
-        let mut bmi = BITMAPINFO {
-            bmiHeader: BITMAPINFOHEADER {
-                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
-                biWidth: width,
-                biHeight: -height, // Top-down
-                biPlanes: 1,
-                biBitCount: 32, 
-                biCompression: BI_RGB.0,
-                ..Default::default()
-            },
-            ..Default::default()
-        };
-
-        let mut pixels = vec![0u8; (width * height * 4) as usize];
-        GetDIBits(hdc_mem, hbitmap, 0, height as u32, Some(pixels.as_mut_ptr() as *mut _), &mut bmi, DIB_RGB_COLORS);
+        let pixels = read_bitmap_pixels(hdc_mem, hbitmap, width, height);
 
         SelectObject(hdc_mem, h_old); DeleteObject(hbitmap); DeleteDC(hdc_mem); ReleaseDC(hwnd_desktop, hdc_screen);
         
@@ -97,6 +187,26 @@ fn capture_pixels(x: i32, y: i32, width: i32, height: i32) -> Result<Vec<u8>> {
     }
 }
 
+unsafe fn read_bitmap_pixels(hdc_mem: HDC, hbitmap: HBITMAP, width: i32, height: i32) -> Vec<u8> {
+    let mut bmi = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: -height, // Top-down
+            biPlanes: 1,
+            biBitCount: 32, 
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+    GetDIBits(hdc_mem, hbitmap, 0, height as u32, Some(pixels.as_mut_ptr() as *mut _), &mut bmi, DIB_RGB_COLORS);
+
+    pixels
+}
+
 fn encode_bgra_to_png(pixels: &[u8], width: i32, height: i32) -> Result<Vec<u8>> {
     use image::{ImageBuffer, RgbaImage, ImageEncoder};
     
@@ -0,0 +1,114 @@
+//! Output Templates
+//! Shapes the LLM response before it goes to the clipboard (`visuals.output_template`).
+//! Presets: "raw" (unchanged), "clean" (no [System Warning] blocks), "answer_only" (first
+//! answer line). Anything else is a custom template with these placeholders:
+//! {answer} first answer line, {letter} A-D / True / False, {text} answer after the letter,
+//! {explanation} remaining lines, {response} full response without warnings.
+//! A trailing "Sources:" line (`rag.append_sources`, `search.append_sources`) is kept after any template.
+
+use crate::utils::{parse_question_type, McqAnswer, QuestionType};
+
+pub const SOURCES_PREFIX: &str = "Sources: ";
+
+pub fn apply(template: &str, response: &str) -> String {
+    // Errors are kept verbatim so the user can still see what went wrong
+    if template == "raw" || template.is_empty() || response.contains("[FATAL ERROR]") {
+        return response.to_string();
+    }
+
+    let (body, sources) = match response.trim_end().rsplit_once('\n') {
+        Some((body, last)) if last.starts_with(SOURCES_PREFIX) => (body, Some(last)),
+        _ => (response, None),
+    };
+    let formatted = apply_template(template, body);
+    match sources {
+        Some(sources) => format!("{}\n{}", formatted, sources),
+        None => formatted,
+    }
+}
+
+fn apply_template(template: &str, response: &str) -> String {
+    let cleaned = strip_warnings(response);
+    let mut lines = cleaned.lines().map(str::trim).filter(|l| !l.is_empty());
+    let answer = lines.next().unwrap_or("").to_string();
+    let explanation = lines.collect::<Vec<_>>().join("\n");
+
+    match template {
+        "clean" => cleaned,
+        "answer_only" => answer,
+        custom => {
+            let (letter, text) = split_answer(&answer);
+            custom
+                .replace("{response}", &cleaned)
+                .replace("{answer}", &answer)
+                .replace("{letter}", &letter)
+                .replace("{text}", &text)
+                .replace("{explanation}", &explanation)
+                .replace("\\n", "\n")
+                .trim()
+                .to_string()
+        }
+    }
+}
+
+fn strip_warnings(response: &str) -> String {
+    response
+        .lines()
+        .filter(|l| !l.trim_start().starts_with("[System Warning"))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// "B) Mars" -> ("B", "Mars"); "True" -> ("True", ""); free text -> ("", text)
+fn split_answer(answer: &str) -> (String, String) {
+    match parse_question_type(answer) {
+        QuestionType::MultipleChoice(choice) => {
+            let letter = match choice {
+                McqAnswer::A => "A",
+                McqAnswer::B => "B",
+                McqAnswer::C => "C",
+                McqAnswer::D => "D",
+            };
+            let text = answer.get(2..).unwrap_or("").trim().to_string();
+            (letter.to_string(), text)
+        }
+        QuestionType::TrueFalse(value) => (if value { "True" } else { "False" }.to_string(), String::new()),
+        QuestionType::Unknown => (String::new(), answer.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = "[System Warning: Search failed]\n\nB) Mars\nIt appears red because of iron oxide.";
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(apply("raw", RESPONSE), RESPONSE);
+        assert_eq!(apply("clean", RESPONSE), "B) Mars\nIt appears red because of iron oxide.");
+        assert_eq!(apply("answer_only", RESPONSE), "B) Mars");
+    }
+
+    #[test]
+    fn test_custom_template() {
+        assert_eq!(apply("Answer: {letter} — {text}", RESPONSE), "Answer: B — Mars");
+        assert_eq!(apply("{answer}\\n({explanation})", RESPONSE), "B) Mars\n(It appears red because of iron oxide.)");
+        assert_eq!(apply("Answer: {letter}", "True\nWater boils at 100C at sea level."), "Answer: True");
+    }
+
+    #[test]
+    fn test_sources_line_survives_template() {
+        let response = format!("{}\n\nSources: [notes/week3.md §2]", RESPONSE);
+        assert_eq!(apply("answer_only", &response), "B) Mars\nSources: [notes/week3.md §2]");
+        assert_eq!(apply("raw", &response), response);
+    }
+
+    #[test]
+    fn test_errors_pass_through() {
+        let error = "[System Warning: x]\n\n[FATAL ERROR]\nAI Error: timeout";
+        assert_eq!(apply("answer_only", error), error);
+    }
+}
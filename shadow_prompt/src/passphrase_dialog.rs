@@ -0,0 +1,68 @@
+//! Passphrase Dialog
+//! Small startup window that asks for the index passphrase (`[rag] encrypt_index`).
+//! Runs on the main thread before the overlay and hotkeys start, like the setup wizard.
+
+use eframe::egui;
+use std::sync::{Arc, Mutex};
+
+struct PassphraseDialog {
+    message: String,
+    input: String,
+    result: Arc<Mutex<Option<String>>>,
+}
+
+impl eframe::App for PassphraseDialog {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label(&self.message);
+            ui.add_space(6.0);
+            let field = ui.add(egui::TextEdit::singleline(&mut self.input).password(true).desired_width(f32::INFINITY));
+            field.request_focus();
+            ui.add_space(6.0);
+
+            let entered = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.horizontal(|ui| {
+                if ui.button("Unlock").clicked() || entered {
+                    *self.result.lock().unwrap() = Some(std::mem::take(&mut self.input));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+                if ui.button("Skip (no knowledge base)").clicked() {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            });
+        });
+    }
+}
+
+/// Blocks until the window is closed; None if it was skipped or closed
+pub fn prompt(message: &str) -> Option<String> {
+    let result = Arc::new(Mutex::new(None));
+    let dialog = PassphraseDialog {
+        message: message.to_string(),
+        input: String::new(),
+        result: result.clone(),
+    };
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([360.0, 110.0])
+            .with_title("ShadowPrompt")
+            .with_resizable(false)
+            .with_always_on_top(),
+        // Return to the caller instead of exiting; a wrong passphrase opens the dialog again
+        run_and_return: true,
+        ..Default::default()
+    };
+
+    let _ = eframe::run_native(
+        "ShadowPrompt Passphrase",
+        options,
+        Box::new(|cc| {
+            cc.egui_ctx.set_visuals(egui::Visuals::dark());
+            Ok(Box::new(dialog))
+        }),
+    );
+
+    let entered = result.lock().unwrap().take();
+    entered
+}
@@ -0,0 +1,233 @@
+//! Query Pipeline
+//! Shared path from a `QueryRequest` to an answer: context gathering, LLM call,
+//! and writing the result to the clipboard/overlay.
+
+use crate::analytics::{self, Event};
+use crate::app_state::JobUi;
+use crate::answer_cache;
+use crate::clipboard::ClipboardManager;
+use crate::config::Config;
+use crate::i18n::tf;
+use crate::knowledge::{split_filters, GatheredContext, KnowledgeProvider};
+use crate::llm::LlmClient;
+use crate::logger;
+use crate::output_format;
+use crate::source::{QueryRequest, QuerySource};
+use crate::ui::{IndicatorState, UICommand};
+use crate::utils::{parse_question_type, truncate_input};
+use std::time::{Duration, Instant};
+
+const VISION_PROMPT: &str = "Analyze the image. If there are questions, answer them directly and concisely. Provide all correct options if it is a multiple-choice question. If it is a matching or matrix question, clearly provide all pairings and answers.";
+
+pub struct QueryPipeline;
+
+impl QueryPipeline {
+    /// Answer the request and publish the result, then reset the indicator to ready.
+    /// Runs as an `AppState` job: once superseded, nothing reaches the clipboard or indicator.
+    /// Log lines are tagged with the caller's query ID, or a new one (stdin/HTTP/extension).
+    pub async fn run(
+        request: QueryRequest,
+        config: &Config,
+        knowledge: &KnowledgeProvider,
+        ui: &JobUi,
+    ) {
+        match logger::query_id() {
+            Some(_) => Self::process(request, config, knowledge, ui).await,
+            None => logger::with_query_id(logger::new_query_id(), Self::process(request, config, knowledge, ui)).await,
+        }
+    }
+
+    async fn process(
+        request: QueryRequest,
+        config: &Config,
+        knowledge: &KnowledgeProvider,
+        ui: &JobUi,
+    ) {
+        let started = Instant::now();
+        let mut request = request;
+        if let Some((before, after)) = Self::guard_input(&mut request, config) {
+            ui.send(UICommand::SetOverlayText(tf(
+                "overlay.input_trimmed",
+                &[("before", &before.to_string()), ("after", &after.to_string())],
+            )));
+        }
+
+        println!("[*] Processing Query: {:.50}...", request.text);
+        info!("Query from {:?}: {}", request.source, logger::content(&request.text));
+        ui.send(UICommand::ResetSecondary);
+        analytics::record(Event::Query);
+        ClipboardManager::remember(&request.text);
+
+        let response = Self::answer(&request, config, knowledge).await;
+        // A newer question took over while this one was answered; leave its clipboard alone
+        if !ui.is_current() {
+            info!("Answer dropped, a newer query is running");
+            return;
+        }
+
+        let output_mode = config.visuals.output_mode.as_str();
+        if output_mode != "toast" {
+            let original = ClipboardManager::read().ok();
+            let output = output_format::apply(&config.visuals.output_template, &response);
+            let written = if config.visuals.formatted_output {
+                ClipboardManager::write_formatted(&output)
+            } else {
+                ClipboardManager::write(&output)
+            };
+            match written {
+                Ok(()) => {
+                    println!("[*] Response written to clipboard.");
+                    info!("[Clipboard] Response written ({} chars)", output.chars().count());
+                    if let Some(original) = original {
+                        schedule_clipboard_restore(config, original, &output);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Clipboard Write Error: {}", e);
+                    error!("[Clipboard] Write failed: {}", e);
+                }
+            }
+        }
+
+        if matches!(output_mode, "toast" | "both") && !response.is_empty() {
+            ui.send(UICommand::ShowToast(short_answer(&response)));
+        }
+
+        if config.visuals.text_overlay_enabled && !response.is_empty() {
+            ui.send(UICommand::SetOverlayText(response.clone()));
+        }
+
+        info!("Answered in {} ms: {}", started.elapsed().as_millis(), logger::content(&response));
+        let question_type = parse_question_type(&response);
+        ui.finish(Some(IndicatorState::from_question_type(question_type)));
+    }
+
+    /// Apply `general.max_input_chars`. Returns (original, kept) char counts when truncated.
+    pub fn guard_input(request: &mut QueryRequest, config: &Config) -> Option<(usize, usize)> {
+        let truncated = truncate_input(&request.text, config.general.max_input_chars)?;
+        let before = request.text.chars().count();
+        let after = truncated.chars().count();
+        eprintln!("[!] Input truncated from {} to {} chars", before, after);
+        info!("Input truncated from {} to {} chars", before, after);
+        request.text = truncated;
+        Some((before, after))
+    }
+
+    pub async fn answer(request: &QueryRequest, config: &Config, knowledge: &KnowledgeProvider) -> String {
+        // 1. Vision first when a screenshot is attached
+        if let Some(image) = &request.image_base64 {
+            match LlmClient::query_with_image(VISION_PROMPT, image, config).await {
+                Ok(response) => {
+                    println!("[+] Vision query success");
+                    return response;
+                }
+                Err(e) => {
+                    eprintln!("[-] Vision query failed: {}, falling back to text...", e);
+                }
+            }
+        }
+
+        let names = knowledge.filter_names().await;
+        let (filter, prompt) = split_filters(&request.text, &names, config);
+
+        // Same question as earlier this session, asked the same way of the same model
+        let cache_scope = answer_cache::Scope::new(answer_cache::Caller::Hotkey, config, &format!("{:?}", filter));
+        if config.general.answer_cache {
+            if let Some(cached) = answer_cache::get(&cache_scope, &request.text) {
+                println!("[+] Answered from the session cache");
+                return cached;
+            }
+        }
+
+        // 2. Gather Context (Search/RAG)
+        let GatheredContext { text: context, warnings, sources, links } = match knowledge.gather_context(prompt, &filter, config).await {
+            Ok(gathered) => gathered,
+            Err(e) => {
+                let err_msg = format!("Knowledge System Error: {}", e);
+                error!("{}", err_msg);
+                analytics::record(Event::Error("knowledge"));
+                GatheredContext { warnings: vec![err_msg], ..Default::default() }
+            }
+        };
+
+        let augmented_prompt = if !context.is_empty() {
+            info!("[*] Context found. Augmenting prompt.");
+            format!("Context:\n{}\nQuestion:\n{}", context, prompt)
+        } else {
+            prompt.to_string()
+        };
+
+        // 3. Query LLM
+        let mut final_output = String::new();
+
+        for warning in warnings {
+            final_output.push_str(&format!("[System Warning: {}]\n\n", warning));
+        }
+
+        match LlmClient::query(&augmented_prompt, config).await {
+            Ok(res) => {
+                println!("[+] LLM query success");
+                if config.general.answer_cache {
+                    answer_cache::insert(&cache_scope, &request.text, &res);
+                }
+                final_output.push_str(&res);
+                let mut cited: Vec<String> = Vec::new();
+                if config.rag.append_sources {
+                    cited.extend(sources);
+                }
+                if config.search.append_sources {
+                    cited.extend(links);
+                }
+                if !cited.is_empty() {
+                    final_output.push_str(&format!("\n\n{}{}", output_format::SOURCES_PREFIX, cited.join(" ")));
+                }
+            }
+            Err(e) => {
+                let err_msg = format!("AI Error: {}", e);
+                error!("{}", err_msg);
+                analytics::record(Event::Error("llm"));
+                // Recognized screen text is still worth having on the clipboard
+                if request.source == QuerySource::Ocr {
+                    return request.text.clone();
+                }
+                final_output.push_str(&format!("[FATAL ERROR]\n{}", err_msg));
+            }
+        };
+
+        final_output
+    }
+}
+
+/// Arm `visuals.clipboard_restore`; "after_paste" is completed by the main loop on Ctrl+V
+fn schedule_clipboard_restore(config: &Config, original: String, written: &str) {
+    if !matches!(config.visuals.clipboard_restore.as_str(), "after_paste" | "timeout") || original == written {
+        return;
+    }
+
+    let generation = ClipboardManager::arm_restore(original, written);
+    let delay = Duration::from_secs(config.visuals.clipboard_restore_secs.max(1));
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        match ClipboardManager::restore_pending(Some(generation)) {
+            Ok(true) => println!("[*] Original clipboard restored."),
+            Ok(false) => {}
+            Err(e) => eprintln!("[-] Clipboard restore failed: {}", e),
+        }
+    });
+}
+
+/// First answer line without system warnings, trimmed to fit a toast
+fn short_answer(response: &str) -> String {
+    const MAX_CHARS: usize = 200;
+    let line = response
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("[System Warning"))
+        .unwrap_or("");
+
+    if line.chars().count() > MAX_CHARS {
+        format!("{}...", line.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
@@ -0,0 +1,157 @@
+//! Provider Health
+//! Rolling success rate and latency per provider for the current session.
+//! Auto mode asks for `fallback_order()` so a provider that keeps failing or
+//! timing out stops being tried first on every query.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Provider {
+    Groq,
+    OpenRouter,
+    Ollama,
+}
+
+impl Provider {
+    /// Default priority: Groq (fastest, free tier) -> OpenRouter -> Ollama (local)
+    const ALL: [Provider; 3] = [Provider::Groq, Provider::OpenRouter, Provider::Ollama];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Provider::Groq => "Groq",
+            Provider::OpenRouter => "OpenRouter",
+            Provider::Ollama => "Ollama",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Weight of the newest sample in the moving averages
+const SMOOTHING: f64 = 0.3;
+/// One second of average latency costs this much score (success rate is 0.0..=1.0)
+const LATENCY_PENALTY_PER_SEC: f64 = 0.02;
+/// A provider must beat the one ahead of it by this much before they swap places
+const HYSTERESIS: f64 = 0.15;
+
+#[derive(Debug, Clone, Copy)]
+struct Stats {
+    success_rate: f64,
+    latency_secs: f64,
+    samples: u32,
+}
+
+impl Stats {
+    const fn new() -> Self {
+        // Optimistic prior so untried providers keep their default position
+        Self {
+            success_rate: 1.0,
+            latency_secs: 0.0,
+            samples: 0,
+        }
+    }
+
+    fn record(&mut self, success: bool, latency: Duration) {
+        let outcome = if success { 1.0 } else { 0.0 };
+        let secs = latency.as_secs_f64();
+        if self.samples == 0 {
+            self.success_rate = outcome;
+            self.latency_secs = secs;
+        } else {
+            self.success_rate += SMOOTHING * (outcome - self.success_rate);
+            self.latency_secs += SMOOTHING * (secs - self.latency_secs);
+        }
+        self.samples += 1;
+    }
+
+    fn score(&self) -> f64 {
+        self.success_rate - self.latency_secs * LATENCY_PENALTY_PER_SEC
+    }
+}
+
+struct HealthTable {
+    stats: [Stats; 3],
+    order: [Provider; 3],
+}
+
+impl HealthTable {
+    const fn new() -> Self {
+        Self {
+            stats: [Stats::new(); 3],
+            order: Provider::ALL,
+        }
+    }
+
+    fn record(&mut self, provider: Provider, success: bool, latency: Duration) {
+        self.stats[provider.index()].record(success, latency);
+        self.reorder();
+    }
+
+    /// Insertion pass that only moves a provider ahead when it wins by more than `HYSTERESIS`,
+    /// so two providers with similar scores don't flip-flop between queries.
+    fn reorder(&mut self) {
+        let previous = self.order;
+        for i in 1..self.order.len() {
+            let mut j = i;
+            while j > 0 && self.score(self.order[j]) > self.score(self.order[j - 1]) + HYSTERESIS {
+                self.order.swap(j, j - 1);
+                j -= 1;
+            }
+        }
+        if self.order != previous {
+            info!(
+                "[Health] Fallback order is now: {}",
+                self.order.iter().map(|p| p.name()).collect::<Vec<_>>().join(" -> ")
+            );
+        }
+    }
+
+    fn score(&self, provider: Provider) -> f64 {
+        self.stats[provider.index()].score()
+    }
+}
+
+static HEALTH: Mutex<HealthTable> = Mutex::new(HealthTable::new());
+
+pub fn record(provider: Provider, success: bool, latency: Duration) {
+    if let Ok(mut table) = HEALTH.lock() {
+        table.record(provider, success, latency);
+    }
+}
+
+pub fn fallback_order() -> [Provider; 3] {
+    HEALTH.lock().map(|table| table.order).unwrap_or(Provider::ALL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failing_provider_moves_back() {
+        let mut table = HealthTable::new();
+        let fast = Duration::from_millis(400);
+
+        table.record(Provider::Groq, true, fast);
+        table.record(Provider::OpenRouter, true, fast);
+        assert_eq!(table.order, Provider::ALL);
+
+        for _ in 0..3 {
+            table.record(Provider::Groq, false, Duration::from_secs(15));
+        }
+        assert_eq!(table.order[0], Provider::OpenRouter);
+    }
+
+    #[test]
+    fn test_hysteresis_prevents_flip_flop() {
+        let mut table = HealthTable::new();
+
+        // OpenRouter is slightly faster, but not by enough to take the lead
+        table.record(Provider::Groq, true, Duration::from_secs(2));
+        table.record(Provider::OpenRouter, true, Duration::from_secs(1));
+        assert_eq!(table.order, Provider::ALL);
+    }
+}
@@ -0,0 +1,167 @@
+//! Remote Config
+//! With `general.remote_config_url`, a TOML snippet is fetched at startup and merged over
+//! the local config, so several portable copies can get hotkey/display changes from one
+//! place. The snippet must match `general.remote_config_sha256`, only the settings in
+//! `MERGEABLE` are taken from it, and the last good copy is kept in
+//! data/remote_config.toml for offline starts.
+
+use crate::config::{get_exe_dir, merge_tables, Config};
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+pub const CACHE_FILE: &str = "remote_config.toml";
+
+/// What a snippet may set: a whole section (`None`) or only the listed keys. Providers, API
+/// keys, URLs, paths, `[browser]` and `[safety]` always stay local.
+const MERGEABLE: &[(&str, Option<&[&str]>)] = &[
+    ("general", None),
+    ("visuals", None),
+    ("search", Some(&["enabled", "max_results", "engine", "fetch_pages", "page_chars", "recency", "append_sources", "wikipedia", "wikipedia_lang"])),
+    ("rag", Some(&["enabled", "max_results", "min_score", "append_sources", "default_collection", "url_refresh_hours"])),
+];
+
+/// `[general]` keys a snippet can't set: where the config comes from, and the user's own ToS acceptance
+const LOCAL_GENERAL: &[&str] = &["remote_config_url", "remote_config_sha256", "tos_accepted", "tos_accepted_version"];
+
+/// The config with the remote snippet (or its cached copy) merged in; unchanged when no
+/// URL is set or nothing usable is available
+pub async fn apply(config: Config) -> Config {
+    let Some(url) = config.general.remote_config_url.clone().filter(|u| !u.trim().is_empty()) else {
+        return config;
+    };
+    let Some(expected) = config.general.remote_config_sha256.clone().filter(|h| !h.trim().is_empty()) else {
+        eprintln!("[!] remote_config_url is set without remote_config_sha256; remote config ignored");
+        log::error!("Remote config ignored: remote_config_sha256 is not set");
+        return config;
+    };
+    let cache_path = get_exe_dir().join("data").join(CACHE_FILE);
+
+    let snippet = match fetch(url.trim(), &expected).await {
+        Ok(snippet) => {
+            if let Err(e) = std::fs::write(&cache_path, &snippet) {
+                eprintln!("[-] Could not cache the remote config: {}", e);
+            }
+            Some(snippet)
+        }
+        Err(e) => {
+            eprintln!("[-] Remote config unavailable ({}), using the cached copy", e);
+            log::warn!("Remote config unavailable: {}", e);
+            // The cache is checked too, so a pin change or an edited file isn't trusted
+            std::fs::read_to_string(&cache_path).ok().filter(|cached| verify(cached, &expected).is_ok())
+        }
+    };
+    let Some(snippet) = snippet else { return config };
+
+    match merge(&config, &snippet) {
+        Ok(merged) => {
+            println!("[*] Remote config applied from {}", url);
+            merged
+        }
+        Err(e) => {
+            eprintln!("[!] Remote config ignored: {}", e);
+            log::error!("Remote config ignored: {}", e);
+            config
+        }
+    }
+}
+
+async fn fetch(url: &str, expected: &str) -> Result<String> {
+    let parsed = reqwest::Url::parse(url).context("not a valid URL")?;
+    if parsed.scheme() != "https" {
+        anyhow::bail!("only https:// URLs are fetched");
+    }
+    // https_only also refuses a redirect to plain http
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).https_only(true).build()?;
+    let body = client.get(parsed).send().await?.error_for_status()?.text().await?;
+    // Only cache what matches and parses, so a broken or swapped upload doesn't replace the last good copy
+    verify(&body, expected)?;
+    body.parse::<toml::Table>().context("not valid TOML")?;
+    Ok(body)
+}
+
+/// Err unless `snippet` hashes to the pinned `expected` SHA-256
+fn verify(snippet: &str, expected: &str) -> Result<()> {
+    let actual: String = Sha256::digest(snippet.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        anyhow::bail!("SHA-256 is {}, not the pinned remote_config_sha256", actual);
+    }
+    Ok(())
+}
+
+/// The part of `overlay` that `MERGEABLE` lets through; what was dropped is logged
+fn allowed(overlay: toml::Table) -> toml::Table {
+    let mut kept = toml::Table::new();
+    for (section, value) in overlay {
+        let rule = MERGEABLE.iter().find(|(name, _)| *name == section).map(|(_, keys)| *keys);
+        let (Some(keys), toml::Value::Table(table)) = (rule, value) else {
+            log::warn!("Remote config: [{}] is not taken from the remote snippet", section);
+            continue;
+        };
+        let mut section_kept = toml::Table::new();
+        for (key, value) in table {
+            let local = section == "general" && LOCAL_GENERAL.contains(&key.as_str());
+            if local || keys.is_some_and(|keys| !keys.contains(&key.as_str())) {
+                log::warn!("Remote config: {}.{} is not taken from the remote snippet", section, key);
+                continue;
+            }
+            section_kept.insert(key, value);
+        }
+        kept.insert(section, toml::Value::Table(section_kept));
+    }
+    kept
+}
+
+fn merge(config: &Config, snippet: &str) -> Result<Config> {
+    let overlay: toml::Table = snippet.parse().context("not valid TOML")?;
+    // No `${VAR}` expansion: the snippet's author must not be able to read local variables
+    let overlay = allowed(overlay);
+
+    let toml::Value::Table(mut table) = toml::Value::try_from(config).context("Failed to serialize config")? else {
+        anyhow::bail!("config did not serialize to a table");
+    };
+    merge_tables(&mut table, overlay);
+    let mut merged: Config = toml::Value::Table(table).try_into().context("a value has the wrong type")?;
+    merged.visuals.apply_color_scheme();
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_keeps_local_url() {
+        let mut config = Config::default();
+        config.general.remote_config_url = Some("https://example.com/shadow.toml".to_string());
+        let merged = merge(&config, "[general]\nwake_key = \"Ctrl+Alt+Space\"\nremote_config_url = \"https://evil.example\"\n[search]\nenabled = false").unwrap();
+        assert_eq!(merged.general.wake_key, "Ctrl+Alt+Space");
+        assert!(!merged.search.enabled);
+        assert_eq!(merged.general.remote_config_url.as_deref(), Some("https://example.com/shadow.toml"));
+
+        assert!(merge(&config, "[general]\nwake_key = 5").is_err());
+        assert!(merge(&config, "not toml").is_err());
+    }
+
+    #[test]
+    fn test_merge_ignores_paths_urls_and_keys() {
+        let config = Config::default();
+        let snippet = "[browser]\nbinary_path = \"C:/evil.exe\"\n[models]\nprovider = \"openrouter\"\n[rag]\nknowledge_path = \"C:/\"\nmin_score = 0.25\n[search]\nserper_api_key = \"${GROQ_API_KEY}\"\nsearxng_url = \"http://evil.example\"";
+        let merged = merge(&config, snippet).unwrap();
+        assert_eq!(merged.browser.binary_path, config.browser.binary_path);
+        assert_eq!(merged.models.provider, config.models.provider);
+        assert_eq!(merged.rag.knowledge_path, config.rag.knowledge_path);
+        assert_eq!(merged.rag.min_score, 0.25);
+        assert_eq!(merged.search.serper_api_key, config.search.serper_api_key);
+        assert_eq!(merged.search.searxng_url, config.search.searxng_url);
+    }
+
+    #[test]
+    fn test_verify() {
+        // SHA-256 of "abc"
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert!(verify("abc", hash).is_ok());
+        assert!(verify("abc", &format!(" {} ", hash.to_uppercase())).is_ok());
+        assert!(verify("abd", hash).is_err());
+    }
+}
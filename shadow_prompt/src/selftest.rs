@@ -0,0 +1,164 @@
+//! Self-Test
+//! `--self-test` exercises every subsystem once and prints a pass/fail report,
+//! so a new machine can be verified before it is relied on.
+
+use crate::clipboard::ClipboardManager;
+use crate::config::{get_exe_dir, Config};
+use crate::input::HotkeyBindings;
+use crate::knowledge::rag::RagSystem;
+use crate::llm::LlmClient;
+use crate::ocr::{render_text_pixels, OcrManager};
+use crate::ui::UIManager;
+use anyhow::Result;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+const OCR_TEST_TEXT: &str = "SHADOW PROMPT 42";
+const SAMPLE_DOC: &str = "The mitochondria is the powerhouse of the cell. It produces ATP through cellular respiration.";
+const SAMPLE_QUERY: &str = "What produces ATP in the cell?";
+
+struct Report {
+    passed: usize,
+    failed: usize,
+}
+
+impl Report {
+    fn record(&mut self, name: &str, started: Instant, result: Result<String>) {
+        let elapsed = started.elapsed().as_millis();
+        match result {
+            Ok(detail) => {
+                self.passed += 1;
+                println!("[PASS] {:<22} {} ({} ms)", name, detail, elapsed);
+            }
+            Err(e) => {
+                self.failed += 1;
+                println!("[FAIL] {:<22} {} ({} ms)", name, e, elapsed);
+                error!("Self-test '{}' failed: {}", name, e);
+            }
+        }
+    }
+
+    fn check(&mut self, name: &str, f: impl FnOnce() -> Result<String>) {
+        let started = Instant::now();
+        self.record(name, started, f());
+    }
+
+    async fn check_async(&mut self, name: &str, f: impl Future<Output = Result<String>>) {
+        let started = Instant::now();
+        let result = f.await;
+        self.record(name, started, result);
+    }
+}
+
+/// Run all checks. Returns `true` when every check passed.
+pub async fn run(config: &Config) -> bool {
+    println!("[*] ShadowPrompt Self-Test");
+    println!("[*] Provider: {}\n", config.models.provider);
+
+    let mut report = Report { passed: 0, failed: 0 };
+
+    report.check("Hotkey bindings", || check_bindings(config));
+    report.check("Keyboard hook", || {
+        crate::input::probe_hook(Duration::from_millis(500))?;
+        Ok("global hook registered".to_string())
+    });
+    report.check("Overlay window", || {
+        UIManager::probe_window()?;
+        Ok("layered window created".to_string())
+    });
+    report.check_async("OCR", check_ocr()).await;
+    report.check("Clipboard round-trip", check_clipboard);
+
+    let providers: Vec<&str> = if config.models.provider == "auto" {
+        vec!["groq", "openrouter", "ollama"]
+    } else {
+        vec![config.models.provider.as_str()]
+    };
+    for provider in providers {
+        let name = format!("Provider ({})", provider);
+        report
+            .check_async(&name, async {
+                let reply = LlmClient::test_provider(provider, config).await?;
+                Ok(format!("replied \"{:.20}\"", reply.trim()))
+            })
+            .await;
+    }
+
+    report.check_async("RAG", check_rag(config)).await;
+
+    println!("\n[*] {} passed, {} failed", report.passed, report.failed);
+    report.failed == 0
+}
+
+fn check_bindings(config: &Config) -> Result<String> {
+    if let Some(problem) = HotkeyBindings::invalid(config).into_iter().next() {
+        anyhow::bail!("{}", problem);
+    }
+
+    if let Err(e) = crate::hotkey_recorder::validate_hotkeys(config) {
+        anyhow::bail!("{}", e);
+    }
+
+    Ok("all bindings parse".to_string())
+}
+
+async fn check_ocr() -> Result<String> {
+    let (width, height) = (480, 80);
+    let pixels = render_text_pixels(OCR_TEST_TEXT, width, height)?;
+    let text = OcrManager::recognize_pixels(&pixels, width, height).await?;
+
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase();
+    if !normalized.contains(OCR_TEST_TEXT) {
+        anyhow::bail!("expected \"{}\", got \"{}\"", OCR_TEST_TEXT, text.trim());
+    }
+    Ok(format!("read \"{}\"", text.trim()))
+}
+
+fn check_clipboard() -> Result<String> {
+    let original = ClipboardManager::read().ok();
+    let marker = format!("shadowprompt-self-test-{}", std::process::id());
+
+    ClipboardManager::write(&marker)?;
+    let read_back = ClipboardManager::read();
+
+    // Put the user's clipboard back before judging the result
+    match &original {
+        Some(text) => ClipboardManager::write(text)?,
+        None => ClipboardManager::clear()?,
+    }
+
+    if read_back? != marker {
+        anyhow::bail!("read back different text than was written");
+    }
+    Ok("write/read matched".to_string())
+}
+
+async fn check_rag(config: &Config) -> Result<String> {
+    let test_dir = get_exe_dir().join("data").join("selftest");
+    let knowledge_dir = test_dir.join("knowledge");
+    std::fs::create_dir_all(&knowledge_dir)?;
+    std::fs::write(knowledge_dir.join("sample.md"), SAMPLE_DOC)?;
+
+    let mut test_config = config.clone();
+    test_config.rag.enabled = true;
+    test_config.rag.knowledge_path = "data/selftest/knowledge".to_string();
+    test_config.rag.index_path = "data/selftest/index.json".to_string();
+    test_config.rag.min_score = 0.0;
+
+    let result = async {
+        let rag = RagSystem::new(&test_config).await;
+        if let Some(e) = rag.get_init_error() {
+            anyhow::bail!("embedding model unavailable: {}", e);
+        }
+        let count = rag.ingest().await?;
+        let results = rag.query(SAMPLE_QUERY, &Default::default()).await?;
+        if !results.iter().any(|passage| passage.content.contains("ATP")) {
+            anyhow::bail!("sample document not returned ({} indexed)", count);
+        }
+        Ok(format!("{} document(s) indexed, sample retrieved", count))
+    }
+    .await;
+
+    let _ = std::fs::remove_dir_all(&test_dir);
+    result
+}
@@ -1,11 +1,15 @@
 use std::sync::mpsc::{self, Receiver};
 use eframe::egui;
-use crate::config::Config;
+use crate::config::{Config, COLOR_SCHEMES};
 use crate::tos_text::{TOS_TEXT, TOS_VERSION};
 use crate::hotkey_recorder::{HotkeyRecorder, hotkey_field, validate_hotkeys};
 use crate::color_picker::{color_picker, color_picker_compact};
 use std::path::Path;
 use crate::llm::LlmClient;
+use crate::input::{HotkeyBindings, InputBackend, InputEvent, InputManager};
+use std::collections::HashSet;
+use crate::i18n::{t, tf};
+use crate::ocr::{OcrLanguageStatus, OcrManager, LANGUAGE_SETTINGS_URI, OCR_INSTALL_HINT};
 
 // --- Helper function to test provider connectivity ---
 fn test_provider_sync(provider: &str, config: &Config) -> Result<String, String> {
@@ -33,8 +37,10 @@ enum SetupPage {
     LLMProvider,
     Features,
     Hotkeys,
+    HotkeyTest,
     Visuals,
     Downloads,
+    Knowledge,
     Credits,
 }
 
@@ -46,25 +52,29 @@ impl SetupPage {
             SetupPage::LLMProvider => 3,
             SetupPage::Features => 4,
             SetupPage::Hotkeys => 5,
-            SetupPage::Visuals => 6,
-            SetupPage::Downloads => 7,
-            SetupPage::Credits => 8,
+            SetupPage::HotkeyTest => 6,
+            SetupPage::Visuals => 7,
+            SetupPage::Downloads => 8,
+            SetupPage::Knowledge => 9,
+            SetupPage::Credits => 10,
         }
     }
 
-    fn total() -> usize { 8 }
-
-    fn title(&self) -> &'static str {
-        match self {
-            SetupPage::Landing => "Welcome",
-            SetupPage::TermsOfService => "Terms of Service",
-            SetupPage::LLMProvider => "LLM Provider",
-            SetupPage::Features => "Features",
-            SetupPage::Hotkeys => "Hotkey Configuration",
-            SetupPage::Visuals => "Visual Preferences",
-            SetupPage::Downloads => "Modules & Models",
-            SetupPage::Credits => "Credits",
-        }
+    fn total() -> usize { 10 }
+
+    fn title(&self) -> String {
+        t(match self {
+            SetupPage::Landing => "wizard.page.welcome",
+            SetupPage::TermsOfService => "wizard.page.terms_of_service",
+            SetupPage::LLMProvider => "wizard.page.llm_provider",
+            SetupPage::Features => "wizard.page.features",
+            SetupPage::Hotkeys => "wizard.page.hotkey_configuration",
+            SetupPage::HotkeyTest => "wizard.page.hotkey_test",
+            SetupPage::Visuals => "wizard.page.visual_preferences",
+            SetupPage::Downloads => "wizard.page.modules_models",
+            SetupPage::Knowledge => "wizard.page.knowledge_base",
+            SetupPage::Credits => "wizard.page.credits",
+        })
     }
 }
 
@@ -91,6 +101,141 @@ impl ProviderState {
     }
 }
 
+// --- Model Picker ---
+
+/// A provider's model list, fetched in the background once its key (or Ollama URL) is entered
+#[derive(Default)]
+struct ModelPicker {
+    models: Vec<String>,
+    filter: String,
+    status: String,
+    rx: Option<Receiver<Result<Vec<String>, String>>>,
+    fetched_for: Option<String>,
+}
+
+impl ModelPicker {
+    /// Fetch the list for a new credential; waits until the key field loses focus so a key
+    /// being typed isn't sent on every keystroke
+    fn request(&mut self, provider: &'static str, credential: &str, editing: bool) {
+        if let Some(rx) = &self.rx {
+            match rx.try_recv() {
+                Ok(Ok(models)) => {
+                    self.status = tf("wizard.provider.models_available", &[("count", &models.len().to_string())]);
+                    self.models = models;
+                    self.rx = None;
+                }
+                Ok(Err(e)) => {
+                    self.status = tf("wizard.provider.models_failed", &[("error", &e)]);
+                    self.models.clear();
+                    self.rx = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => self.rx = None,
+            }
+        }
+        let credential = credential.trim();
+        if editing || credential.is_empty() || self.fetched_for.as_deref() == Some(credential) {
+            return;
+        }
+        self.fetched_for = Some(credential.to_string());
+        self.status = t("wizard.provider.loading_models");
+        let (tx, rx) = mpsc::channel();
+        self.rx = Some(rx);
+        let credential = credential.to_string();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let credential = crate::config::resolve_value(&credential);
+            let result = rt.block_on(LlmClient::list_models(provider, &credential)).map_err(|e| e.to_string());
+            let _ = tx.send(result);
+        });
+    }
+
+    /// Searchable dropdown of the fetched IDs; a free-text field until the list is in
+    fn show(&mut self, ui: &mut egui::Ui, id: &str, model_id: &mut String) {
+        ui.horizontal(|ui| {
+            ui.label(t("wizard.provider.model_label"));
+            if self.models.is_empty() {
+                ui.add(egui::TextEdit::singleline(model_id).desired_width(200.0));
+                return;
+            }
+            egui::ComboBox::from_id_salt(id)
+                .selected_text(model_id.as_str())
+                .width(250.0)
+                // Clicking into the search box must not close the list
+                .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+                .show_ui(ui, |ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.filter).hint_text(t("wizard.provider.model_search")).desired_width(230.0));
+                    let filter = self.filter.to_lowercase();
+                    egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
+                        for model in self.models.iter().filter(|m| m.to_lowercase().contains(&filter)) {
+                            ui.selectable_value(model_id, model.clone(), model);
+                        }
+                    });
+                });
+        });
+        if !self.models.is_empty() && !self.models.contains(model_id) {
+            ui.colored_label(egui::Color32::YELLOW, tf("wizard.provider.model_not_offered", &[("model", model_id.as_str())]));
+        } else if !self.status.is_empty() {
+            ui.label(egui::RichText::new(&self.status).color(egui::Color32::GRAY).small());
+        }
+        if self.rx.is_some() {
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(200));
+        }
+    }
+}
+
+// --- Hotkey Test ---
+
+/// The real global hook, started on the first visit to the test page and re-bound on later ones
+struct HotkeyTest {
+    events: Receiver<InputEvent>,
+    rebind: mpsc::Sender<HotkeyBindings>,
+    confirmed: HashSet<&'static str>,
+}
+
+/// Label keys for `HotkeyBindings::named`, as on the Hotkeys page
+const HOTKEY_LABELS: &[(&str, &str)] = &[
+    ("wake_key", "wizard.hotkeys.wake"),
+    ("model_key", "wizard.hotkeys.model"),
+    ("panic_key", "wizard.hotkeys.panic"),
+    ("hide_key", "wizard.hotkeys.hide"),
+    ("key_clipboard_cycle", "wizard.hotkeys.clipboard_cycle"),
+    ("key_rag_capture", "wizard.hotkeys.rag_capture"),
+    ("key_browser_pass", "wizard.hotkeys.browser_pass"),
+    ("key_browser_exec", "wizard.hotkeys.browser_exec"),
+    ("key_browser_exec_single", "wizard.hotkeys.browser_exec_single"),
+    ("key_browser_abort", "wizard.hotkeys.browser_abort"),
+    ("key_browser_incognito", "wizard.hotkeys.browser_incognito"),
+    ("key_browser_pause", "wizard.hotkeys.browser_pause"),
+    ("key_log_viewer", "wizard.hotkeys.log_viewer"),
+    ("key_log_dump", "wizard.hotkeys.log_dump"),
+];
+
+// --- Theme ---
+
+const MIN_SCALE: f32 = 0.75;
+const MAX_SCALE: f32 = 2.0;
+const SCALE_STEP: f32 = 0.125;
+
+fn wizard_visuals(light: bool, high_contrast: bool) -> egui::Visuals {
+    let mut visuals = if light { egui::Visuals::light() } else { egui::Visuals::dark() };
+    if high_contrast {
+        let (fg, bg) = if light { (egui::Color32::BLACK, egui::Color32::WHITE) } else { (egui::Color32::WHITE, egui::Color32::BLACK) };
+        visuals.override_text_color = Some(fg);
+        visuals.panel_fill = bg;
+        visuals.window_fill = bg;
+        visuals.extreme_bg_color = bg;
+        visuals.faint_bg_color = bg;
+        let widgets = &mut visuals.widgets;
+        for state in [&mut widgets.noninteractive, &mut widgets.inactive, &mut widgets.hovered, &mut widgets.active, &mut widgets.open] {
+            state.fg_stroke = egui::Stroke::new(2.0, fg);
+            state.bg_stroke = egui::Stroke::new(1.5, fg);
+        }
+        visuals.selection.stroke = egui::Stroke::new(2.0, fg);
+    }
+    visuals
+}
+
 // --- Main Wizard Struct ---
 
 pub struct SetupWizard {
@@ -102,6 +247,9 @@ pub struct SetupWizard {
 
     // Provider
     provider_state: ProviderState,
+    groq_models: ModelPicker,
+    openrouter_models: ModelPicker,
+    ollama_models: ModelPicker,
 
     // Hotkeys
     wake_recorder: HotkeyRecorder,
@@ -113,7 +261,18 @@ pub struct SetupWizard {
     browser_exec_single_recorder: HotkeyRecorder,
     browser_abort_recorder: HotkeyRecorder,
     browser_incognito_recorder: HotkeyRecorder,
+    browser_pause_recorder: HotkeyRecorder,
+    clipboard_cycle_recorder: HotkeyRecorder,
+    rag_capture_recorder: HotkeyRecorder,
+    log_dump_recorder: HotkeyRecorder,
     hotkey_error: Option<String>,
+    hotkey_test: Option<HotkeyTest>,
+
+    // Theme last applied to the context: (theme, scale, high contrast)
+    applied_theme: Option<(String, f32, bool)>,
+
+    // OCR
+    ocr_status: Result<OcrLanguageStatus, String>,
 
     // Downloads
     downloading: bool,
@@ -122,12 +281,29 @@ pub struct SetupWizard {
     download_rx: Option<Receiver<(f32, String)>>,
     download_success: bool,
 
+    // Knowledge
+    knowledge_files: Option<(String, usize)>,
+    ingesting: bool,
+    ingest_progress: f32,
+    ingest_status: String,
+    ingest_rx: Option<Receiver<(f32, String)>>,
+
     finished: bool,
+
+    // --settings: edit single pages of an existing setup
+    settings_mode: bool,
+    settings_status: Option<String>,
 }
 
+/// Pages `--settings` can open on their own
+const SETTINGS_PAGES: [SetupPage; 5] = [SetupPage::LLMProvider, SetupPage::Features, SetupPage::Hotkeys, SetupPage::Visuals, SetupPage::Knowledge];
+
 impl SetupWizard {
     pub fn new() -> Self {
-        let config = Config::load().unwrap_or_default();
+        let config = Config::load_raw().unwrap_or_default();
+        crate::i18n::set_language(&config.general.language);
+        // Mask the keys themselves, not their ${VAR}/enc: placeholders
+        crate::logger::configure(&config.resolved());
         let provider_state = ProviderState::from_config(&config);
 
         Self {
@@ -135,6 +311,9 @@ impl SetupWizard {
             config,
             tos_accepted: false,
             provider_state,
+            groq_models: ModelPicker::default(),
+            openrouter_models: ModelPicker::default(),
+            ollama_models: ModelPicker::default(),
             wake_recorder: HotkeyRecorder::new(),
             model_recorder: HotkeyRecorder::new(),
             panic_recorder: HotkeyRecorder::new(),
@@ -144,22 +323,47 @@ impl SetupWizard {
             browser_exec_single_recorder: HotkeyRecorder::new(),
             browser_abort_recorder: HotkeyRecorder::new(),
             browser_incognito_recorder: HotkeyRecorder::new(),
+            browser_pause_recorder: HotkeyRecorder::new(),
+            clipboard_cycle_recorder: HotkeyRecorder::new(),
+            rag_capture_recorder: HotkeyRecorder::new(),
+            log_dump_recorder: HotkeyRecorder::new(),
             hotkey_error: None,
+            hotkey_test: None,
+            applied_theme: None,
+            ocr_status: OcrManager::language_status().map_err(|e| e.to_string()),
             downloading: false,
             download_progress: 0.0,
-            download_status: "Ready to download.".to_string(),
+            download_status: t("wizard.downloads.ready"),
             download_rx: None,
             download_success: false,
+            knowledge_files: None,
+            ingesting: false,
+            ingest_progress: 0.0,
+            ingest_status: String::new(),
+            ingest_rx: None,
             finished: false,
+            settings_mode: false,
+            settings_status: None,
         }
     }
 
+    /// `--settings`: the wizard's pages pre-filled from the current config, one at a time
+    pub fn settings() -> Self {
+        let mut wizard = Self::new();
+        wizard.settings_mode = true;
+        wizard.current_page = SetupPage::LLMProvider;
+        wizard.tos_accepted = wizard.config.general.tos_accepted;
+        wizard
+    }
+
     pub fn show(self) -> bool {
+        let title = t(if self.settings_mode { "wizard.settings_title" } else { "wizard.title" });
+        let scale = self.config.visuals.wizard_scale.clamp(MIN_SCALE, MAX_SCALE);
         let options = eframe::NativeOptions {
             viewport: egui::ViewportBuilder::default()
-                .with_inner_size([520.0, 680.0])
+                .with_inner_size([520.0 * scale, 680.0 * scale])
                 .with_min_inner_size([450.0, 500.0])
-                .with_title("ShadowPrompt Setup")
+                .with_title(title.as_str())
                 .with_resizable(true),
             ..Default::default()
         };
@@ -169,7 +373,6 @@ impl SetupWizard {
             options,
             Box::new(|cc| {
                 egui_extras::install_image_loaders(&cc.egui_ctx);
-                cc.egui_ctx.set_visuals(egui::Visuals::dark());
                 Ok(Box::new(self))
             }),
         );
@@ -186,25 +389,31 @@ impl SetupWizard {
             SetupPage::LLMProvider => self.provider_state.has_at_least_one(),
             SetupPage::Features => true,
             SetupPage::Hotkeys => self.hotkey_error.is_none(),
+            SetupPage::HotkeyTest => self.untested_hotkeys().is_empty(),
             SetupPage::Visuals => true,
-            SetupPage::Downloads => self.download_success,
+            SetupPage::Downloads => self.download_success || !self.config.rag.enabled,
+            SetupPage::Knowledge => !self.ingesting,
             SetupPage::Credits => true,
         }
     }
 
-    fn next_page(&mut self) {
-        // Validate before advancing
-        if self.current_page == SetupPage::Hotkeys {
-            if let Err(e) = validate_hotkeys(
-                &self.config.general.wake_key,
-                &self.config.general.model_key,
-                &self.config.general.panic_key,
-                Some(&self.config.visuals.hide_key),
-            ) {
+    fn check_hotkeys(&mut self) -> bool {
+        match validate_hotkeys(&self.config) {
+            Ok(()) => {
+                self.hotkey_error = None;
+                true
+            }
+            Err(e) => {
                 self.hotkey_error = Some(e);
-                return;
+                false
             }
-            self.hotkey_error = None;
+        }
+    }
+
+    fn next_page(&mut self) {
+        // Validate before advancing
+        if self.current_page == SetupPage::Hotkeys && !self.check_hotkeys() {
+            return;
         }
 
         self.current_page = match self.current_page {
@@ -212,9 +421,15 @@ impl SetupWizard {
             SetupPage::TermsOfService => SetupPage::LLMProvider,
             SetupPage::LLMProvider => SetupPage::Features,
             SetupPage::Features => SetupPage::Hotkeys,
-            SetupPage::Hotkeys => SetupPage::Visuals,
+            SetupPage::Hotkeys => {
+                self.begin_hotkey_test();
+                SetupPage::HotkeyTest
+            }
+            SetupPage::HotkeyTest => SetupPage::Visuals,
             SetupPage::Visuals => SetupPage::Downloads,
+            SetupPage::Downloads if self.config.rag.enabled => SetupPage::Knowledge,
             SetupPage::Downloads => SetupPage::Credits,
+            SetupPage::Knowledge => SetupPage::Credits,
             SetupPage::Credits => SetupPage::Credits,
         };
     }
@@ -226,8 +441,11 @@ impl SetupWizard {
             SetupPage::LLMProvider => SetupPage::TermsOfService,
             SetupPage::Features => SetupPage::LLMProvider,
             SetupPage::Hotkeys => SetupPage::Features,
-            SetupPage::Visuals => SetupPage::Hotkeys,
+            SetupPage::HotkeyTest => SetupPage::Hotkeys,
+            SetupPage::Visuals => SetupPage::HotkeyTest,
             SetupPage::Downloads => SetupPage::Visuals,
+            SetupPage::Knowledge => SetupPage::Downloads,
+            SetupPage::Credits if self.config.rag.enabled => SetupPage::Knowledge,
             SetupPage::Credits => SetupPage::Downloads,
         };
     }
@@ -237,6 +455,8 @@ impl SetupWizard {
 
 impl eframe::App for SetupWizard {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.apply_theme(ctx);
+
         // Poll download status
         if let Some(rx) = &self.download_rx {
             while let Ok((prog, status)) = rx.try_recv() {
@@ -253,29 +473,55 @@ impl eframe::App for SetupWizard {
             }
         }
 
+        // Poll first ingestion
+        if let Some(rx) = &self.ingest_rx {
+            while let Ok((prog, status)) = rx.try_recv() {
+                self.ingest_progress = prog;
+                if prog >= 1.0 || status.starts_with("Error") {
+                    self.ingesting = false;
+                }
+                self.ingest_status = status;
+            }
+            if self.ingesting {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             // --- Header ---
             ui.vertical_centered(|ui| {
                 ui.add(egui::Image::new(egui::include_image!("../assets/logo_512.png")).max_width(80.0));
                 ui.add_space(4.0);
-                ui.heading("ShadowPrompt Setup");
+                ui.heading(t(if self.settings_mode { "wizard.settings_title" } else { "wizard.title" }));
             });
 
-            // --- Step Indicator ---
+            self.show_accessibility_bar(ui);
+
             ui.add_space(8.0);
-            ui.horizontal(|ui| {
-                let step_text = format!(
-                    "Step {} of {} — {}",
-                    self.current_page.index(),
-                    SetupPage::total(),
-                    self.current_page.title()
-                );
-                ui.label(egui::RichText::new(step_text).color(egui::Color32::GRAY).size(14.0));
-            });
+            if self.settings_mode {
+                // --- Page Tabs ---
+                ui.horizontal(|ui| {
+                    for page in SETTINGS_PAGES {
+                        if ui.selectable_label(self.current_page == page, page.title()).clicked() {
+                            self.current_page = page;
+                        }
+                    }
+                });
+            } else {
+                // --- Step Indicator ---
+                ui.horizontal(|ui| {
+                    let step_text = tf("wizard.nav.step", &[
+                        ("step", &self.current_page.index().to_string()),
+                        ("total", &SetupPage::total().to_string()),
+                        ("title", &self.current_page.title()),
+                    ]);
+                    ui.label(egui::RichText::new(step_text).color(egui::Color32::GRAY).size(14.0));
+                });
 
-            // Progress bar
-            let progress = self.current_page.index() as f32 / SetupPage::total() as f32;
-            ui.add(egui::ProgressBar::new(progress).show_percentage().desired_height(16.0));
+                // Progress bar
+                let progress = self.current_page.index() as f32 / SetupPage::total() as f32;
+                ui.add(egui::ProgressBar::new(progress).show_percentage().desired_height(16.0));
+            }
 
             ui.add_space(12.0);
             ui.separator();
@@ -291,8 +537,10 @@ impl eframe::App for SetupWizard {
                         SetupPage::LLMProvider => self.show_llm_provider(ui),
                         SetupPage::Features => self.show_features(ui),
                         SetupPage::Hotkeys => self.show_hotkeys(ui),
+                        SetupPage::HotkeyTest => self.show_hotkey_test(ui),
                         SetupPage::Visuals => self.show_visuals(ui),
                         SetupPage::Downloads => self.show_downloads(ui),
+                        SetupPage::Knowledge => self.show_knowledge(ui),
                         SetupPage::Credits => self.show_credits(ui),
                     }
                 });
@@ -302,29 +550,34 @@ impl eframe::App for SetupWizard {
             ui.separator();
             ui.add_space(8.0);
 
+            if self.settings_mode {
+                self.show_settings_footer(ui);
+                return;
+            }
+
             ui.horizontal(|ui| {
                 // Back button
                 let show_back = self.current_page != SetupPage::Landing
                     && self.current_page != SetupPage::Credits
                     && !self.downloading;
 
-                if show_back && ui.button("← Back").clicked() {
+                if show_back && ui.button(t("wizard.nav.back")).clicked() {
                     self.prev_page();
                 }
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if self.current_page == SetupPage::Credits {
-                        if ui.button("Start ShadowPrompt →").clicked() {
+                        if ui.button(t("wizard.nav.start")).clicked() {
                             let _ = self.config.save();
                             let _ = Config::mark_setup_complete();
                             self.finished = true;
                             self.spawn_app_and_exit();
                         }
                     } else if self.current_page == SetupPage::TermsOfService && !self.tos_accepted {
-                        if ui.button("I Decline").clicked() {
+                        if ui.button(t("wizard.nav.decline")).clicked() {
                             std::process::exit(0);
                         }
-                        if ui.button("I Accept").clicked() {
+                        if ui.button(t("wizard.nav.accept")).clicked() {
                             self.tos_accepted = true;
                             self.config.general.tos_accepted = true;
                             self.config.general.tos_accepted_version = TOS_VERSION.to_string();
@@ -332,7 +585,7 @@ impl eframe::App for SetupWizard {
                         }
                     } else {
                         let enabled = self.can_go_next() && !self.downloading;
-                        if ui.add_enabled(enabled, egui::Button::new("Next →")).clicked() {
+                        if ui.add_enabled(enabled, egui::Button::new(t("wizard.nav.next"))).clicked() {
                             self.next_page();
                         }
                     }
@@ -345,7 +598,9 @@ impl eframe::App for SetupWizard {
             || self.model_recorder.is_recording() || self.panic_recorder.is_recording()
             || self.hide_recorder.is_recording() || self.browser_pass_recorder.is_recording()
             || self.browser_exec_recorder.is_recording() || self.browser_abort_recorder.is_recording()
-            || self.browser_incognito_recorder.is_recording()
+            || self.browser_incognito_recorder.is_recording() || self.browser_pause_recorder.is_recording()
+            || self.clipboard_cycle_recorder.is_recording() || self.rag_capture_recorder.is_recording()
+            || self.log_dump_recorder.is_recording()
         {
             ctx.request_repaint();
         }
@@ -356,36 +611,50 @@ impl eframe::App for SetupWizard {
 
 impl SetupWizard {
     fn show_landing(&mut self, ui: &mut egui::Ui) {
-        ui.label("Welcome to ShadowPrompt!");
+        let languages = crate::i18n::available();
+        if languages.len() > 1 {
+            ui.horizontal(|ui| {
+                ui.label("🌐");
+                egui::ComboBox::from_id_salt("language")
+                    .selected_text(&self.config.general.language)
+                    .show_ui(ui, |ui| {
+                        for language in &languages {
+                            if ui.selectable_value(&mut self.config.general.language, language.clone(), language).changed() {
+                                crate::i18n::set_language(language);
+                            }
+                        }
+                    });
+            });
+            ui.add_space(8.0);
+        }
+        ui.label(t("wizard.landing.heading"));
         ui.add_space(8.0);
 
-        ui.label("This wizard will guide you through the initial setup of your portable AI assistant.");
+        ui.label(t("wizard.landing.intro"));
         ui.add_space(12.0);
 
         ui.colored_label(
             egui::Color32::YELLOW,
-            egui::RichText::new("⚠ IMPORTANT: This setup runs only ONCE.")
+            egui::RichText::new(t("wizard.landing.runs_once"))
                 .strong()
         );
         ui.add_space(4.0);
 
-        ui.label("After completing setup, ShadowPrompt will run invisibly in the background. There is no GUI by design.");
+        ui.label(t("wizard.landing.runs_hidden"));
         ui.add_space(8.0);
 
-        ui.label("To modify settings later, edit the ");
-        ui.code("config/config.toml");
-        ui.label(" file directly.");
+        ui.label(tf("wizard.landing.edit_config_later", &[("file", "config/config.toml")]));
         ui.add_space(12.0);
 
         ui.separator();
         ui.add_space(8.0);
 
-        ui.label(egui::RichText::new("Portable Design").strong());
-        ui.label("ShadowPrompt is designed to be fully contained. After setup, you can place the entire folder on a USB drive and run it on any Windows 10/11 computer.");
+        ui.label(egui::RichText::new(t("wizard.landing.portable_heading")).strong());
+        ui.label(t("wizard.landing.portable_body"));
     }
 
     fn show_tos(&mut self, ui: &mut egui::Ui) {
-        ui.label(egui::RichText::new("Please read and accept the Terms of Service to continue.").strong());
+        ui.label(egui::RichText::new(t("wizard.tos.prompt")).strong());
         ui.add_space(8.0);
 
         // Scrollable TOS text
@@ -403,16 +672,16 @@ impl SetupWizard {
         ui.add_space(8.0);
 
         if self.tos_accepted {
-            ui.colored_label(egui::Color32::GREEN, "✓ Terms accepted");
+            ui.colored_label(egui::Color32::GREEN, t("wizard.tos.accepted"));
         }
     }
 
     fn show_llm_provider(&mut self, ui: &mut egui::Ui) {
-        ui.label(egui::RichText::new("Configure at least one LLM provider to continue.").strong());
+        ui.label(egui::RichText::new(t("wizard.provider.none_configured")).strong());
         ui.add_space(4.0);
-        ui.label("You can configure multiple providers. ShadowPrompt will automatically fall back to the next available provider if one fails.");
+        ui.label(t("wizard.provider.fallback_note"));
         ui.add_space(8.0);
-        ui.label(egui::RichText::new("Tip: Click 'Test Connection' to verify your API key works.").color(egui::Color32::GRAY).small());
+        ui.label(egui::RichText::new(t("wizard.provider.test_tip")).color(egui::Color32::GRAY).small());
         ui.add_space(12.0);
 
         // --- Groq ---
@@ -420,28 +689,26 @@ impl SetupWizard {
             ui.horizontal(|ui| {
                 ui.checkbox(&mut self.provider_state.groq_enabled, "");
                 ui.label(egui::RichText::new("Groq").strong());
-                ui.label(egui::RichText::new("(Recommended)").color(egui::Color32::GREEN).small());
+                ui.label(egui::RichText::new(t("wizard.provider.recommended")).color(egui::Color32::GREEN).small());
             });
-            ui.label("Ultra-fast inference with a generous free tier.");
+            ui.label(t("wizard.provider.groq_description"));
 
             if self.provider_state.groq_enabled {
                 let groq = self.config.models.groq.get_or_insert_with(Default::default);
                 ui.add_space(4.0);
-                ui.horizontal(|ui| {
-                    ui.label("API Key:");
-                    ui.add(egui::TextEdit::singleline(&mut groq.api_key).desired_width(250.0).password(true));
-                });
-                ui.horizontal(|ui| {
-                    ui.label("Model:");
-                    ui.add(egui::TextEdit::singleline(&mut groq.model_id).desired_width(200.0));
-                });
+                let editing = ui.horizontal(|ui| {
+                    ui.label(t("wizard.provider.api_key_label"));
+                    ui.add(egui::TextEdit::singleline(&mut groq.api_key).desired_width(250.0).password(true)).has_focus()
+                }).inner;
+                self.groq_models.request("groq", &groq.api_key, editing);
+                self.groq_models.show(ui, "groq_model", &mut groq.model_id);
                 ui.add_space(4.0);
                 
                 // Test Connection button
                 let groq_enabled = self.provider_state.groq_enabled;
                 if groq_enabled {
-                    if ui.button("Test Groq Connection").clicked() {
-                        let config = self.config.clone();
+                    if ui.button(tf("wizard.provider.test_connection", &[("provider", "Groq")])).clicked() {
+                        let config = self.config.resolved();
                         std::thread::spawn(move || {
                             let result = test_provider_sync("groq", &config);
                             match &result {
@@ -450,7 +717,7 @@ impl SetupWizard {
                             }
                         });
                     }
-                    ui.label(egui::RichText::new("(Check logs for result)").color(egui::Color32::GRAY).small());
+                    ui.label(egui::RichText::new(t("wizard.provider.test_result_in_logs")).color(egui::Color32::GRAY).small());
                 }
             }
         });
@@ -463,22 +730,20 @@ impl SetupWizard {
                 ui.checkbox(&mut self.provider_state.openrouter_enabled, "");
                 ui.label(egui::RichText::new("OpenRouter").strong());
             });
-            ui.label("Wide selection of models from various providers.");
+            ui.label(t("wizard.provider.openrouter_description"));
 
             if self.provider_state.openrouter_enabled {
                 let or = self.config.models.openrouter.get_or_insert_with(Default::default);
                 ui.add_space(4.0);
-                ui.horizontal(|ui| {
-                    ui.label("API Key:");
-                    ui.add(egui::TextEdit::singleline(&mut or.api_key).desired_width(250.0).password(true));
-                });
-                ui.horizontal(|ui| {
-                    ui.label("Model:");
-                    ui.add(egui::TextEdit::singleline(&mut or.model_id).desired_width(200.0));
-                });
+                let editing = ui.horizontal(|ui| {
+                    ui.label(t("wizard.provider.api_key_label"));
+                    ui.add(egui::TextEdit::singleline(&mut or.api_key).desired_width(250.0).password(true)).has_focus()
+                }).inner;
+                self.openrouter_models.request("openrouter", &or.api_key, editing);
+                self.openrouter_models.show(ui, "openrouter_model", &mut or.model_id);
                 ui.add_space(4.0);
-                if ui.button("Test OpenRouter Connection").clicked() {
-                    let config = self.config.clone();
+                if ui.button(tf("wizard.provider.test_connection", &[("provider", "OpenRouter")])).clicked() {
+                    let config = self.config.resolved();
                     std::thread::spawn(move || {
                         let result = test_provider_sync("openrouter", &config);
                         match &result {
@@ -487,7 +752,7 @@ impl SetupWizard {
                         }
                     });
                 }
-                ui.label(egui::RichText::new("(Check logs for result)").color(egui::Color32::GRAY).small());
+                ui.label(egui::RichText::new(t("wizard.provider.test_result_in_logs")).color(egui::Color32::GRAY).small());
             }
         });
 
@@ -498,24 +763,22 @@ impl SetupWizard {
             ui.horizontal(|ui| {
                 ui.checkbox(&mut self.provider_state.ollama_enabled, "");
                 ui.label(egui::RichText::new("Ollama").strong());
-                ui.label(egui::RichText::new("⚠ Developer Only").color(egui::Color32::YELLOW).small());
+                ui.label(egui::RichText::new(t("wizard.provider.developer_only")).color(egui::Color32::YELLOW).small());
             });
-            ui.label("Local models. Requires Ollama server running separately.");
+            ui.label(t("wizard.provider.ollama_description"));
 
             if self.provider_state.ollama_enabled {
                 let ol = self.config.models.ollama.get_or_insert_with(Default::default);
                 ui.add_space(4.0);
-                ui.horizontal(|ui| {
-                    ui.label("Base URL:");
-                    ui.add(egui::TextEdit::singleline(&mut ol.base_url).desired_width(200.0));
-                });
-                ui.horizontal(|ui| {
-                    ui.label("Model:");
-                    ui.add(egui::TextEdit::singleline(&mut ol.model_id).desired_width(150.0));
-                });
+                let editing = ui.horizontal(|ui| {
+                    ui.label(t("wizard.provider.base_url_label"));
+                    ui.add(egui::TextEdit::singleline(&mut ol.base_url).desired_width(200.0)).has_focus()
+                }).inner;
+                self.ollama_models.request("ollama", &ol.base_url, editing);
+                self.ollama_models.show(ui, "ollama_model", &mut ol.model_id);
                 ui.add_space(4.0);
-                if ui.button("Test Ollama Connection").clicked() {
-                    let config = self.config.clone();
+                if ui.button(tf("wizard.provider.test_connection", &[("provider", "Ollama")])).clicked() {
+                    let config = self.config.resolved();
                     std::thread::spawn(move || {
                         let result = test_provider_sync("ollama", &config);
                         match &result {
@@ -524,54 +787,123 @@ impl SetupWizard {
                         }
                     });
                 }
-                ui.label(egui::RichText::new("(Check logs for result)").color(egui::Color32::GRAY).small());
+                ui.label(egui::RichText::new(t("wizard.provider.test_result_in_logs")).color(egui::Color32::GRAY).small());
             }
         });
 
         ui.add_space(12.0);
 
         if !self.provider_state.has_at_least_one() {
-            ui.colored_label(egui::Color32::RED, "⚠ Please enable and configure at least one provider.");
+            ui.colored_label(egui::Color32::RED, t("wizard.provider.enable_one"));
         }
     }
 
     fn show_features(&mut self, ui: &mut egui::Ui) {
-        ui.label(egui::RichText::new("ShadowPrompt includes the following features:").strong());
+        ui.label(egui::RichText::new(t("wizard.features.intro")).strong());
         ui.add_space(12.0);
 
         // Web Search
         ui.group(|ui| {
-            ui.label(egui::RichText::new("🔍 Web Search").strong());
-            ui.label("LLM models can search the web, significantly improving accuracy for current information.");
+            ui.label(egui::RichText::new(t("wizard.features.search_heading")).strong());
+            ui.label(t("wizard.features.search_description"));
             ui.add_space(8.0);
-            ui.label("Search Engine:");
+            ui.label(t("wizard.features.search_engine_label"));
             ui.add_space(4.0);
             ui.radio_value(&mut self.config.search.engine, "serper".to_string(), "Serper.dev (Recommended - reliable, $0.50/1k queries)");
+            ui.radio_value(&mut self.config.search.engine, "bing".to_string(), "Bing Web Search (Azure key)");
+            ui.radio_value(&mut self.config.search.engine, "searxng".to_string(), "SearXNG (your own instance)");
+            ui.radio_value(&mut self.config.search.engine, "academic".to_string(), "Academic papers (Semantic Scholar, arXiv, Crossref)");
             ui.radio_value(&mut self.config.search.engine, "duckduckgo".to_string(), "DuckDuckGo (Free - may rate-limit)");
             
             if self.config.search.engine == "serper" {
                 ui.add_space(8.0);
                 let api_key = self.config.search.serper_api_key.get_or_insert_with(String::new);
                 ui.horizontal(|ui| {
-                    ui.label("Serper API Key:");
+                    ui.label(t("wizard.features.serper_key_label"));
+                    ui.add(egui::TextEdit::singleline(api_key).desired_width(250.0).password(true));
+                });
+                ui.label(egui::RichText::new(t("wizard.features.serper_key_hint")).color(egui::Color32::GRAY).small());
+            } else if self.config.search.engine == "bing" {
+                ui.add_space(8.0);
+                let api_key = self.config.search.bing_api_key.get_or_insert_with(String::new);
+                ui.horizontal(|ui| {
+                    ui.label(t("wizard.features.bing_key_label"));
                     ui.add(egui::TextEdit::singleline(api_key).desired_width(250.0).password(true));
                 });
-                ui.label(egui::RichText::new("Get your free API key at serper.dev").color(egui::Color32::GRAY).small());
+                ui.label(egui::RichText::new(t("wizard.features.bing_key_hint")).color(egui::Color32::GRAY).small());
+            } else if self.config.search.engine == "searxng" {
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label(t("wizard.features.searxng_url_label"));
+                    ui.add(egui::TextEdit::singleline(&mut self.config.search.searxng_url).desired_width(250.0).hint_text("http://localhost:8080"));
+                });
+                ui.label(egui::RichText::new(t("wizard.features.searxng_url_hint")).color(egui::Color32::GRAY).small());
+            } else if self.config.search.engine == "academic" {
+                ui.add_space(8.0);
+                let api_key = self.config.search.semantic_scholar_api_key.get_or_insert_with(String::new);
+                ui.horizontal(|ui| {
+                    ui.label(t("wizard.features.semantic_scholar_key_label"));
+                    ui.add(egui::TextEdit::singleline(api_key).desired_width(250.0).password(true).hint_text(t("wizard.features.optional")));
+                });
+                ui.label(egui::RichText::new(t("wizard.features.semantic_scholar_hint")).color(egui::Color32::GRAY).small());
+            }
+
+            ui.add_space(8.0);
+            let mut fetch_pages = self.config.search.fetch_pages > 0;
+            if ui.checkbox(&mut fetch_pages, t("wizard.features.fetch_pages")).changed() {
+                self.config.search.fetch_pages = if fetch_pages { 2 } else { 0 };
             }
+            ui.checkbox(&mut self.config.search.wikipedia, t("wizard.features.wikipedia"));
         });
 
         ui.add_space(8.0);
 
         // Local RAG
         ui.group(|ui| {
-            ui.label(egui::RichText::new("📚 Local RAG").strong());
-            ui.label("Place documents in the knowledge/ folder. The AI can retrieve relevant context from your local files.");
-            ui.colored_label(egui::Color32::YELLOW, "⚠ Too many documents may slow down responses.");
-            ui.add_space(4.0);
-            if ui.button("📂 View Folder").clicked() {
-                let knowledge_path = crate::config::get_exe_dir().join("knowledge");
-                let _ = std::fs::create_dir_all(&knowledge_path);
-                let _ = open::that(&knowledge_path);
+            ui.label(egui::RichText::new(t("wizard.features.rag_heading")).strong());
+            ui.label(t("wizard.features.rag_description"));
+            ui.checkbox(&mut self.config.rag.enabled, t("wizard.features.rag_enable"));
+        });
+
+        ui.add_space(8.0);
+
+        // OCR Languages
+        ui.group(|ui| {
+            ui.label(egui::RichText::new(t("wizard.features.ocr_heading")).strong());
+            let mut recheck = false;
+            match &self.ocr_status {
+                Ok(status) => {
+                    match &status.active {
+                        Some(lang) => {
+                            ui.colored_label(egui::Color32::GREEN, format!("✔ OCR will use: {}", lang));
+                        }
+                        None => {
+                            ui.colored_label(egui::Color32::RED, t("wizard.features.ocr_no_language"));
+                            ui.label(t("wizard.features.ocr_install_hint"));
+                            ui.label(egui::RichText::new(OCR_INSTALL_HINT).monospace().small());
+                        }
+                    }
+                    let installed = if status.installed.is_empty() {
+                        "none".to_string()
+                    } else {
+                        status.installed.join(", ")
+                    };
+                    ui.label(egui::RichText::new(format!("Installed OCR languages: {}", installed)).color(egui::Color32::GRAY).small());
+                }
+                Err(e) => {
+                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ Could not query OCR languages: {}", e));
+                }
+            }
+            ui.horizontal(|ui| {
+                if ui.button(t("wizard.features.ocr_language_settings")).clicked() {
+                    let _ = open::that(LANGUAGE_SETTINGS_URI);
+                }
+                if ui.button(t("wizard.features.ocr_recheck")).clicked() {
+                    recheck = true;
+                }
+            });
+            if recheck {
+                self.ocr_status = OcrManager::language_status().map_err(|e| e.to_string());
             }
         });
 
@@ -579,56 +911,68 @@ impl SetupWizard {
 
         // Auto LLM Selection
         ui.group(|ui| {
-            ui.label(egui::RichText::new("🔄 Auto-LLM Fallback").strong());
-            ui.label("If your primary provider hits rate limits, ShadowPrompt automatically switches to the next available provider.");
-            ui.label(egui::RichText::new("Priority: Groq → OpenRouter → Ollama").color(egui::Color32::GRAY).small());
+            ui.label(egui::RichText::new(t("wizard.features.fallback_heading")).strong());
+            ui.label(t("wizard.features.fallback_description"));
+            ui.label(egui::RichText::new(t("wizard.features.fallback_priority")).color(egui::Color32::GRAY).small());
         });
 
         ui.add_space(8.0);
 
         // Hallucinations Warning
         ui.group(|ui| {
-            ui.label(egui::RichText::new("⚠ AI Limitations").strong().color(egui::Color32::YELLOW));
-            ui.label("LLMs can produce incorrect or fabricated information (hallucinations). This is inherent to AI technology.");
-            ui.label("For better accuracy, use smarter models and enable Web Search.");
+            ui.label(egui::RichText::new(t("wizard.features.limitations_heading")).strong().color(egui::Color32::YELLOW));
+            ui.label(t("wizard.features.limitations_description"));
+            ui.label(t("wizard.features.limitations_tip"));
         });
     }
 
     fn show_hotkeys(&mut self, ui: &mut egui::Ui) {
-        ui.label(egui::RichText::new("Configure your global hotkeys.").strong());
+        ui.label(egui::RichText::new(t("wizard.hotkeys.heading")).strong());
         ui.add_space(4.0);
-        ui.label("Click 'Record' and press your desired key combination within 5 seconds.");
+        ui.label(t("wizard.hotkeys.record_hint"));
         ui.add_space(12.0);
 
         // Hotkey fields
-        hotkey_field(ui, "Wake (OCR):", &mut self.config.general.wake_key, &mut self.wake_recorder, "wake");
+        hotkey_field(ui, &t("wizard.hotkeys.wake"), &mut self.config.general.wake_key, &mut self.wake_recorder, "wake");
+        ui.add_space(8.0);
+
+        hotkey_field(ui, &t("wizard.hotkeys.model"), &mut self.config.general.model_key, &mut self.model_recorder, "model");
+        ui.add_space(8.0);
+
+        hotkey_field(ui, &t("wizard.hotkeys.panic"), &mut self.config.general.panic_key, &mut self.panic_recorder, "panic");
         ui.add_space(8.0);
 
-        hotkey_field(ui, "Model Query:", &mut self.config.general.model_key, &mut self.model_recorder, "model");
+        hotkey_field(ui, &t("wizard.hotkeys.hide"), &mut self.config.visuals.hide_key, &mut self.hide_recorder, "hide");
         ui.add_space(8.0);
 
-        hotkey_field(ui, "Panic (Exit):", &mut self.config.general.panic_key, &mut self.panic_recorder, "panic");
+        hotkey_field(ui, &t("wizard.hotkeys.clipboard_cycle"), &mut self.config.general.key_clipboard_cycle, &mut self.clipboard_cycle_recorder, "clip_cycle");
         ui.add_space(8.0);
 
-        hotkey_field(ui, "Hide Graphics:", &mut self.config.visuals.hide_key, &mut self.hide_recorder, "hide");
+        hotkey_field(ui, &t("wizard.hotkeys.rag_capture"), &mut self.config.general.key_rag_capture, &mut self.rag_capture_recorder, "rag_capture");
+        ui.add_space(8.0);
+
+        hotkey_field(ui, &t("wizard.hotkeys.log_dump"), &mut self.config.general.key_log_dump, &mut self.log_dump_recorder, "log_dump");
         ui.add_space(12.0);
 
-        ui.label(egui::RichText::new("Headless Browser Keybinds").strong());
+        ui.label(egui::RichText::new(t("wizard.hotkeys.browser_heading")).strong());
         ui.add_space(4.0);
 
-        hotkey_field(ui, "Save Password:", &mut self.config.general.key_browser_pass, &mut self.browser_pass_recorder, "b_pass");
+        hotkey_field(ui, &t("wizard.hotkeys.browser_pass"), &mut self.config.general.key_browser_pass, &mut self.browser_pass_recorder, "b_pass");
+        ui.add_space(8.0);
+
+        hotkey_field(ui, &t("wizard.hotkeys.browser_exec"), &mut self.config.general.key_browser_exec, &mut self.browser_exec_recorder, "b_exec");
         ui.add_space(8.0);
 
-        hotkey_field(ui, "Execute Form (Auto):", &mut self.config.general.key_browser_exec, &mut self.browser_exec_recorder, "b_exec");
+        hotkey_field(ui, &t("wizard.hotkeys.browser_exec_single"), &mut self.config.general.key_browser_exec_single, &mut self.browser_exec_single_recorder, "b_exec_single");
         ui.add_space(8.0);
 
-        hotkey_field(ui, "Execute Single Page:", &mut self.config.general.key_browser_exec_single, &mut self.browser_exec_single_recorder, "b_exec_single");
+        hotkey_field(ui, &t("wizard.hotkeys.browser_abort"), &mut self.config.general.key_browser_abort, &mut self.browser_abort_recorder, "b_abort");
         ui.add_space(8.0);
 
-        hotkey_field(ui, "Abort Headless:", &mut self.config.general.key_browser_abort, &mut self.browser_abort_recorder, "b_abort");
+        hotkey_field(ui, &t("wizard.hotkeys.browser_incognito"), &mut self.config.general.key_browser_incognito, &mut self.browser_incognito_recorder, "b_incognito");
         ui.add_space(8.0);
 
-        hotkey_field(ui, "Launch Incognito:", &mut self.config.general.key_browser_incognito, &mut self.browser_incognito_recorder, "b_incognito");
+        hotkey_field(ui, &t("wizard.hotkeys.browser_pause"), &mut self.config.general.key_browser_pause, &mut self.browser_pause_recorder, "b_pause");
         ui.add_space(12.0);
 
         // Validation error
@@ -640,126 +984,224 @@ impl SetupWizard {
         ui.separator();
         ui.add_space(8.0);
 
-        ui.label(egui::RichText::new("Hotkey Tips:").strong());
-        ui.label("• Use combinations like Ctrl+Shift+Space");
-        ui.label("• Avoid common shortcuts (Ctrl+C, Ctrl+V)");
-        ui.label("• Each hotkey must be unique");
+        ui.label(egui::RichText::new(t("wizard.hotkeys.tips_heading")).strong());
+        ui.label(t("wizard.hotkeys.tip_combinations"));
+        ui.label(t("wizard.hotkeys.tip_common_shortcuts"));
+        ui.label(t("wizard.hotkeys.tip_unique"));
+    }
+
+    /// Register the chosen hotkeys with the configured input backend and forget earlier presses
+    fn begin_hotkey_test(&mut self) {
+        let bindings = HotkeyBindings::from_config(&self.config);
+        match &mut self.hotkey_test {
+            Some(test) => {
+                let _ = test.rebind.send(bindings);
+                test.confirmed.clear();
+            }
+            None => {
+                let (tx, events) = mpsc::channel();
+                let backend = InputBackend::from_config(&self.config.general.input_backend);
+                let rebind = InputManager::start_rebindable(bindings, backend, tx);
+                self.hotkey_test = Some(HotkeyTest { events, rebind, confirmed: HashSet::new() });
+            }
+        }
+    }
+
+    /// Bound hotkeys that haven't reached the hook yet, in page order
+    fn untested_hotkeys(&self) -> Vec<(&'static str, String)> {
+        let confirmed = self.hotkey_test.as_ref().map(|test| &test.confirmed);
+        HotkeyBindings::named(&self.config)
+            .into_iter()
+            .filter(|(name, binding)| !binding.trim().is_empty() && (*name != "key_log_viewer" || cfg!(feature = "debug")))
+            .filter(|(name, _)| confirmed.map_or(true, |c| !c.contains(name)))
+            .map(|(name, binding)| (name, binding.to_string()))
+            .collect()
+    }
+
+    fn show_hotkey_test(&mut self, ui: &mut egui::Ui) {
+        if let Some(test) = &mut self.hotkey_test {
+            test.confirmed.extend(test.events.try_iter().filter_map(|event| event.binding_name()));
+        }
+        // Hook events don't wake egui, so keep polling while this page is open
+        ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+
+        ui.label(egui::RichText::new(t("wizard.hotkey_test.heading")).strong());
+        ui.add_space(4.0);
+        ui.label(t("wizard.hotkey_test.explanation"));
+        ui.add_space(12.0);
+
+        let untested: Vec<&str> = self.untested_hotkeys().into_iter().map(|(name, _)| name).collect();
+        egui::Grid::new("hotkey_test").num_columns(3).spacing([20.0, 6.0]).show(ui, |ui| {
+            for (name, label) in HOTKEY_LABELS {
+                let Some((_, binding)) = HotkeyBindings::named(&self.config).into_iter().find(|(n, _)| n == name) else { continue };
+                if binding.trim().is_empty() || (*name == "key_log_viewer" && !cfg!(feature = "debug")) {
+                    continue;
+                }
+                ui.label(t(label));
+                ui.code(binding);
+                if untested.contains(name) {
+                    ui.colored_label(egui::Color32::GRAY, t("wizard.hotkey_test.waiting"));
+                } else {
+                    ui.colored_label(egui::Color32::GREEN, t("wizard.hotkey_test.received"));
+                }
+                ui.end_row();
+            }
+        });
+
+        ui.add_space(12.0);
+        if untested.is_empty() {
+            ui.colored_label(egui::Color32::GREEN, t("wizard.hotkey_test.all_received"));
+        } else {
+            ui.label(egui::RichText::new(t("wizard.hotkey_test.not_received_hint")).color(egui::Color32::GRAY).small());
+        }
     }
 
     fn show_visuals(&mut self, ui: &mut egui::Ui) {
-        ui.label(egui::RichText::new("Customize the visual indicators.").strong());
+        ui.label(egui::RichText::new(t("wizard.visuals.heading")).strong());
         ui.add_space(4.0);
-        ui.label("ShadowPrompt displays small pixel indicators to show its status.");
+        ui.label(t("wizard.visuals.intro"));
         ui.add_space(12.0);
 
         // Position
         ui.horizontal(|ui| {
-            ui.label("Indicator Position:");
+            ui.label(t("wizard.visuals.indicator_position"));
             egui::ComboBox::from_id_salt("indicator_position")
                 .selected_text(&self.config.visuals.position)
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(&mut self.config.visuals.position, "top-right".to_string(), "Top Right");
-                    ui.selectable_value(&mut self.config.visuals.position, "top-left".to_string(), "Top Left");
-                    ui.selectable_value(&mut self.config.visuals.position, "bottom-right".to_string(), "Bottom Right");
-                    ui.selectable_value(&mut self.config.visuals.position, "bottom-left".to_string(), "Bottom Left");
+                    ui.selectable_value(&mut self.config.visuals.position, "top-right".to_string(), t("wizard.visuals.top_right"));
+                    ui.selectable_value(&mut self.config.visuals.position, "top-left".to_string(), t("wizard.visuals.top_left"));
+                    ui.selectable_value(&mut self.config.visuals.position, "bottom-right".to_string(), t("wizard.visuals.bottom_right"));
+                    ui.selectable_value(&mut self.config.visuals.position, "bottom-left".to_string(), t("wizard.visuals.bottom_left"));
+                });
+        });
+
+        ui.add_space(12.0);
+
+        // Color Scheme
+        ui.horizontal(|ui| {
+            ui.label(t("wizard.visuals.color_scheme"));
+            let before = self.config.visuals.color_scheme.clone();
+            egui::ComboBox::from_id_salt("color_scheme")
+                .selected_text(&self.config.visuals.color_scheme)
+                .show_ui(ui, |ui| {
+                    for scheme in COLOR_SCHEMES {
+                        ui.selectable_value(&mut self.config.visuals.color_scheme, scheme.to_string(), *scheme);
+                    }
                 });
+            if self.config.visuals.color_scheme != before {
+                self.config.visuals.apply_color_scheme();
+            }
         });
+        ui.label(egui::RichText::new(t("wizard.visuals.color_scheme_hint")).color(egui::Color32::GRAY).small());
 
         ui.add_space(12.0);
 
+        // Any manual edit below detaches from the preset
+        let mut colors_changed = false;
+
         // Status Colors
-        ui.label(egui::RichText::new("Status Colors").strong());
+        ui.label(egui::RichText::new(t("wizard.visuals.status_colors")).strong());
         ui.add_space(4.0);
 
-        color_picker(ui, "Ready:", &mut self.config.visuals.ready_color);
+        colors_changed |= color_picker(ui, "Ready:", &mut self.config.visuals.ready_color);
         ui.add_space(4.0);
-        color_picker(ui, "Processing:", &mut self.config.visuals.color_processing);
+        colors_changed |= color_picker(ui, "Processing:", &mut self.config.visuals.color_processing);
 
         ui.add_space(16.0);
 
         // MCQ Colors
-        ui.label(egui::RichText::new("Multiple Choice Indicator Colors").strong());
+        ui.label(egui::RichText::new(t("wizard.visuals.mcq_colors")).strong());
         ui.add_space(4.0);
 
         ui.horizontal(|ui| {
-            color_picker_compact(ui, "A:", &mut self.config.visuals.color_mcq_a);
+            colors_changed |= color_picker_compact(ui, "A:", &mut self.config.visuals.color_mcq_a);
             ui.add_space(16.0);
-            color_picker_compact(ui, "B:", &mut self.config.visuals.color_mcq_b);
+            colors_changed |= color_picker_compact(ui, "B:", &mut self.config.visuals.color_mcq_b);
         });
 
         ui.add_space(4.0);
 
         ui.horizontal(|ui| {
-            color_picker_compact(ui, "C:", &mut self.config.visuals.color_mcq_c);
+            colors_changed |= color_picker_compact(ui, "C:", &mut self.config.visuals.color_mcq_c);
             ui.add_space(16.0);
-            color_picker_compact(ui, "D:", &mut self.config.visuals.color_mcq_d);
+            colors_changed |= color_picker_compact(ui, "D:", &mut self.config.visuals.color_mcq_d);
         });
 
         ui.add_space(16.0);
 
         // True/False Colors
-        ui.label(egui::RichText::new("True/False Indicator Colors").strong());
+        ui.label(egui::RichText::new(t("wizard.visuals.true_false_colors")).strong());
         ui.add_space(4.0);
 
         ui.horizontal(|ui| {
-            color_picker_compact(ui, "True:", &mut self.config.visuals.color_true);
+            colors_changed |= color_picker_compact(ui, "True:", &mut self.config.visuals.color_true);
             ui.add_space(16.0);
-            color_picker_compact(ui, "False:", &mut self.config.visuals.color_false);
+            colors_changed |= color_picker_compact(ui, "False:", &mut self.config.visuals.color_false);
         });
 
+        if colors_changed {
+            self.config.visuals.color_scheme = "custom".to_string();
+        }
+
         ui.add_space(16.0);
 
         // Text Overlay Settings
-        ui.label(egui::RichText::new("Text Answer Display").strong());
+        ui.label(egui::RichText::new(t("wizard.visuals.text_overlay_heading")).strong());
         ui.add_space(4.0);
 
-        ui.checkbox(&mut self.config.visuals.text_overlay_enabled, "Show answer text at bottom-right");
+        ui.checkbox(&mut self.config.visuals.text_overlay_enabled, t("wizard.visuals.text_overlay_enable"));
         
         if self.config.visuals.text_overlay_enabled {
             ui.add_space(8.0);
             
             ui.horizontal(|ui| {
-                ui.label("Position:");
+                ui.label(t("wizard.visuals.text_overlay_position"));
                 egui::ComboBox::from_id_salt("text_overlay_position")
                     .selected_text(&self.config.visuals.text_overlay_position)
                     .show_ui(ui, |ui| {
-                        ui.selectable_value(&mut self.config.visuals.text_overlay_position, "bottom-right".to_string(), "Bottom Right");
-                        ui.selectable_value(&mut self.config.visuals.text_overlay_position, "top-right".to_string(), "Top Right");
-                        ui.selectable_value(&mut self.config.visuals.text_overlay_position, "bottom-left".to_string(), "Bottom Left");
-                        ui.selectable_value(&mut self.config.visuals.text_overlay_position, "top-left".to_string(), "Top Left");
+                        ui.selectable_value(&mut self.config.visuals.text_overlay_position, "bottom-right".to_string(), t("wizard.visuals.bottom_right"));
+                        ui.selectable_value(&mut self.config.visuals.text_overlay_position, "top-right".to_string(), t("wizard.visuals.top_right"));
+                        ui.selectable_value(&mut self.config.visuals.text_overlay_position, "bottom-left".to_string(), t("wizard.visuals.bottom_left"));
+                        ui.selectable_value(&mut self.config.visuals.text_overlay_position, "top-left".to_string(), t("wizard.visuals.top_left"));
                     });
             });
 
             ui.add_space(4.0);
             
             ui.horizontal(|ui| {
-                ui.label("Font Size:");
+                ui.label(t("wizard.visuals.font_size"));
                 ui.add(egui::Slider::new(&mut self.config.visuals.text_overlay_font_size, 8..=48).text(""));
             });
 
             ui.add_space(4.0);
             
             ui.horizontal(|ui| {
-                ui.label("Background Opacity:");
+                ui.label(t("wizard.visuals.background_opacity"));
                 ui.add(egui::Slider::new(&mut self.config.visuals.text_overlay_bg_opacity, 50..=255).text(""));
             });
             
             ui.add_space(4.0);
             
             ui.horizontal(|ui| {
-                ui.label("Text Opacity:");
+                ui.label(t("wizard.visuals.text_opacity"));
                 ui.add(egui::Slider::new(&mut self.config.visuals.text_overlay_text_opacity, 50..=255).text(""));
             });
         }
     }
 
     fn show_downloads(&mut self, ui: &mut egui::Ui) {
-        ui.label(egui::RichText::new("Required Downloads").strong());
+        ui.label(egui::RichText::new(t("wizard.downloads.heading")).strong());
         ui.add_space(4.0);
-        ui.label("ShadowPrompt needs to download embedding models for local RAG functionality.");
+        ui.label(t("wizard.downloads.intro"));
         ui.add_space(12.0);
 
-        ui.label(format!("Status: {}", self.download_status));
+        // The DLL and models are only for local RAG
+        if !self.config.rag.enabled && !self.downloading {
+            ui.colored_label(egui::Color32::GRAY, t("wizard.downloads.rag_disabled"));
+            return;
+        }
+
+        ui.label(tf("wizard.downloads.status", &[("status", &self.download_status)]));
         ui.add_space(8.0);
 
         if self.downloading {
@@ -767,54 +1209,141 @@ impl SetupWizard {
             ui.add_space(8.0);
             ui.spinner();
         } else if self.download_success {
-            ui.colored_label(egui::Color32::GREEN, "✓ Downloads complete! You may proceed.");
+            ui.colored_label(egui::Color32::GREEN, t("wizard.downloads.done"));
         } else {
-            let button_label = if self.download_status.starts_with("Error") {
-                "Retry Download"
+            let button_label = t(if self.download_status.starts_with("Error") {
+                "wizard.downloads.retry"
             } else {
-                "Download Models"
-            };
+                "wizard.downloads.start"
+            });
 
             if ui.button(button_label).clicked() {
                 self.start_download();
             }
 
             ui.add_space(8.0);
-            ui.label(egui::RichText::new("This download is required to complete setup.").color(egui::Color32::GRAY).small());
+            ui.label(egui::RichText::new(t("wizard.downloads.required")).color(egui::Color32::GRAY).small());
         }
     }
 
+    fn show_knowledge(&mut self, ui: &mut egui::Ui) {
+        ui.label(egui::RichText::new(t("wizard.knowledge.heading")).strong());
+        ui.add_space(4.0);
+        ui.label(t("wizard.knowledge.intro"));
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            ui.label(t("wizard.knowledge.folder_label"));
+            ui.add_enabled(!self.ingesting, egui::TextEdit::singleline(&mut self.config.rag.knowledge_path).desired_width(250.0));
+            if ui.button(t("wizard.knowledge.open")).clicked() {
+                let knowledge_path = crate::config::get_exe_dir().join(&self.config.rag.knowledge_path);
+                let _ = std::fs::create_dir_all(&knowledge_path);
+                let _ = open::that(&knowledge_path);
+            }
+        });
+        ui.label(egui::RichText::new(t("wizard.knowledge.folder_hint")).color(egui::Color32::GRAY).small());
+        ui.add_space(8.0);
+
+        // Recount when the path changes or on request (files may be copied in meanwhile)
+        let path = self.config.rag.knowledge_path.clone();
+        if self.knowledge_files.as_ref().map(|(counted, _)| counted != &path).unwrap_or(true) {
+            let count = count_knowledge_files(&crate::config::get_exe_dir().join(&path));
+            self.knowledge_files = Some((path, count));
+        }
+        let count = self.knowledge_files.as_ref().map(|(_, count)| *count).unwrap_or(0);
+        ui.horizontal(|ui| {
+            ui.label(tf("wizard.knowledge.files_found", &[("count", &count.to_string()), ("types", &crate::knowledge::formats::EXTENSIONS.join(", "))]));
+            if ui.small_button("⟳").clicked() {
+                self.knowledge_files = None;
+            }
+        });
+        ui.add_space(12.0);
+
+        if self.config.rag.encrypt_index {
+            ui.label(egui::RichText::new(t("wizard.knowledge.encrypted_note")).color(egui::Color32::GRAY));
+            return;
+        }
+
+        if self.ingesting {
+            ui.add(egui::ProgressBar::new(self.ingest_progress).show_percentage().animate(true));
+            ui.label(&self.ingest_status);
+        } else {
+            if !self.ingest_status.is_empty() {
+                let color = if self.ingest_status.starts_with("Error") { egui::Color32::RED } else { egui::Color32::GREEN };
+                ui.colored_label(color, &self.ingest_status);
+                ui.add_space(4.0);
+            }
+            if ui.add_enabled(count > 0, egui::Button::new(t("wizard.knowledge.index_now"))).clicked() {
+                self.start_ingest();
+            }
+            ui.label(egui::RichText::new(t("wizard.knowledge.index_later_note")).color(egui::Color32::GRAY).small());
+        }
+    }
+
+    fn start_ingest(&mut self) {
+        if self.ingesting { return; }
+
+        self.ingesting = true;
+        self.ingest_progress = 0.0;
+        self.ingest_status = t("wizard.knowledge.loading_model");
+
+        let (tx, rx) = mpsc::channel();
+        self.ingest_rx = Some(rx);
+        let config_clone = self.config.resolved();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async move {
+                let rag_system = crate::knowledge::rag::RagSystem::new(&config_clone).await;
+                if !rag_system.is_operational() {
+                    let error_msg = rag_system.get_init_error().unwrap_or("Unknown error");
+                    let _ = tx.send((0.0, format!("Error: {}", error_msg)));
+                    return;
+                }
+                let progress_tx = tx.clone();
+                let progress: crate::knowledge::rag::IngestProgress = std::sync::Arc::new(move |done, total| {
+                    let fraction = done as f32 / total.max(1) as f32;
+                    let _ = progress_tx.send((0.99 * fraction, tf("wizard.knowledge.indexing", &[("done", &done.to_string()), ("total", &total.to_string())])));
+                });
+                match rag_system.ingest_with_progress(Some(progress)).await {
+                    Ok(chunks) => { let _ = tx.send((1.0, tf("wizard.knowledge.ready", &[("chunks", &chunks.to_string())]))); }
+                    Err(e) => { let _ = tx.send((0.0, format!("Error: {}", e))); }
+                }
+            });
+        });
+    }
+
     fn show_credits(&mut self, ui: &mut egui::Ui) {
         ui.vertical_centered(|ui| {
-            ui.label(egui::RichText::new("Thank You!").strong().size(20.0));
+            ui.label(egui::RichText::new(t("wizard.credits.heading")).strong().size(20.0));
             ui.add_space(8.0);
-            ui.label("ShadowPrompt setup is complete.");
+            ui.label(t("wizard.credits.complete"));
         });
 
         ui.add_space(16.0);
 
         // Quick Start Summary
         ui.group(|ui| {
-            ui.label(egui::RichText::new("📋 Quick Start Summary").strong());
+            ui.label(egui::RichText::new(t("wizard.credits.summary_heading")).strong());
             ui.add_space(8.0);
 
             egui::Grid::new("hotkey_summary")
                 .num_columns(2)
                 .spacing([20.0, 4.0])
                 .show(ui, |ui| {
-                    ui.label("Wake (OCR):");
+                    ui.label(t("wizard.hotkeys.wake"));
                     ui.code(&self.config.general.wake_key);
                     ui.end_row();
 
-                    ui.label("Model Query:");
+                    ui.label(t("wizard.hotkeys.model"));
                     ui.code(&self.config.general.model_key);
                     ui.end_row();
 
-            ui.label("Panic (Exit):");
+            ui.label(t("wizard.hotkeys.panic"));
                     ui.code(&self.config.general.panic_key);
                     ui.end_row();
 
-                    ui.label("Hide Graphics:");
+                    ui.label(t("wizard.hotkeys.hide"));
                     ui.code(&self.config.visuals.hide_key);
                     ui.end_row();
                 });
@@ -827,12 +1356,12 @@ impl SetupWizard {
         ui.add_space(8.0);
 
         ui.vertical_centered(|ui| {
-            ui.label("Developed by");
+            ui.label(t("wizard.credits.developed_by"));
             ui.label(egui::RichText::new("Hyowon Bernabe").strong());
             ui.add_space(4.0);
             ui.hyperlink_to("www.hyowonbernabe.me", "https://www.hyowonbernabe.me");
             ui.add_space(8.0);
-            ui.hyperlink_to("GitHub Repository", "https://github.com/hyowonbernabe/ShadowPrompt");
+            ui.hyperlink_to(t("wizard.credits.github"), "https://github.com/hyowonbernabe/ShadowPrompt");
         });
     }
 
@@ -842,7 +1371,7 @@ impl SetupWizard {
         if self.downloading { return; }
 
         self.downloading = true;
-        self.download_status = "Initializing...".to_string();
+        self.download_status = t("wizard.downloads.initializing");
         self.download_progress = 0.0;
         self.download_success = false;
 
@@ -854,33 +1383,86 @@ impl SetupWizard {
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
             rt.block_on(async move {
-                // Step 1: Check and download onnxruntime.dll
-                let dll_path = Path::new("onnxruntime.dll");
-                let bin_dll_path = Path::new("bin/onnxruntime.dll");
+                let progress = |fraction, status| { let _ = tx.send((fraction, status)); };
+                match download_modules(&config_clone, &progress).await {
+                    // Indexing the knowledge folder happens on the Knowledge page
+                    Ok(()) => progress(1.0, t("wizard.downloads.complete")),
+                    Err(e) => progress(0.0, format!("Error: {}", e)),
+                }
+            });
+        });
+    }
 
-                if !dll_path.exists() && !bin_dll_path.exists() {
-                    let _ = tx.send((0.1, "Downloading ONNX Runtime DLL...".to_string()));
+    fn apply_theme(&mut self, ctx: &egui::Context) {
+        let visuals = &self.config.visuals;
+        let wanted = (visuals.wizard_theme.clone(), visuals.wizard_scale.clamp(MIN_SCALE, MAX_SCALE), visuals.wizard_high_contrast);
+        if self.applied_theme.as_ref() == Some(&wanted) {
+            return;
+        }
+        ctx.set_visuals(wizard_visuals(wanted.0 == "light", wanted.2));
+        ctx.set_zoom_factor(wanted.1);
+        self.applied_theme = Some(wanted);
+    }
 
-                    if let Err(e) = download_onnx_dll().await {
-                        let _ = tx.send((0.0, format!("Error downloading DLL: {}", e)));
-                        return;
-                    }
+    /// Theme, text size and contrast, on every page so they can be fixed before reading on
+    fn show_accessibility_bar(&mut self, ui: &mut egui::Ui) {
+        let visuals = &mut self.config.visuals;
+        ui.horizontal(|ui| {
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.checkbox(&mut visuals.wizard_high_contrast, t("wizard.theme.high_contrast"));
+                if ui.small_button("A+").on_hover_text(t("wizard.theme.larger")).clicked() {
+                    visuals.wizard_scale = (visuals.wizard_scale + SCALE_STEP).min(MAX_SCALE);
                 }
+                ui.label(format!("{:.0}%", visuals.wizard_scale * 100.0));
+                if ui.small_button("A−").on_hover_text(t("wizard.theme.smaller")).clicked() {
+                    visuals.wizard_scale = (visuals.wizard_scale - SCALE_STEP).max(MIN_SCALE);
+                }
+                let light = visuals.wizard_theme == "light";
+                if ui.small_button(if light { "🌙" } else { "☀" }).on_hover_text(t("wizard.theme.toggle")).clicked() {
+                    visuals.wizard_theme = if light { "dark" } else { "light" }.to_string();
+                }
+            });
+        });
+    }
 
-                // Step 2: Initialize FastEmbed models
-                let _ = tx.send((0.4, "Downloading embedding models...".to_string()));
-
-                let rag_system = crate::knowledge::rag::RagSystem::new(&config_clone).await;
-                if rag_system.is_operational() {
-                     let _ = tx.send((1.0, "Download complete!".to_string()));
-                } else {
-                     let error_msg = rag_system.get_init_error().unwrap_or("Unknown error");
-                     let _ = tx.send((0.0, format!("Error: {}", error_msg)));
+    fn show_settings_footer(&mut self, ui: &mut egui::Ui) {
+        if let Some(status) = &self.settings_status {
+            ui.label(egui::RichText::new(status).color(egui::Color32::GRAY));
+        }
+        ui.horizontal(|ui| {
+            if ui.button(t("wizard.nav.close")).clicked() {
+                std::process::exit(0);
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let restart = ui.button(t("wizard.nav.save_restart")).clicked();
+                let save = ui.button(t("wizard.nav.save")).clicked();
+                if !(save || restart) {
+                    return;
+                }
+                if !self.check_hotkeys() {
+                    self.current_page = SetupPage::Hotkeys;
+                    self.settings_status = Some(t("wizard.nav.fix_hotkeys"));
+                    return;
+                }
+                match self.config.save() {
+                    Ok(()) if restart => self.restart_app_and_exit(),
+                    Ok(()) => self.settings_status = Some(t("wizard.nav.saved")),
+                    Err(e) => self.settings_status = Some(format!("Could not save: {}", e)),
                 }
             });
         });
     }
 
+    /// Stop the running background instance (same executable, any other PID) and start a new one
+    fn restart_app_and_exit(&self) {
+        let exe = std::env::current_exe().unwrap_or_else(|_| std::path::PathBuf::from("shadow_prompt.exe"));
+        let image = exe.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "shadow_prompt.exe".to_string());
+        let _ = std::process::Command::new("taskkill")
+            .args(["/F", "/IM", &image, "/FI", &format!("PID ne {}", std::process::id())])
+            .output();
+        self.spawn_app_and_exit();
+    }
+
     fn spawn_app_and_exit(&self) {
         let exe = std::env::current_exe().unwrap_or_else(|_| std::path::PathBuf::from("shadow_prompt.exe"));
 
@@ -890,8 +1472,49 @@ impl SetupWizard {
     }
 }
 
+// --- Knowledge Helper ---
+
+/// Files the indexer would pick up, subfolders included
+fn count_knowledge_files(dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .map(|path| {
+            if path.is_dir() {
+                count_knowledge_files(&path)
+            } else {
+                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+                usize::from(crate::knowledge::formats::EXTENSIONS.contains(&ext.as_str()))
+            }
+        })
+        .sum()
+}
+
 // --- Download Helper ---
 
+/// ONNX Runtime DLL and the embedding models, shared by the Downloads page and `--setup-headless`
+pub(crate) async fn download_modules(config: &Config, progress: &dyn Fn(f32, String)) -> Result<(), String> {
+    // Step 1: Check and download onnxruntime.dll
+    let dll_path = Path::new("onnxruntime.dll");
+    let bin_dll_path = Path::new("bin/onnxruntime.dll");
+
+    if !dll_path.exists() && !bin_dll_path.exists() {
+        progress(0.1, t("wizard.downloads.onnx"));
+        download_onnx_dll().await.map_err(|e| format!("downloading DLL: {}", e))?;
+    }
+
+    // Step 2: Initialize FastEmbed models
+    progress(0.4, t("wizard.downloads.models"));
+
+    let rag_system = crate::knowledge::rag::RagSystem::new(config).await;
+    if rag_system.is_operational() {
+        Ok(())
+    } else {
+        Err(rag_system.get_init_error().unwrap_or("Unknown error").to_string())
+    }
+}
+
 async fn download_onnx_dll() -> anyhow::Result<()> {
     use std::io::Write;
 
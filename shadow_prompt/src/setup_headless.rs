@@ -0,0 +1,102 @@
+//! Headless Setup
+//! `--setup-headless --answers setup.toml` does what the wizard does without a window, so
+//! many USB sticks can be prepared from a script. The answers file uses config.toml's
+//! sections (providers, keys, hotkeys, visuals) plus a top-level `accept_tos = "<version>"`
+//! that must match the bundled Terms of Service version.
+
+use crate::config::{merge_tables, Config};
+use crate::hotkey_recorder::validate_hotkeys;
+use crate::tos_text::TOS_VERSION;
+use anyhow::{anyhow, Context, Result};
+use std::path::PathBuf;
+
+pub fn run(args: &[String]) -> Result<()> {
+    let answers_path = args
+        .iter()
+        .skip_while(|a| *a != "--answers")
+        .nth(1)
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow!("--setup-headless needs --answers <setup.toml>"))?;
+    let answers = std::fs::read_to_string(&answers_path)
+        .with_context(|| format!("Failed to read {}", answers_path.display()))?;
+
+    let config = apply_answers(Config::load_raw().unwrap_or_default(), &answers)?;
+    println!("[*] Answers accepted (Terms of Service {}).", TOS_VERSION);
+
+    if config.rag.enabled {
+        println!("[*] Downloading modules and models...");
+        let rt = tokio::runtime::Runtime::new()?;
+        let progress = |fraction: f32, status: String| println!("    [{:>3.0}%] {}", fraction * 100.0, status);
+        rt.block_on(crate::setup::download_modules(&config, &progress))
+            .map_err(|e| anyhow!("Download failed: {}", e))?;
+    } else {
+        println!("[*] Local RAG is disabled ([rag] enabled = false); skipping the model downloads.");
+    }
+
+    config.save()?;
+    Config::mark_setup_complete()?;
+    println!("[+] Setup complete. config.toml written and setup marked as done.");
+    Ok(())
+}
+
+/// `base` with the answers merged over it, checked the way the wizard's pages check them
+fn apply_answers(base: Config, answers: &str) -> Result<Config> {
+    let mut answers: toml::Table = answers.parse().context("The answers file is not valid TOML")?;
+    match answers.remove("accept_tos") {
+        Some(toml::Value::String(version)) if version == TOS_VERSION => {}
+        Some(toml::Value::String(version)) => anyhow::bail!(
+            "accept_tos = \"{}\" does not match the current Terms of Service ({}). Read them (tos_text.rs or the wizard) and update it.",
+            version,
+            TOS_VERSION
+        ),
+        _ => anyhow::bail!("The answers file must accept the Terms of Service: accept_tos = \"{}\"", TOS_VERSION),
+    }
+
+    let toml::Value::Table(mut table) = toml::Value::try_from(&base).context("Failed to serialize config")? else {
+        anyhow::bail!("config did not serialize to a table");
+    };
+    merge_tables(&mut table, answers);
+    let mut config: Config = toml::Value::Table(table).try_into().context("A value in the answers file has the wrong type")?;
+
+    let models = &config.models;
+    let has_provider = models.groq.as_ref().is_some_and(|g| !g.api_key.is_empty())
+        || models.openrouter.as_ref().is_some_and(|o| !o.api_key.is_empty())
+        || models.ollama.is_some();
+    if !has_provider {
+        anyhow::bail!("Configure at least one provider: [models.groq] or [models.openrouter] with an api_key, or [models.ollama]");
+    }
+    validate_hotkeys(&config)
+    .map_err(|e| anyhow!("Hotkeys: {}", e))?;
+
+    config.general.tos_accepted = true;
+    config.general.tos_accepted_version = TOS_VERSION.to_string();
+    config.visuals.apply_color_scheme();
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_answers() {
+        let answers = format!(
+            "accept_tos = \"{}\"\n[models.groq]\napi_key = \"gsk_test\"\nmodel_id = \"llama-3.3-70b-versatile\"\n[general]\nwake_key = \"Ctrl+Alt+Space\"\n",
+            TOS_VERSION
+        );
+        let config = apply_answers(Config::default(), &answers).unwrap();
+        assert!(config.general.tos_accepted);
+        assert_eq!(config.general.wake_key, "Ctrl+Alt+Space");
+        assert_eq!(config.models.groq.unwrap().model_id, "llama-3.3-70b-versatile");
+    }
+
+    #[test]
+    fn test_apply_answers_rejects_incomplete_files() {
+        let provider = "[models.ollama]\nbase_url = \"http://localhost:11434\"\nmodel_id = \"llama3\"\n";
+        assert!(apply_answers(Config::default(), provider).is_err());
+        assert!(apply_answers(Config::default(), &format!("accept_tos = \"0.0.1\"\n{}", provider)).is_err());
+        assert!(apply_answers(Config::default(), &format!("accept_tos = \"{}\"\n", TOS_VERSION)).is_err());
+        let clash = format!("accept_tos = \"{}\"\n[general]\nwake_key = \"Ctrl+Shift+V\"\n{}", TOS_VERSION, provider);
+        assert!(apply_answers(Config::default(), &clash).is_err());
+    }
+}
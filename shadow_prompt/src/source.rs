@@ -0,0 +1,110 @@
+//! Question Sources
+//! Every place a question can come from (clipboard, OCR region, stdin, ...) produces
+//! a `QueryRequest`, so the pipeline never has to care where the text originated.
+//! Push-style sources (HTTP API, browser extension) build a `QueryRequest` directly.
+
+use crate::clipboard::ClipboardManager;
+use crate::ocr::{CaptureBackend, OcrManager};
+use anyhow::{Context, Result};
+use std::future::Future;
+use std::io::Read;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+pub enum QuerySource {
+    Clipboard,
+    Ocr,
+    Stdin,
+    Http,
+    Extension,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryRequest {
+    pub source: QuerySource,
+    pub text: String,
+    /// PNG screenshot for vision-capable models
+    pub image_base64: Option<String>,
+}
+
+impl QueryRequest {
+    pub fn text(source: QuerySource, text: impl Into<String>) -> Self {
+        Self {
+            source,
+            text: text.into(),
+            image_base64: None,
+        }
+    }
+}
+
+pub trait QuestionSource {
+    fn read(&self) -> impl Future<Output = Result<QueryRequest>> + Send;
+}
+
+pub struct ClipboardSource;
+
+impl QuestionSource for ClipboardSource {
+    async fn read(&self) -> Result<QueryRequest> {
+        let text = ClipboardManager::read_question()?;
+        Ok(QueryRequest::text(QuerySource::Clipboard, text))
+    }
+}
+
+pub struct OcrSource {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+    /// Also attach a screenshot so vision models can read the region directly
+    pub capture_image: bool,
+    pub capture_backend: CaptureBackend,
+}
+
+impl QuestionSource for OcrSource {
+    async fn read(&self) -> Result<QueryRequest> {
+        let image_base64 = if self.capture_image {
+            match OcrManager::capture_as_base64(self.capture_backend, self.x, self.y, self.w, self.h).await {
+                Ok(image) => Some(image),
+                Err(e) => {
+                    eprintln!("[-] Screenshot capture failed: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // OCR text is still extracted for vision requests; it is the fallback if the vision call fails
+        let text = match OcrManager::extract_from_screen(self.capture_backend, self.x, self.y, self.w, self.h).await {
+            Ok(text) => {
+                println!("[+] OCR Success: \"{}\"", text.trim());
+                text
+            }
+            Err(e) if image_base64.is_some() => {
+                eprintln!("[-] OCR Failed: {}, relying on screenshot only", e);
+                String::new()
+            }
+            Err(e) => return Err(e),
+        };
+
+        Ok(QueryRequest {
+            source: QuerySource::Ocr,
+            text,
+            image_base64,
+        })
+    }
+}
+
+pub struct StdinSource;
+
+impl QuestionSource for StdinSource {
+    async fn read(&self) -> Result<QueryRequest> {
+        let text = tokio::task::spawn_blocking(|| {
+            let mut buf = String::new();
+            std::io::stdin().read_to_string(&mut buf).map(|_| buf)
+        })
+        .await?
+        .context("Failed to read question from stdin")?;
+        Ok(QueryRequest::text(QuerySource::Stdin, text))
+    }
+}
@@ -0,0 +1,205 @@
+//! Spend Tracking
+//! Each chat completion's token usage is added to data/spend.json per day and provider.
+//! OpenRouter reports what a request cost; Groq's cost is estimated from the `[safety]`
+//! prices. Past `spend_warning_usd` the ready indicator turns `color_spend_warning`; past
+//! `daily_spend_limit_usd` paid providers are refused until the next day, Ollama still works.
+//! `--spend-report` prints the totals.
+
+use crate::config::{get_exe_dir, Config, SafetyConfig};
+use crate::provider_health::Provider;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Days shown by `--spend-report`
+const REPORT_DAYS: usize = 30;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct Usage {
+    #[serde(default)]
+    requests: u64,
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+    #[serde(default)]
+    cost_usd: f64,
+}
+
+/// Date (YYYY-MM-DD) -> provider name -> usage
+type Store = BTreeMap<String, BTreeMap<String, Usage>>;
+
+/// Loaded on first use
+static STORE: Mutex<Option<Store>> = Mutex::new(None);
+
+/// `safety.spend_warning_usd` of the running config (0 = off)
+static WARNING_USD: Mutex<f64> = Mutex::new(0.0);
+
+fn db_path() -> PathBuf {
+    get_exe_dir().join("data").join("spend.json")
+}
+
+fn load() -> Store {
+    std::fs::read_to_string(db_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn with_store<R>(f: impl FnOnce(&mut Store) -> R) -> R {
+    let mut guard = STORE.lock().unwrap();
+    f(guard.get_or_insert_with(load))
+}
+
+/// Pick up the warning threshold; call after the config is loaded
+pub fn configure(config: &Config) {
+    *WARNING_USD.lock().unwrap() = config.safety.spend_warning_usd;
+}
+
+/// Add one completion's `usage` object (OpenAI-style, or Ollama's eval counts)
+pub fn record(provider: Provider, usage: &Value, config: &Config) {
+    let usage = parse_usage(provider, usage, &config.safety);
+    let total = with_store(|store| {
+        let date = today();
+        add(store, &date, provider.name(), &usage);
+        let result = serde_json::to_string(store)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| std::fs::write(db_path(), json).map_err(anyhow::Error::from));
+        if let Err(e) = result {
+            error!("Failed to write spend: {}", e);
+        }
+        day_total(store, &date)
+    });
+    info!("{} used {} + {} tokens (${:.4}), ${:.4} today", provider.name(), usage.prompt_tokens, usage.completion_tokens, usage.cost_usd, total);
+}
+
+/// Spent so far today, all providers
+pub fn today_usd() -> f64 {
+    with_store(|store| day_total(store, &today()))
+}
+
+/// Whether today's spend is past `spend_warning_usd`
+pub fn warning_reached() -> bool {
+    let threshold = *WARNING_USD.lock().unwrap();
+    threshold > 0.0 && today_usd() >= threshold
+}
+
+/// Err once today's spend reaches `daily_spend_limit_usd` (paid providers only)
+pub fn check_paid(provider: Provider, config: &Config) -> Result<()> {
+    let limit = config.safety.daily_spend_limit_usd;
+    if provider == Provider::Ollama || limit <= 0.0 {
+        return Ok(());
+    }
+    if today_usd() >= limit {
+        // No digits here: is_retryable_error would take them for a status code
+        anyhow::bail!(
+            "Daily spend limit reached, {} is paused until tomorrow (Ollama still works; see --spend-report)",
+            provider.name()
+        );
+    }
+    Ok(())
+}
+
+fn parse_usage(provider: Provider, usage: &Value, safety: &SafetyConfig) -> Usage {
+    let count = |keys: &[&str]| keys.iter().find_map(|key| usage[*key].as_u64()).unwrap_or(0);
+    let prompt_tokens = count(&["prompt_tokens", "prompt_eval_count"]);
+    let completion_tokens = count(&["completion_tokens", "eval_count"]);
+    let cost_usd = match provider {
+        Provider::Ollama => 0.0,
+        // OpenRouter reports the charge when asked to (`"usage": {"include": true}`)
+        _ => usage["cost"].as_f64().unwrap_or_else(|| {
+            (prompt_tokens as f64 * safety.input_usd_per_million + completion_tokens as f64 * safety.output_usd_per_million)
+                / 1_000_000.0
+        }),
+    };
+    Usage { requests: 1, prompt_tokens, completion_tokens, cost_usd }
+}
+
+fn add(store: &mut Store, date: &str, provider: &str, usage: &Usage) {
+    let entry = store.entry(date.to_string()).or_default().entry(provider.to_string()).or_default();
+    entry.requests += usage.requests;
+    entry.prompt_tokens += usage.prompt_tokens;
+    entry.completion_tokens += usage.completion_tokens;
+    entry.cost_usd += usage.cost_usd;
+}
+
+fn day_total(store: &Store, date: &str) -> f64 {
+    store.get(date).map(|day| day.values().map(|u| u.cost_usd).sum()).unwrap_or(0.0)
+}
+
+/// Print the last `REPORT_DAYS` days per provider (`--spend-report`)
+pub fn print_report(config: &Config) {
+    let store = load();
+    if store.is_empty() {
+        println!("[*] No API usage recorded yet.");
+        return;
+    }
+
+    println!("{:<12} {:<12} {:>8} {:>10} {:>11} {:>9}", "Date", "Provider", "Requests", "Prompt", "Completion", "Cost");
+    let mut total = 0.0;
+    for (date, day) in store.iter().rev().take(REPORT_DAYS).collect::<Vec<_>>().into_iter().rev() {
+        for (provider, usage) in day {
+            println!(
+                "{:<12} {:<12} {:>8} {:>10} {:>11} {:>9}",
+                date,
+                provider,
+                usage.requests,
+                usage.prompt_tokens,
+                usage.completion_tokens,
+                format!("${:.4}", usage.cost_usd)
+            );
+        }
+        total += day_total(&store, date);
+    }
+    println!("{:<12} {:<12} {:>8} {:>10} {:>11} {:>9}", "Total", "", "", "", "", format!("${:.4}", total));
+
+    let spent = day_total(&store, &today());
+    let limit = config.safety.daily_spend_limit_usd;
+    if limit > 0.0 {
+        println!("\nToday: ${:.4} of the ${:.2} daily limit", spent, limit);
+        if spent >= limit {
+            println!("[!] Limit reached: Groq and OpenRouter are refused until tomorrow; Ollama still works.");
+        }
+    } else {
+        println!("\nToday: ${:.4} (no daily limit set)", spent);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_usage() {
+        let safety = SafetyConfig::default();
+        let groq = parse_usage(Provider::Groq, &json!({"prompt_tokens": 1_000_000, "completion_tokens": 0}), &safety);
+        assert!((groq.cost_usd - safety.input_usd_per_million).abs() < 1e-9);
+
+        let openrouter = parse_usage(Provider::OpenRouter, &json!({"prompt_tokens": 10, "completion_tokens": 5, "cost": 0.0021}), &safety);
+        assert_eq!((openrouter.prompt_tokens, openrouter.completion_tokens, openrouter.cost_usd), (10, 5, 0.0021));
+
+        let ollama = parse_usage(Provider::Ollama, &json!({"prompt_eval_count": 40, "eval_count": 12}), &safety);
+        assert_eq!((ollama.prompt_tokens, ollama.completion_tokens, ollama.cost_usd), (40, 12, 0.0));
+    }
+
+    #[test]
+    fn test_day_totals() {
+        let mut store = Store::new();
+        let usage = Usage { requests: 1, prompt_tokens: 100, completion_tokens: 20, cost_usd: 0.25 };
+        add(&mut store, "2026-01-01", "Groq", &usage);
+        add(&mut store, "2026-01-01", "Groq", &usage);
+        add(&mut store, "2026-01-01", "OpenRouter", &usage);
+        add(&mut store, "2026-01-02", "Groq", &usage);
+        assert_eq!(store["2026-01-01"]["Groq"].requests, 2);
+        assert_eq!(day_total(&store, "2026-01-01"), 0.75);
+        assert_eq!(day_total(&store, "2026-01-03"), 0.0);
+    }
+}
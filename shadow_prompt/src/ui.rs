@@ -1,25 +1,98 @@
 use crate::config::VisualsConfig;
+use crate::utils::{parse_hex_color, McqAnswer, QuestionType};
 use std::sync::mpsc::Receiver;
 use std::thread;
+use std::time::{Duration, Instant};
 use windows::core::w;
 use windows::Win32::Foundation::{COLORREF, HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
 use windows::Win32::Graphics::Gdi::{
     BeginPaint, CreateSolidBrush, DeleteObject, EndPaint, FillRect, InvalidateRect, PAINTSTRUCT,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
-    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetSystemMetrics, PeekMessageW,
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetSystemMetrics, PeekMessageW,
     PostQuitMessage, RegisterClassW, SetLayeredWindowAttributes, SetWindowPos, ShowWindow,
     TranslateMessage, CS_HREDRAW, CS_VREDRAW, HCURSOR, HICON, HMENU, LWA_ALPHA, LWA_COLORKEY, MSG,
     PM_REMOVE, SM_CXSCREEN, SM_CYSCREEN, SWP_NOACTIVATE, SWP_SHOWWINDOW, SW_HIDE, SW_SHOW,
-    WM_DESTROY, WM_ERASEBKGND, WM_PAINT, WNDCLASSW, WS_EX_LAYERED, WS_EX_TOOLWINDOW, WS_EX_TOPMOST,
-    WS_POPUP, WS_VISIBLE,
+    SW_SHOWNOACTIVATE,
+    WM_DESTROY, WM_ERASEBKGND, WM_PAINT, WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE,
+    WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP, WS_VISIBLE,
 };
 
 const HWND_TOPMOST: HWND = HWND(-1_isize as *mut std::ffi::c_void);
 
+/// Named indicator states. Ready/Processing drive the main pixel, the rest the MCQ/TF pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndicatorState {
+    Ready,
+    Processing,
+    McqA,
+    McqB,
+    McqC,
+    McqD,
+    McqNone,
+    True,
+    False,
+    /// Set by the panic hook
+    Crashed,
+}
+
+impl IndicatorState {
+    pub fn from_question_type(question_type: QuestionType) -> Self {
+        match question_type {
+            QuestionType::MultipleChoice(McqAnswer::A) => IndicatorState::McqA,
+            QuestionType::MultipleChoice(McqAnswer::B) => IndicatorState::McqB,
+            QuestionType::MultipleChoice(McqAnswer::C) => IndicatorState::McqC,
+            QuestionType::MultipleChoice(McqAnswer::D) => IndicatorState::McqD,
+            QuestionType::TrueFalse(true) => IndicatorState::True,
+            QuestionType::TrueFalse(false) => IndicatorState::False,
+            QuestionType::Unknown => IndicatorState::McqNone,
+        }
+    }
+
+    /// Key used in `visuals.state_overrides`
+    pub fn key(self) -> &'static str {
+        match self {
+            IndicatorState::Ready => "ready",
+            IndicatorState::Processing => "processing",
+            IndicatorState::McqA => "mcq_a",
+            IndicatorState::McqB => "mcq_b",
+            IndicatorState::McqC => "mcq_c",
+            IndicatorState::McqD => "mcq_d",
+            IndicatorState::McqNone => "mcq_none",
+            IndicatorState::True => "true",
+            IndicatorState::False => "false",
+            IndicatorState::Crashed => "crash",
+        }
+    }
+
+    fn is_secondary(self) -> bool {
+        !matches!(self, IndicatorState::Ready | IndicatorState::Processing | IndicatorState::Crashed)
+    }
+
+    fn color(self, config: &VisualsConfig) -> u32 {
+        let hex = match self {
+            IndicatorState::Ready if crate::spend::warning_reached() => &config.color_spend_warning,
+            IndicatorState::Ready => &config.ready_color,
+            IndicatorState::Processing => &config.color_processing,
+            IndicatorState::McqA => &config.color_mcq_a,
+            IndicatorState::McqB => &config.color_mcq_b,
+            IndicatorState::McqC => &config.color_mcq_c,
+            IndicatorState::McqD => &config.color_mcq_d,
+            IndicatorState::McqNone => &config.color_mcq_none,
+            IndicatorState::True => &config.color_true,
+            IndicatorState::False => &config.color_false,
+            IndicatorState::Crashed => &config.color_crash,
+        };
+        parse_hex_color(hex)
+    }
+}
+
 #[allow(dead_code)]
 pub enum UICommand {
+    /// Ad-hoc main indicator color with the default size/alpha
     SetColor(u32),
+    SetState(IndicatorState),
+    ResetSecondary,
     DrawDebugRect(i32, i32, i32, i32),
     ClearDebugRect,
     #[allow(dead_code)]
@@ -28,18 +101,60 @@ pub enum UICommand {
     SetOverlayText(String),
     ClearOverlayText,
     UpdateOverlayConfig(i32, u8, u8),
+    /// Self-dismissing notification window (`visuals.output_mode = "toast"`)
+    ShowToast(String),
 }
 
 static mut CURRENT_COLOR: u32 = 0x0000FF00;
+static mut SECONDARY_COLOR: u32 = 0x00FFFFFF;
 static mut IS_HIDDEN: bool = false;
 static mut OVERLAY_TEXT: String = String::new();
 static mut OVERLAY_FONT_SIZE: i32 = 16;
 static mut OVERLAY_BG_OPACITY: u8 = 200;
 static mut OVERLAY_TEXT_OPACITY: u8 = 255;
+static mut TOAST_TEXT: String = String::new();
+
+const TOAST_WIDTH: i32 = 360;
+const TOAST_HEIGHT: i32 = 110;
+const TOAST_MARGIN: i32 = 16;
 
 pub struct UIManager;
 
 impl UIManager {
+    /// Create and destroy a hidden indicator-style window to check that overlays work (`--self-test`).
+    pub fn probe_window() -> anyhow::Result<()> {
+        unsafe {
+            let instance = HINSTANCE::default();
+            let class_name = w!("ShadowPromptSelfTest");
+            let wc = WNDCLASSW {
+                lpszClassName: class_name,
+                hInstance: instance,
+                lpfnWndProc: Some(wnd_proc),
+                ..Default::default()
+            };
+            RegisterClassW(&wc);
+
+            let hwnd = CreateWindowExW(
+                WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_LAYERED,
+                class_name,
+                w!(""),
+                WS_POPUP,
+                0,
+                0,
+                1,
+                1,
+                HWND::default(),
+                HMENU::default(),
+                instance,
+                None,
+            )?;
+            let result = SetLayeredWindowAttributes(hwnd, COLORREF(0), 255, LWA_ALPHA);
+            let _ = DestroyWindow(hwnd);
+            result?;
+        }
+        Ok(())
+    }
+
     pub fn start(rx: Receiver<UICommand>, config: VisualsConfig) {
         thread::spawn(move || {
             #[allow(static_mut_refs)]
@@ -60,6 +175,19 @@ impl UIManager {
                 };
                 RegisterClassW(&wc);
 
+                // 1b. Secondary (MCQ/TF) Indicator Window Class
+                let secondary_class_name = w!("ShadowPromptSecondary");
+                let wc_secondary = WNDCLASSW {
+                    hCursor: HCURSOR::default(),
+                    hIcon: HICON::default(),
+                    lpszClassName: secondary_class_name,
+                    hInstance: instance,
+                    lpfnWndProc: Some(secondary_wnd_proc),
+                    style: CS_HREDRAW | CS_VREDRAW,
+                    ..Default::default()
+                };
+                RegisterClassW(&wc_secondary);
+
                 // 2. Debug Overlay Window Class (Black Box)
                 let wc_debug = WNDCLASSW {
                     hCursor: HCURSOR::default(),
@@ -83,6 +211,18 @@ impl UIManager {
                 };
                 RegisterClassW(&wc_overlay);
 
+                // 2c. Toast Window Class
+                let toast_class_name = w!("ShadowPromptToast");
+                let wc_toast = WNDCLASSW {
+                    hCursor: HCURSOR::default(),
+                    hIcon: HICON::default(),
+                    lpszClassName: toast_class_name,
+                    hInstance: instance,
+                    lpfnWndProc: Some(toast_wnd_proc),
+                    ..Default::default()
+                };
+                RegisterClassW(&wc_toast);
+
                 // Calculate Position
                 let screen_w = GetSystemMetrics(SM_CXSCREEN);
                 let screen_h = GetSystemMetrics(SM_CYSCREEN);
@@ -143,10 +283,39 @@ impl UIManager {
                 )
                 .unwrap_or(HWND::default());
 
+                // Create Indicator 2 (MCQ/TF) next to the main one, towards the screen center.
+                // Hidden until the first answer arrives.
+                let secondary_x = if config.position.ends_with("left") {
+                    x + size + 1
+                } else {
+                    x - size - 1
+                };
+                let hwnd_secondary = CreateWindowExW(
+                    WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_LAYERED,
+                    secondary_class_name,
+                    w!(""),
+                    WS_POPUP,
+                    secondary_x,
+                    y,
+                    size,
+                    size,
+                    HWND::default(),
+                    HMENU::default(),
+                    instance,
+                    None,
+                )
+                .unwrap_or(HWND::default());
+
+                let hold = Duration::from_secs(config.secondary_indicator_hold_secs);
+                let mut secondary_shown = false;
+                let mut secondary_reset_at: Option<Instant> = None;
+                SECONDARY_COLOR = IndicatorState::McqNone.color(&config);
+                apply_state_style(hwnd_secondary, &config, IndicatorState::McqNone.key(), secondary_x, y);
 
                 // Create Debug Window (Hidden initially)
+                // Click-through so the selection clicks still reach the app underneath
                 let hwnd_debug = CreateWindowExW(
-                    WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_LAYERED,
+                    WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_NOACTIVATE,
                     debug_class_name,
                     w!("DebugOverlay"),
                     WS_POPUP, // Not visible initially
@@ -200,6 +369,25 @@ impl UIManager {
                 )
                 .unwrap_or(HWND::default());
 
+                // Create Toast Window (Hidden initially), bottom-right above the taskbar
+                let hwnd_toast = CreateWindowExW(
+                    WS_EX_TOPMOST | WS_EX_LAYERED | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+                    toast_class_name,
+                    w!("Toast"),
+                    WS_POPUP,
+                    screen_w - TOAST_WIDTH - TOAST_MARGIN,
+                    screen_h - TOAST_HEIGHT - TOAST_MARGIN * 4,
+                    TOAST_WIDTH,
+                    TOAST_HEIGHT,
+                    HWND::default(),
+                    HMENU::default(),
+                    instance,
+                    None,
+                )
+                .unwrap_or(HWND::default());
+                let toast_duration = Duration::from_secs(config.toast_duration_secs.max(1));
+                let mut toast_hide_at: Option<Instant> = None;
+
                 if hwnd.0.is_null() {
                     return;
                 }
@@ -209,12 +397,15 @@ impl UIManager {
                 // Opacity for Indicator 1
                 let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), 255, LWA_ALPHA);
 
-                // Opacity for Debug (50%)
-                let _ = SetLayeredWindowAttributes(hwnd_debug, COLORREF(0), 128, LWA_ALPHA);
+                // Opacity for Debug (~31%)
+                // Translucent enough to still read the text being selected
+                let _ = SetLayeredWindowAttributes(hwnd_debug, COLORREF(0), 80, LWA_ALPHA);
 
                 // Opacity for Text Overlay - use color key for transparent background
                 let _ = SetLayeredWindowAttributes(hwnd_overlay, COLORREF(0), 255, LWA_COLORKEY);
 
+                let _ = SetLayeredWindowAttributes(hwnd_toast, COLORREF(0), 230, LWA_ALPHA);
+
                 // Loop
                 loop {
                     let mut msg = MSG::default();
@@ -226,12 +417,57 @@ impl UIManager {
                         DispatchMessageW(&msg);
                     }
 
-                    if let Ok(cmd) = rx.try_recv() {
+                    // Toast auto-hide
+                    if let Some(hide_at) = toast_hide_at {
+                        if Instant::now() >= hide_at {
+                            let _ = ShowWindow(hwnd_toast, SW_HIDE);
+                            toast_hide_at = None;
+                        }
+                    }
+
+                    // Secondary indicator hold timeout
+                    if let Some(reset_at) = secondary_reset_at {
+                        if Instant::now() >= reset_at {
+                            SECONDARY_COLOR = IndicatorState::McqNone.color(&config);
+                            apply_state_style(hwnd_secondary, &config, IndicatorState::McqNone.key(), secondary_x, y);
+                            let _ = InvalidateRect(hwnd_secondary, None, false);
+                            secondary_reset_at = None;
+                        }
+                    }
+
+                    // Drain everything queued this tick; the hook sends a debug rect every 16 ms
+                    while let Ok(cmd) = rx.try_recv() {
                         match cmd {
                             UICommand::SetColor(c) => {
                                 CURRENT_COLOR = c;
+                                apply_state_style(hwnd, &config, "", x, y);
                                 let _ = InvalidateRect(hwnd, None, false);
                             }
+                            UICommand::SetState(state) if !state.is_secondary() => {
+                                CURRENT_COLOR = state.color(&config);
+                                apply_state_style(hwnd, &config, state.key(), x, y);
+                                let _ = InvalidateRect(hwnd, None, false);
+                            }
+                            UICommand::SetState(state) => {
+                                SECONDARY_COLOR = state.color(&config);
+                                apply_state_style(hwnd_secondary, &config, state.key(), secondary_x, y);
+                                secondary_shown = true;
+                                if !IS_HIDDEN {
+                                    let _ = ShowWindow(hwnd_secondary, SW_SHOW);
+                                }
+                                let _ = InvalidateRect(hwnd_secondary, None, false);
+                                secondary_reset_at = if hold.is_zero() {
+                                    None
+                                } else {
+                                    Some(Instant::now() + hold)
+                                };
+                            }
+                            UICommand::ResetSecondary => {
+                                SECONDARY_COLOR = IndicatorState::McqNone.color(&config);
+                                apply_state_style(hwnd_secondary, &config, IndicatorState::McqNone.key(), secondary_x, y);
+                                secondary_reset_at = None;
+                                let _ = InvalidateRect(hwnd_secondary, None, false);
+                            }
                             UICommand::DrawDebugRect(x, y, w, h) => {
                                 let _ = ShowWindow(hwnd_debug, SW_SHOW);
                                 let _ = SetWindowPos(
@@ -254,9 +490,15 @@ impl UIManager {
                                 IS_HIDDEN = !IS_HIDDEN;
                                 if IS_HIDDEN {
                                     let _ = ShowWindow(hwnd, SW_HIDE);
+                                    let _ = ShowWindow(hwnd_secondary, SW_HIDE);
                                     let _ = ShowWindow(hwnd_overlay, SW_HIDE);
+                                    let _ = ShowWindow(hwnd_toast, SW_HIDE);
+                                    toast_hide_at = None;
                                 } else {
                                     let _ = ShowWindow(hwnd, SW_SHOW);
+                                    if secondary_shown {
+                                        let _ = ShowWindow(hwnd_secondary, SW_SHOW);
+                                    }
                                 }
                             }
                             UICommand::SetOverlayText(text) => {
@@ -274,15 +516,35 @@ impl UIManager {
                                 OVERLAY_TEXT_OPACITY = text_opacity;
                                 let _ = InvalidateRect(hwnd_overlay, None, false);
                             }
+                            UICommand::ShowToast(text) => {
+                                TOAST_TEXT = text;
+                                if !IS_HIDDEN {
+                                    let _ = ShowWindow(hwnd_toast, SW_SHOWNOACTIVATE);
+                                }
+                                let _ = InvalidateRect(hwnd_toast, None, true);
+                                toast_hide_at = Some(Instant::now() + toast_duration);
+                            }
                         }
                     }
-                    thread::sleep(std::time::Duration::from_millis(16));
+                    thread::sleep(Duration::from_millis(16));
                 }
             }
         });
     }
 }
 
+/// Resize and fade an indicator for a state, keeping the edges that face the screen corner fixed.
+/// `x`/`y` are the window origin at the base `visuals.size`.
+unsafe fn apply_state_style(hwnd: HWND, config: &VisualsConfig, state_key: &str, x: i32, y: i32) {
+    let (size, alpha) = config.state_style(state_key);
+    let delta = config.size - size;
+    let new_x = if config.position.ends_with("left") { x } else { x + delta };
+    let new_y = if config.position.starts_with("bottom") { y + delta } else { y };
+
+    let _ = SetWindowPos(hwnd, HWND_TOPMOST, new_x, new_y, size, size, SWP_NOACTIVATE);
+    let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA);
+}
+
 unsafe extern "system" fn wnd_proc(
     hwnd: HWND,
     msg: u32,
@@ -308,6 +570,26 @@ unsafe extern "system" fn wnd_proc(
     }
 }
 
+unsafe extern "system" fn secondary_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let brush = CreateSolidBrush(COLORREF(SECONDARY_COLOR));
+            FillRect(hdc, &ps.rcPaint, brush);
+            let _ = DeleteObject(brush);
+            let _ = EndPaint(hwnd, &ps);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
 unsafe extern "system" fn debug_wnd_proc(
     hwnd: HWND,
     msg: u32,
@@ -426,3 +708,54 @@ unsafe extern "system" fn overlay_wnd_proc(
         _ => DefWindowProcW(hwnd, msg, wparam, lparam),
     }
 }
+
+#[allow(static_mut_refs)]
+unsafe extern "system" fn toast_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_PAINT => {
+            use windows::Win32::Foundation::RECT;
+            use windows::Win32::Graphics::Gdi::{
+                CreateFontW, DrawTextW, SelectObject, SetBkMode, SetTextColor, DT_END_ELLIPSIS,
+                DT_LEFT, DT_WORDBREAK, TRANSPARENT,
+            };
+
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+
+            // Dark grey card (BGR)
+            let brush = CreateSolidBrush(COLORREF(0x00302820));
+            let mut rect = RECT {
+                left: 0,
+                top: 0,
+                right: TOAST_WIDTH,
+                bottom: TOAST_HEIGHT,
+            };
+            FillRect(hdc, &rect, brush);
+            let _ = DeleteObject(brush);
+
+            let font = CreateFontW(18, 0, 0, 0, 400, 0, 0, 0, 0, 0, 0, 0, 0, w!("Segoe UI"));
+            let old_font = SelectObject(hdc, font);
+            let _ = SetBkMode(hdc, TRANSPARENT);
+            let _ = SetTextColor(hdc, COLORREF(0x00FFFFFF));
+
+            rect.left += 12;
+            rect.top += 10;
+            rect.right -= 12;
+            rect.bottom -= 10;
+            let mut text: Vec<u16> = TOAST_TEXT.encode_utf16().collect();
+            let _ = DrawTextW(hdc, &mut text, &mut rect, DT_LEFT | DT_WORDBREAK | DT_END_ELLIPSIS);
+
+            SelectObject(hdc, old_font);
+            let _ = DeleteObject(font);
+            let _ = EndPaint(hwnd, &ps);
+            LRESULT(0)
+        }
+        WM_ERASEBKGND => LRESULT(1),
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
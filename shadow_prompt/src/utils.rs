@@ -1,85 +1,306 @@
 
-use rdev::Key;
+use rdev::{Button, Key};
 
+// Mouse buttons are bound as pseudo-keys carrying their Windows virtual-key codes,
+// which never arrive from a keyboard, so combos can mix keys and buttons.
+pub const MOUSE_MIDDLE: Key = Key::Unknown(0x04); // VK_MBUTTON
+pub const MOUSE_X1: Key = Key::Unknown(0x05); // VK_XBUTTON1
+pub const MOUSE_X2: Key = Key::Unknown(0x06); // VK_XBUTTON2
+
+/// Pseudo-key for a bindable mouse button; left/right are never bindable
+pub fn mouse_button_key(button: Button) -> Option<Key> {
+    match button {
+        Button::Middle => Some(MOUSE_MIDDLE),
+        Button::Unknown(1) => Some(MOUSE_X1),
+        Button::Unknown(2) => Some(MOUSE_X2),
+        _ => None,
+    }
+}
+
+/// "Double-tap Ctrl" / "DoubleTap+Shift" -> the single key to tap twice
+pub fn parse_double_tap(config_str: &str) -> Option<Key> {
+    let lower = config_str.trim().to_lowercase();
+    let rest = ["double-tap", "doubletap", "double tap"]
+        .iter()
+        .find_map(|prefix| lower.strip_prefix(prefix))?;
+    let key_name = rest.trim_start_matches(|c: char| c == '+' || c.is_whitespace());
+
+    match parse_keys(key_name).as_slice() {
+        [key] => Some(*key),
+        _ => None,
+    }
+}
+
+// Plain "Ctrl"/"Shift"/"Alt"/"Win" parse to the left key and accept either side;
+// these pseudo-keys (left-side virtual-key codes) bind the left key only.
+pub const LEFT_CTRL: Key = Key::Unknown(0xA2); // VK_LCONTROL
+pub const LEFT_SHIFT: Key = Key::Unknown(0xA0); // VK_LSHIFT
+pub const LEFT_ALT: Key = Key::Unknown(0xA4); // VK_LMENU
+pub const LEFT_META: Key = Key::Unknown(0x5B); // VK_LWIN
+
+/// Whether a physical key press satisfies a key from `parse_keys`
+pub fn key_matches(target: Key, actual: Key) -> bool {
+    match target {
+        Key::ControlLeft => matches!(actual, Key::ControlLeft | Key::ControlRight),
+        Key::ShiftLeft => matches!(actual, Key::ShiftLeft | Key::ShiftRight),
+        Key::Alt => matches!(actual, Key::Alt | Key::AltGr),
+        Key::MetaLeft => matches!(actual, Key::MetaLeft | Key::MetaRight),
+        LEFT_CTRL => actual == Key::ControlLeft,
+        LEFT_SHIFT => actual == Key::ShiftLeft,
+        LEFT_ALT => actual == Key::Alt,
+        LEFT_META => actual == Key::MetaLeft,
+        _ => target == actual,
+    }
+}
+
+/// One key name (case-insensitive) from a binding string
+pub fn parse_key(name: &str) -> Option<Key> {
+    let key = match name.trim().to_lowercase().as_str() {
+        // Modifiers
+        "ctrl" | "control" => Key::ControlLeft,
+        "shift" => Key::ShiftLeft,
+        "alt" => Key::Alt,
+        "meta" | "win" | "super" => Key::MetaLeft,
+        "lctrl" | "leftctrl" | "lcontrol" | "leftcontrol" => LEFT_CTRL,
+        "rctrl" | "rightctrl" | "rcontrol" | "rightcontrol" => Key::ControlRight,
+        "lshift" | "leftshift" => LEFT_SHIFT,
+        "rshift" | "rightshift" => Key::ShiftRight,
+        "lalt" | "leftalt" => LEFT_ALT,
+        "ralt" | "rightalt" | "altgr" => Key::AltGr,
+        "lwin" | "leftwin" | "lmeta" => LEFT_META,
+        "rwin" | "rightwin" | "rmeta" => Key::MetaRight,
+
+        // Functional
+        "space" => Key::Space,
+        "enter" | "return" => Key::Return,
+        "esc" | "escape" => Key::Escape,
+        "tab" => Key::Tab,
+        "backspace" => Key::Backspace,
+        "capslock" => Key::CapsLock,
+        "printscreen" | "prtsc" | "prtscn" | "print" => Key::PrintScreen,
+        "scrolllock" => Key::ScrollLock,
+        "pause" | "break" => Key::Pause,
+        "numlock" => Key::NumLock,
+
+        // Navigation
+        "insert" | "ins" => Key::Insert,
+        "delete" | "del" => Key::Delete,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" | "pgup" => Key::PageUp,
+        "pagedown" | "pgdn" => Key::PageDown,
+        "up" | "arrowup" | "uparrow" => Key::UpArrow,
+        "down" | "arrowdown" | "downarrow" => Key::DownArrow,
+        "left" | "arrowleft" | "leftarrow" => Key::LeftArrow,
+        "right" | "arrowright" | "rightarrow" => Key::RightArrow,
+
+        // Punctuation ('+' itself is the separator, so it is spelled out)
+        "-" | "minus" => Key::Minus,
+        "=" | "equal" | "equals" | "plus" => Key::Equal,
+        "[" | "openbracket" | "leftbracket" => Key::LeftBracket,
+        "]" | "closebracket" | "rightbracket" => Key::RightBracket,
+        ";" | "semicolon" => Key::SemiColon,
+        "'" | "quote" | "apostrophe" => Key::Quote,
+        "`" | "backtick" | "backquote" | "grave" => Key::BackQuote,
+        "\\" | "backslash" => Key::BackSlash,
+        "," | "comma" => Key::Comma,
+        "." | "period" | "dot" => Key::Dot,
+        "/" | "slash" => Key::Slash,
+
+        // Mouse Buttons
+        "middle" | "mouse3" | "mmb" => MOUSE_MIDDLE,
+        "mouse4" | "xbutton1" => MOUSE_X1,
+        "mouse5" | "xbutton2" => MOUSE_X2,
+
+        // F-Keys
+        "f1" => Key::F1,
+        "f2" => Key::F2,
+        "f3" => Key::F3,
+        "f4" => Key::F4,
+        "f5" => Key::F5,
+        "f6" => Key::F6,
+        "f7" => Key::F7,
+        "f8" => Key::F8,
+        "f9" => Key::F9,
+        "f10" => Key::F10,
+        "f11" => Key::F11,
+        "f12" => Key::F12,
+
+        // Letters
+        "a" => Key::KeyA,
+        "b" => Key::KeyB,
+        "c" => Key::KeyC,
+        "d" => Key::KeyD,
+        "e" => Key::KeyE,
+        "f" => Key::KeyF,
+        "g" => Key::KeyG,
+        "h" => Key::KeyH,
+        "i" => Key::KeyI,
+        "j" => Key::KeyJ,
+        "k" => Key::KeyK,
+        "l" => Key::KeyL,
+        "m" => Key::KeyM,
+        "n" => Key::KeyN,
+        "o" => Key::KeyO,
+        "p" => Key::KeyP,
+        "q" => Key::KeyQ,
+        "r" => Key::KeyR,
+        "s" => Key::KeyS,
+        "t" => Key::KeyT,
+        "u" => Key::KeyU,
+        "v" => Key::KeyV,
+        "w" => Key::KeyW,
+        "x" => Key::KeyX,
+        "y" => Key::KeyY,
+        "z" => Key::KeyZ,
+
+        // Numbers
+        "0" => Key::Num0,
+        "1" => Key::Num1,
+        "2" => Key::Num2,
+        "3" => Key::Num3,
+        "4" => Key::Num4,
+        "5" => Key::Num5,
+        "6" => Key::Num6,
+        "7" => Key::Num7,
+        "8" => Key::Num8,
+        "9" => Key::Num9,
+
+        // Numpad
+        "numpad0" | "kp0" => Key::Kp0,
+        "numpad1" | "kp1" => Key::Kp1,
+        "numpad2" | "kp2" => Key::Kp2,
+        "numpad3" | "kp3" => Key::Kp3,
+        "numpad4" | "kp4" => Key::Kp4,
+        "numpad5" | "kp5" => Key::Kp5,
+        "numpad6" | "kp6" => Key::Kp6,
+        "numpad7" | "kp7" => Key::Kp7,
+        "numpad8" | "kp8" => Key::Kp8,
+        "numpad9" | "kp9" => Key::Kp9,
+        "numpadplus" | "numpadadd" | "kpplus" => Key::KpPlus,
+        "numpadminus" | "numpadsubtract" | "kpminus" => Key::KpMinus,
+        "numpadmultiply" | "numpad*" | "kpmultiply" => Key::KpMultiply,
+        "numpaddivide" | "numpad/" | "kpdivide" => Key::KpDivide,
+        "numpaddecimal" | "numpad." | "kpdelete" => Key::KpDelete,
+
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Parse a "Ctrl+Shift+X" binding; unknown parts are skipped (see `unknown_keys`)
 pub fn parse_keys(config_str: &str) -> Vec<Key> {
-    let mut keys = Vec::new();
-    for part in config_str.split('+') {
-        let trimmed = part.trim().to_lowercase();
-        match trimmed.as_str() {
-            // Modifiers
-            "ctrl" | "control" => keys.push(Key::ControlLeft), // Simplifying to Left for now
-            "shift" => keys.push(Key::ShiftLeft),
-            "alt" => keys.push(Key::Alt),
-            "meta" | "win" | "super" => keys.push(Key::MetaLeft),
-
-            // Functional
-            "space" => keys.push(Key::Space),
-            "enter" | "return" => keys.push(Key::Return),
-            "esc" | "escape" => keys.push(Key::Escape),
-            "tab" => keys.push(Key::Tab),
-            "backspace" => keys.push(Key::Backspace),
-            "capslock" => keys.push(Key::CapsLock),
-
-            // F-Keys
-            "f1" => keys.push(Key::F1),
-            "f2" => keys.push(Key::F2),
-            "f3" => keys.push(Key::F3),
-            "f4" => keys.push(Key::F4),
-            "f5" => keys.push(Key::F5),
-            "f6" => keys.push(Key::F6),
-            "f7" => keys.push(Key::F7),
-            "f8" => keys.push(Key::F8),
-            "f9" => keys.push(Key::F9),
-            "f10" => keys.push(Key::F10),
-            "f11" => keys.push(Key::F11),
-            "f12" => keys.push(Key::F12),
-
-            // Letters
-            "a" => keys.push(Key::KeyA),
-            "b" => keys.push(Key::KeyB),
-            "c" => keys.push(Key::KeyC),
-            "d" => keys.push(Key::KeyD),
-            "e" => keys.push(Key::KeyE),
-            "f" => keys.push(Key::KeyF),
-            "g" => keys.push(Key::KeyG),
-            "h" => keys.push(Key::KeyH),
-            "i" => keys.push(Key::KeyI),
-            "j" => keys.push(Key::KeyJ),
-            "k" => keys.push(Key::KeyK),
-            "l" => keys.push(Key::KeyL),
-            "m" => keys.push(Key::KeyM),
-            "n" => keys.push(Key::KeyN),
-            "o" => keys.push(Key::KeyO),
-            "p" => keys.push(Key::KeyP),
-            "q" => keys.push(Key::KeyQ),
-            "r" => keys.push(Key::KeyR),
-            "s" => keys.push(Key::KeyS),
-            "t" => keys.push(Key::KeyT),
-            "u" => keys.push(Key::KeyU),
-            "v" => keys.push(Key::KeyV),
-            "w" => keys.push(Key::KeyW),
-            "x" => keys.push(Key::KeyX),
-            "y" => keys.push(Key::KeyY),
-            "z" => keys.push(Key::KeyZ),
-
-            // Numbers
-            "0" => keys.push(Key::Num0),
-            "1" => keys.push(Key::Num1),
-            "2" => keys.push(Key::Num2),
-            "3" => keys.push(Key::Num3),
-            "4" => keys.push(Key::Num4),
-            "5" => keys.push(Key::Num5),
-            "6" => keys.push(Key::Num6),
-            "7" => keys.push(Key::Num7),
-            "8" => keys.push(Key::Num8),
-            "9" => keys.push(Key::Num9),
-
-            _ => {
-                eprintln!("Warning: Unknown key in config: {}", part);
+    config_str.split('+').filter_map(parse_key).collect()
+}
+
+/// Parts of a binding string that `parse_keys` would drop
+pub fn unknown_keys(config_str: &str) -> Vec<String> {
+    config_str
+        .split('+')
+        .map(str::trim)
+        .filter(|part| !part.is_empty() && parse_key(part).is_none())
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum McqAnswer {
+    A,
+    B,
+    C,
+    D,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum QuestionType {
+    MultipleChoice(McqAnswer),
+    TrueFalse(bool),
+    Unknown,
+}
+
+/// Classify an LLM answer written in the system prompt's format ("A) 4", "True", ...).
+/// Leading `[System Warning: ...]` lines are ignored; only the first answer line counts.
+pub fn parse_question_type(output: &str) -> QuestionType {
+    let first_line = output
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("[System Warning"));
+
+    let line = match first_line {
+        Some(l) => l,
+        None => return QuestionType::Unknown,
+    };
+
+    let lower = line.trim_end_matches('.').to_lowercase();
+    if lower == "true" || lower == "t" {
+        return QuestionType::TrueFalse(true);
+    }
+    if lower == "false" || lower == "f" {
+        return QuestionType::TrueFalse(false);
+    }
+
+    let mut chars = line.chars();
+    let first = chars.next();
+    let second = chars.next();
+    // A bare letter counts, but a bare digit is more likely a numeric answer than option 4
+    let is_delimited = matches!(second, Some(')') | Some('.') | Some(':'))
+        || (second.is_none() && first.is_some_and(|c| c.is_ascii_alphabetic()));
+    if !is_delimited {
+        return QuestionType::Unknown;
+    }
+
+    match first.map(|c| c.to_ascii_uppercase()) {
+        Some('A') | Some('1') => QuestionType::MultipleChoice(McqAnswer::A),
+        Some('B') | Some('2') => QuestionType::MultipleChoice(McqAnswer::B),
+        Some('C') | Some('3') => QuestionType::MultipleChoice(McqAnswer::C),
+        Some('D') | Some('4') => QuestionType::MultipleChoice(McqAnswer::D),
+        _ => QuestionType::Unknown,
+    }
+}
+
+fn looks_like_question(block: &str) -> bool {
+    let lower = block.trim_start().to_lowercase();
+    block.contains('?')
+        || lower.starts_with("question")
+        || block.lines().any(|line| {
+            let mut chars = line.trim_start().chars();
+            matches!(
+                (chars.next(), chars.next()),
+                (Some('A'..='D' | 'a'..='d'), Some(')' | '.'))
+            )
+        })
+}
+
+/// Shrink an oversized input to `max_chars` (0 = unlimited). Keeps the last
+/// question-looking block and what follows it (options), plus as much of the
+/// preceding context as fits; without one, keeps the tail. None if it already fits.
+pub fn truncate_input(text: &str, max_chars: usize) -> Option<String> {
+    if max_chars == 0 || text.chars().count() <= max_chars {
+        return None;
+    }
+
+    let normalized = text.replace("\r\n", "\n");
+    let blocks: Vec<&str> = normalized.split("\n\n").map(str::trim).filter(|b| !b.is_empty()).collect();
+
+    let kept = match blocks.iter().rposition(|b| looks_like_question(b)) {
+        Some(start) => {
+            let mut kept = blocks[start..].join("\n\n");
+            for block in blocks[..start].iter().rev() {
+                let candidate = format!("{}\n\n{}", block, kept);
+                if candidate.chars().count() > max_chars {
+                    break;
+                }
+                kept = candidate;
             }
+            // Question first: if it alone is too long, cut its end
+            kept.chars().take(max_chars).collect()
         }
-    }
-    keys
+        None => {
+            let skip = normalized.chars().count().saturating_sub(max_chars);
+            normalized.chars().skip(skip).collect()
+        }
+    };
+    Some(kept)
 }
 
 pub fn parse_hex_color(hex: &str) -> u32 {
@@ -109,6 +330,24 @@ mod tests {
         assert_eq!(parse_hex_color("ZZZ"), 0x00000000);
     }
 
+    #[test]
+    fn test_question_type_parsing() {
+        assert_eq!(parse_question_type("A) 4"), QuestionType::MultipleChoice(McqAnswer::A));
+        assert_eq!(parse_question_type("A) Earth, B) Mars"), QuestionType::MultipleChoice(McqAnswer::A));
+        assert_eq!(parse_question_type("d. Rome"), QuestionType::MultipleChoice(McqAnswer::D));
+        assert_eq!(parse_question_type("True"), QuestionType::TrueFalse(true));
+        assert_eq!(parse_question_type("false."), QuestionType::TrueFalse(false));
+        assert_eq!(
+            parse_question_type("[System Warning: Search failed]\n\nC) Paris"),
+            QuestionType::MultipleChoice(McqAnswer::C)
+        );
+        assert_eq!(parse_question_type("2) Paris"), QuestionType::MultipleChoice(McqAnswer::B));
+        assert_eq!(parse_question_type("4"), QuestionType::Unknown);
+        assert_eq!(parse_question_type("Paris"), QuestionType::Unknown);
+        assert_eq!(parse_question_type("A function that adds"), QuestionType::Unknown);
+        assert_eq!(parse_question_type(""), QuestionType::Unknown);
+    }
+
     #[test]
     fn test_key_parsing() {
         use rdev::Key;
@@ -127,4 +366,78 @@ mod tests {
         let keys = crate::utils::parse_keys("  Alt +  Tab ");
         assert_eq!(keys, vec![Key::Alt, Key::Tab]);
     }
+
+    #[test]
+    fn test_extended_key_parsing() {
+        use crate::utils::{parse_keys, unknown_keys, LEFT_CTRL};
+        use rdev::Key;
+
+        assert_eq!(parse_keys("Ctrl+["), vec![Key::ControlLeft, Key::LeftBracket]);
+        assert_eq!(parse_keys("Shift+;"), vec![Key::ShiftLeft, Key::SemiColon]);
+        assert_eq!(parse_keys("Alt+Up"), vec![Key::Alt, Key::UpArrow]);
+        assert_eq!(parse_keys("Numpad7"), vec![Key::Kp7]);
+        assert_eq!(parse_keys("RightCtrl+PrintScreen"), vec![Key::ControlRight, Key::PrintScreen]);
+        assert_eq!(parse_keys("LCtrl+PageDown"), vec![LEFT_CTRL, Key::PageDown]);
+
+        assert!(unknown_keys("Ctrl+Shift+Space").is_empty());
+        assert_eq!(unknown_keys("Ctrl+Hyper+Foo"), vec!["Hyper", "Foo"]);
+    }
+
+    #[test]
+    fn test_modifier_sides() {
+        use crate::utils::{key_matches, LEFT_CTRL};
+        use rdev::Key;
+
+        assert!(key_matches(Key::ControlLeft, Key::ControlRight));
+        assert!(key_matches(Key::ControlLeft, Key::ControlLeft));
+        assert!(key_matches(LEFT_CTRL, Key::ControlLeft));
+        assert!(!key_matches(LEFT_CTRL, Key::ControlRight));
+        assert!(!key_matches(Key::ControlRight, Key::ControlLeft));
+        assert!(key_matches(Key::Alt, Key::AltGr));
+    }
+
+    #[test]
+    fn test_input_truncation() {
+        use crate::utils::truncate_input;
+
+        assert_eq!(truncate_input("short question?", 100), None);
+        assert_eq!(truncate_input("anything", 0), None);
+
+        let filler = "Lorem ipsum dolor sit amet. ".repeat(50);
+        let text = format!("{}\n\nWhich gas do plants absorb?\nA) Oxygen\nB) Carbon dioxide\n\n{}", filler, "Page 12");
+        let kept = truncate_input(&text, 120).unwrap();
+        assert!(kept.starts_with("Which gas do plants absorb?"));
+        assert!(kept.contains("B) Carbon dioxide"));
+        assert!(kept.chars().count() <= 120);
+
+        // No question: keep the tail
+        let kept = truncate_input(&format!("{}END", filler), 10).unwrap();
+        assert!(kept.ends_with("END"));
+        assert_eq!(kept.chars().count(), 10);
+    }
+
+    #[test]
+    fn test_double_tap_parsing() {
+        use crate::utils::parse_double_tap;
+        use rdev::Key;
+
+        assert_eq!(parse_double_tap("Double-tap Ctrl"), Some(Key::ControlLeft));
+        assert_eq!(parse_double_tap("doubletap+shift"), Some(Key::ShiftLeft));
+        assert_eq!(parse_double_tap("Ctrl+Shift+Space"), None);
+        assert_eq!(parse_double_tap("Double-tap Ctrl+Shift"), None);
+    }
+
+    #[test]
+    fn test_mouse_button_parsing() {
+        use crate::utils::{mouse_button_key, MOUSE_MIDDLE, MOUSE_X1, MOUSE_X2};
+        use rdev::{Button, Key};
+
+        assert_eq!(crate::utils::parse_keys("Mouse4"), vec![MOUSE_X1]);
+        assert_eq!(crate::utils::parse_keys("Ctrl+Mouse5"), vec![Key::ControlLeft, MOUSE_X2]);
+        assert_eq!(crate::utils::parse_keys("Middle"), vec![MOUSE_MIDDLE]);
+
+        assert_eq!(mouse_button_key(Button::Unknown(1)), Some(MOUSE_X1));
+        assert_eq!(mouse_button_key(Button::Middle), Some(MOUSE_MIDDLE));
+        assert_eq!(mouse_button_key(Button::Left), None);
+    }
 }